@@ -1,10 +1,23 @@
 use clap::{Parser, Subcommand};
+use crate::models::{parse_priority, TaskListFormat, PRIORITY_NAMES};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
+    /// Draw the TUI in the normal screen so its last frame stays in scrollback
+    #[arg(long = "no-alt-screen")]
+    pub no_alt_screen: bool,
+    /// Show similarity scores when asking to confirm a fuzzy match
+    #[arg(short = 'v', long, global = true)]
+    pub verbose: bool,
+    /// Only print command output and errors, not status messages (errors go to stderr)
+    #[arg(short = 'q', long, global = true)]
+    pub quiet: bool,
+    /// Print how long the command took, and the time spent on the name indexes, to stderr
+    #[arg(long, global = true)]
+    pub timing: bool,
 }
 
 #[derive(Subcommand)]
@@ -23,6 +36,22 @@ pub enum Commands {
         /// Read content from file path instead of using data argument
         #[arg(short = 'p', long = "path")]
         path: Option<String>,
+        /// Run a shell command and store the command with its output
+        #[arg(long = "run", value_name = "COMMAND")]
+        run: Option<String>,
+        /// Don't ask for confirmation before running the --run command
+        #[arg(short = 'y', long = "yes", requires = "run")]
+        yes: bool,
+        /// Use the system clipboard's text as the content
+        #[arg(long)]
+        clipboard: bool,
+    },
+
+    /// Append a note to the inbox aide (config: inbox_aide), creating it if needed.
+    /// `aide "some text"` does the same.
+    Capture {
+        #[arg(value_name = "TEXT", required = true, num_args = 1..)]
+        text: Vec<String>,
     },
 
     /// Set a configuration value
@@ -40,6 +69,15 @@ pub enum Commands {
         key: String,
     },
 
+    /// Print every config key starting with a prefix, e.g. `alias.` or `theme_`
+    ConfigGetAll {
+        #[arg(value_name = "PREFIX")]
+        prefix: String,
+        /// Print the keys as a JSON object instead of key=value lines
+        #[arg(long)]
+        json: bool,
+    },
+
     /// List all configuration keys and values
     ConfigList,
 
@@ -49,10 +87,33 @@ pub enum Commands {
         key: String,
     },
 
+    /// Rename a configuration key, keeping its value and creation time
+    ConfigRename {
+        #[arg(value_name = "OLD_KEY")]
+        old_key: String,
+        #[arg(value_name = "NEW_KEY")]
+        new_key: String,
+    },
+
     /// Search for data by input text
     Search {
         #[arg(value_name = "INPUT_TEXT")]
         input_text: String,
+        /// Print how many entries matched instead of the best match
+        #[arg(long)]
+        count: bool,
+        /// List the K best matches with their scores
+        #[arg(long, value_name = "K")]
+        top: Option<usize>,
+        /// Only search the entries of this aide
+        #[arg(long, value_name = "NAME")]
+        aide: Option<String>,
+        /// Ignore matches scoring below this fuzzy score
+        #[arg(long = "min-score", value_name = "SCORE", default_value_t = 0)]
+        min_score: i64,
+        /// Treat INPUT_TEXT as a regular expression and list every exact match
+        #[arg(long, conflicts_with = "min_score")]
+        regex: bool,
     },
 
 
@@ -60,6 +121,9 @@ pub enum Commands {
     Task {
         #[arg(value_name = "TASK_NAME")]
         task_name: String,
+        /// Open with this editor instead of the configured one
+        #[arg(long, value_name = "CMD")]
+        editor: Option<String>,
     },
     /// Change task status
     TaskStatus {
@@ -68,19 +132,157 @@ pub enum Commands {
         #[arg(value_name = "STATUS")]
         status: String,
     },
-    /// Change task priority
+    /// Change the status of every task matching the filters at once
+    TaskStatusAll {
+        /// Only tasks with this status
+        #[arg(long, value_name = "STATUS")]
+        from: Option<String>,
+        /// The new status
+        #[arg(long, value_name = "STATUS")]
+        to: String,
+        /// Only tasks with this priority (1-5 or a name)
+        #[arg(long, value_name = "PRIORITY", value_parser = parse_priority_arg)]
+        priority: Option<u8>,
+        /// Skip the confirmation prompt
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+    },
+    /// Change task priority: 1-5 or highest, high, medium, low, lowest
     TaskPriority {
         #[arg(value_name = "TASK_NAME")]
         task_name: String,
-        #[arg(value_name = "PRIORITY")]
+        #[arg(value_name = "PRIORITY", value_parser = parse_priority_arg)]
         priority: u8,
     },
     /// List all tasks
-    TaskList,
+    TaskList {
+        /// Maximum number of tasks to show
+        #[arg(long, value_name = "N")]
+        limit: Option<u32>,
+        /// Number of tasks to skip before listing
+        #[arg(long, value_name = "M", default_value_t = 0)]
+        offset: u32,
+        /// Output layout
+        #[arg(long, value_enum, default_value_t = TaskListFormat::Table)]
+        format: TaskListFormat,
+        /// Only tasks created today (UTC)
+        #[arg(long, conflicts_with = "week")]
+        today: bool,
+        /// Only tasks created this week, since Monday (UTC)
+        #[arg(long)]
+        week: bool,
+        /// Redraw the list every few seconds until q or Ctrl+C
+        #[arg(long)]
+        watch: bool,
+        /// Seconds between redraws with --watch
+        #[arg(long, value_name = "SECS", default_value_t = 5, requires = "watch",
+              value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+    /// Set how many minutes a task is expected to take
+    TaskEstimate {
+        #[arg(value_name = "TASK_NAME")]
+        task_name: String,
+        #[arg(value_name = "MINUTES")]
+        minutes: u32,
+    },
+    /// Log time spent on a task, e.g. `aide task-time auth +30` (a negative value corrects it)
+    TaskTime {
+        #[arg(value_name = "TASK_NAME")]
+        task_name: String,
+        #[arg(value_name = "MINUTES", allow_negative_numbers = true)]
+        minutes: i64,
+    },
+    /// Compare estimated and actual time for tasks that have either
+    TaskStats {
+        /// Redraw the stats every few seconds until q or Ctrl+C
+        #[arg(long)]
+        watch: bool,
+        /// Seconds between redraws with --watch
+        #[arg(long, value_name = "SECS", default_value_t = 5, requires = "watch",
+              value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+    /// Move a task in the manual ordering (used when task_sort is "manual")
+    #[command(group(clap::ArgGroup::new("movement").required(true).args(["up", "down", "to"])))]
+    TaskMove {
+        #[arg(value_name = "TASK_NAME")]
+        task_name: String,
+        /// Move the task one place up
+        #[arg(long)]
+        up: bool,
+        /// Move the task one place down
+        #[arg(long)]
+        down: bool,
+        /// Move the task to this 1-based position
+        #[arg(long, value_name = "N")]
+        to: Option<usize>,
+    },
+    /// Pin a task or aide so it's listed first
+    Pin {
+        #[arg(value_name = "NAME")]
+        name: String,
+        /// Only match task names
+        #[arg(long, conflicts_with = "aide")]
+        task: bool,
+        /// Only match aide names
+        #[arg(long)]
+        aide: bool,
+    },
+    /// Unpin a task or aide
+    Unpin {
+        #[arg(value_name = "NAME")]
+        name: String,
+        /// Only match task names
+        #[arg(long, conflicts_with = "aide")]
+        task: bool,
+        /// Only match aide names
+        #[arg(long)]
+        aide: bool,
+    },
+    /// Record that a task needs another task to be completed first
+    TaskDep {
+        #[arg(value_name = "TASK_NAME")]
+        task_name: String,
+        /// The task it depends on
+        #[arg(long, value_name = "OTHER")]
+        needs: String,
+        /// Remove the dependency instead of adding it
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Print the task dependency tree
+    TaskGraph,
+    /// Show the most urgent task that isn't completed or blocked
+    Next,
+    /// Combine task logs into one chronological Markdown report
+    TaskReport {
+        /// Only include log entries on or after this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+        /// Only include completed tasks
+        #[arg(long)]
+        completed: bool,
+        /// Write to this file instead of stdout
+        #[arg(short = 'o', long = "out", value_name = "FILE")]
+        out: Option<String>,
+    },
+    /// Search the contents of task log files
+    TaskSearch {
+        #[arg(value_name = "TEXT")]
+        text: String,
+        /// Ignore lines scoring below this fuzzy score
+        #[arg(long = "min-score", value_name = "SCORE", default_value_t = 0)]
+        min_score: i64,
+    },
     /// Edit task log file
     TaskEdit {
+        /// Task to edit; omit to pick one interactively
         #[arg(value_name = "TASK_NAME")]
-        task_name: String,
+        task_name: Option<String>,
+        /// Open with this editor instead of the configured one
+        #[arg(long, value_name = "CMD")]
+        editor: Option<String>,
     },
     /// Add log entry to task
     TaskLogUpdate {
@@ -88,25 +290,196 @@ pub enum Commands {
         task_name: String,
         #[arg(value_name = "LOG_TEXT")]
         log_text: String,
+        /// Create the task first if it doesn't exist
+        #[arg(long)]
+        create: bool,
     },
     /// List all aides
-    AideList,
+    AideList {
+        /// Maximum number of aides to show
+        #[arg(long, value_name = "N")]
+        limit: Option<u32>,
+        /// Number of aides to skip before listing
+        #[arg(long, value_name = "M", default_value_t = 0)]
+        offset: u32,
+        /// Only aides created today (UTC)
+        #[arg(long, conflicts_with = "week")]
+        today: bool,
+        /// Only aides created this week, since Monday (UTC)
+        #[arg(long)]
+        week: bool,
+    },
     /// Clear all data from database and TF-IDF indexes
     Clear,
     /// Reset all data (WARNING: Deletes all tasks and aides)
     Reset,
-    /// Open aide file in vim editor
+    /// Check for data rows of deleted aides, aide files without aides and aides without files
+    Fsck {
+        /// Repair what was found: delete orphaned rows, adopt stray files, recreate missing files
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Regenerate aide .txt files from the database (all aides, or just NAME)
+    RebuildFiles {
+        #[arg(value_name = "NAME")]
+        name: Option<String>,
+    },
+    /// Show where data lives: the database and a tree of ~/.aide with sizes
+    #[command(visible_alias = "ls")]
+    Files {
+        /// Print the tree as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Rebuild the TF-IDF fuzzy-matching indexes from the database
+    Reindex,
+    /// Show how a query scores against every task, aide or config name (for tuning fuzzy_threshold)
+    MatchDebug {
+        /// task, aide or config
+        #[arg(value_name = "TYPE")]
+        kind: String,
+        #[arg(value_name = "QUERY")]
+        query: String,
+    },
+    /// Create the data directories and seed default config keys (safe to re-run)
+    Init,
+    /// Show or change an aide's settings, e.g. `aide aide-config notes timestamp off`
+    AideConfig {
+        #[arg(value_name = "NAME")]
+        name: String,
+        #[arg(value_name = "KEY", requires = "value")]
+        key: Option<String>,
+        #[arg(value_name = "VALUE")]
+        value: Option<String>,
+    },
+    /// Delete a single entry from an aide (index as shown by export-md / the TUI preview)
+    EntryDelete {
+        #[arg(value_name = "NAME")]
+        name: String,
+        /// 1-based entry number
+        #[arg(value_name = "INDEX")]
+        index: usize,
+        /// Skip the confirmation prompt
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+    },
+    /// Export an aide's entries as Markdown
+    ExportMd {
+        #[arg(value_name = "NAME")]
+        name: String,
+        /// Write to this file instead of stdout
+        #[arg(short = 'o', long = "out", value_name = "FILE")]
+        out: Option<String>,
+    },
+    /// Open aide file in an external editor
     Write {
+        /// Aide to edit; omit to pick one interactively
+        #[arg(value_name = "AIDE_NAME")]
+        aide_name: Option<String>,
+        /// Open with this editor instead of the configured one
+        #[arg(long, value_name = "CMD")]
+        editor: Option<String>,
+    },
+    /// Show daily streaks and a per-day heatmap of an aide's entries
+    Streak {
+        #[arg(value_name = "AIDE_NAME")]
+        aide_name: String,
+        /// Number of weeks shown in the heatmap (1-520)
+        #[arg(short = 'w', long, value_name = "N", default_value_t = 8,
+              value_parser = clap::value_parser!(u32).range(1..=520))]
+        weeks: u32,
+    },
+    /// Create a new aide as a copy of an existing one (file, entries and settings)
+    Clone {
+        #[arg(value_name = "SOURCE")]
+        source: String,
+        #[arg(value_name = "NEW_NAME")]
+        new_name: String,
+    },
+    /// Encrypt an aide's entries and file with your passphrase (the first use sets it)
+    Encrypt {
+        #[arg(value_name = "AIDE_NAME")]
+        aide_name: String,
+    },
+    /// Store an encrypted aide's entries and file as plain text again
+    Decrypt {
         #[arg(value_name = "AIDE_NAME")]
         aide_name: String,
     },
     /// Launch TUI interface
-    Tui,
+    Tui {
+        /// Draw in the normal screen so the last frame stays in scrollback
+        #[arg(long = "no-alt-screen")]
+        no_alt_screen: bool,
+    },
 
     /// Ask a question to the LLM
+    #[command(group(clap::ArgGroup::new("action").args(["print_only", "copy", "exec"])))]
     Ask {
-        #[arg(value_name = "QUESTION")]
-        question: String,
+        #[arg(value_name = "QUESTION", required_unless_present = "last", conflicts_with = "last")]
+        question: Option<String>,
+        /// Print the command from the previous `aide ask` again instead of asking
+        #[arg(long)]
+        last: bool,
+        /// Sampling temperature (lower is more deterministic)
+        #[arg(long)]
+        temperature: Option<f32>,
+        /// Nucleus sampling probability mass
+        #[arg(long = "top-p")]
+        top_p: Option<f32>,
+        /// Maximum number of tokens to generate
+        #[arg(long = "num-predict")]
+        num_predict: Option<i32>,
+        /// Use this model instead of OLLAMA_MODEL_NAME (it must be available on the server)
+        #[arg(long, value_name = "MODEL")]
+        model: Option<String>,
+        /// Include an aide's entries in the prompt as reference notes
+        #[arg(long = "context", value_name = "AIDE")]
+        context: Option<String>,
+        /// Print the answer as JSON: query, command, model and whether it was cached
+        #[arg(long)]
+        json: bool,
+        /// Only print the command (the default)
+        #[arg(long = "print-only")]
+        print_only: bool,
+        /// Also copy the command to the clipboard
+        #[arg(long)]
+        copy: bool,
+        /// Run the command after asking for confirmation
+        #[arg(long)]
+        exec: bool,
+    },
+
+    /// Show recently created or updated tasks, aides and configs
+    Recent {
+        /// Number of entries to show
+        #[arg(short = 'n', long = "n", value_name = "N", default_value_t = 15)]
+        n: u32,
+        /// Print entries as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Run a read-only SQL query against the database (advanced, unsupported)
+    Sql {
+        #[arg(value_name = "QUERY")]
+        query: String,
+        /// Print result rows as JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Manage command aliases, e.g. `aide alias add tl task-list --limit 10` makes `aide tl` run that
+    Alias {
+        #[command(subcommand)]
+        action: AliasAction,
+    },
+
+    /// Require a passphrase to open the TUI, and again after it sits idle (see lock_timeout)
+    Lock {
+        #[command(subcommand)]
+        action: LockAction,
     },
 
     /// Generate shell completion script
@@ -114,4 +487,43 @@ pub enum Commands {
         #[arg(value_name = "SHELL")]
         shell: String,
     },
-}
\ No newline at end of file
+}
+
+#[derive(Subcommand)]
+pub enum AliasAction {
+    /// Define (or redefine) an alias
+    Add {
+        #[arg(value_name = "NAME")]
+        name: String,
+        /// Subcommand, optionally with arguments, that the alias expands to
+        #[arg(value_name = "TARGET", required = true, num_args = 1.., allow_hyphen_values = true)]
+        target: Vec<String>,
+    },
+    /// List defined aliases
+    List,
+    /// Remove an alias
+    Remove {
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum LockAction {
+    /// Set or change the TUI passphrase
+    Set,
+    /// Remove the TUI passphrase (asks for the current one)
+    Clear,
+}
+
+// Whether `name` is a built-in subcommand (so an alias can't take it over)
+pub fn is_subcommand(name: &str) -> bool {
+    use clap::CommandFactory;
+    Cli::command().find_subcommand(name).is_some()
+}
+
+// Accepts a number (range-checked when applied) or a level name such as "high"
+fn parse_priority_arg(value: &str) -> Result<u8, String> {
+    parse_priority(value)
+        .ok_or_else(|| format!("expected 1-5 or one of: {}", PRIORITY_NAMES.join(", ")))
+}
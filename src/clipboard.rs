@@ -0,0 +1,11 @@
+// Copy text to the system clipboard. Fails where there is none, e.g. over SSH or on a
+// Linux machine without a display.
+pub fn copy_to_clipboard(text: &str) -> Result<(), arboard::Error> {
+    arboard::Clipboard::new()?.set_text(text)
+}
+
+// Read the system clipboard's text. An empty clipboard (or one holding only an image) is
+// arboard::Error::ContentNotAvailable.
+pub fn paste_from_clipboard() -> Result<String, arboard::Error> {
+    arboard::Clipboard::new()?.get_text()
+}
@@ -0,0 +1,110 @@
+use age::secrecy::{ExposeSecret, SecretString};
+use anyhow::{anyhow, bail, Result};
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use std::io::{self, IsTerminal, Write};
+
+// Encrypted text is stored ASCII-armored, so it fits the TEXT columns and .txt files as is
+const ARMOR_HEADER: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+
+// Read instead of prompting when set, for scripts
+pub const PASSPHRASE_ENV: &str = "AIDE_PASSPHRASE";
+
+pub fn is_encrypted(text: &str) -> bool {
+    text.starts_with(ARMOR_HEADER)
+}
+
+// A new key pair: the public recipient (stored as is; encrypting needs nothing else) and the
+// secret identity sealed with the passphrase. Only unsealing pays for the slow scrypt step,
+// once per session, rather than every entry.
+pub fn generate_key(passphrase: &str) -> Result<(String, String)> {
+    let identity = age::x25519::Identity::generate();
+    let sealer = age::scrypt::Recipient::new(SecretString::from(passphrase.to_string()));
+    let sealed = age::encrypt_and_armor(&sealer, identity.to_string().expose_secret().as_bytes())?;
+    Ok((identity.to_public().to_string(), sealed))
+}
+
+pub fn unseal_identity(sealed: &str, passphrase: &str) -> Result<age::x25519::Identity> {
+    let unsealer = age::scrypt::Identity::new(SecretString::from(passphrase.to_string()));
+    let secret = age::decrypt(&unsealer, sealed.as_bytes()).map_err(|_| anyhow!("Wrong passphrase."))?;
+    String::from_utf8(secret)?
+        .parse()
+        .map_err(|err| anyhow!("Stored encryption key is damaged: {}", err))
+}
+
+pub fn encrypt(recipient: &str, text: &str) -> Result<String> {
+    let recipient: age::x25519::Recipient = recipient
+        .parse()
+        .map_err(|err| anyhow!("Stored encryption key is damaged: {}", err))?;
+    Ok(age::encrypt_and_armor(&recipient, text.as_bytes())?)
+}
+
+pub fn decrypt(identity: &age::x25519::Identity, text: &str) -> Result<String> {
+    let plain = age::decrypt(identity, text.as_bytes())?;
+    Ok(String::from_utf8(plain)?)
+}
+
+// Salted argon2 hash of a passphrase, as a self-describing PHC string
+pub fn hash_passphrase(passphrase: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(passphrase.as_bytes(), &salt)
+        .map_err(|err| anyhow!("Could not hash the passphrase: {}", err))?;
+    Ok(hash.to_string())
+}
+
+pub fn verify_passphrase(passphrase: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .is_ok_and(|hash| Argon2::default().verify_password(passphrase.as_bytes(), &hash).is_ok())
+}
+
+// The passphrase from AIDE_PASSPHRASE, or typed at the terminal without echo
+pub fn read_passphrase(prompt: &str) -> Result<String> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+    if !io::stdin().is_terminal() {
+        bail!("A passphrase is needed but there is no terminal to ask on. Set {}.", PASSPHRASE_ENV);
+    }
+    prompt_hidden(prompt)
+}
+
+// Ask at the terminal without echoing what's typed
+pub fn prompt_hidden(prompt: &str) -> Result<String> {
+    if !io::stdin().is_terminal() {
+        bail!("A passphrase is needed but there is no terminal to ask on.");
+    }
+
+    eprint!("{}", prompt);
+    io::stderr().flush()?;
+    terminal::enable_raw_mode()?;
+    let typed = read_hidden_line();
+    terminal::disable_raw_mode()?;
+    eprintln!();
+    typed?.ok_or_else(|| anyhow!("Operation cancelled."))
+}
+
+// Collect keys until Enter; None on Esc or Ctrl+C
+fn read_hidden_line() -> Result<Option<String>> {
+    let mut line = String::new();
+    loop {
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Enter => return Ok(Some(line)),
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(None),
+            KeyCode::Backspace => {
+                line.pop();
+            }
+            KeyCode::Char(c) => line.push(c),
+            _ => {}
+        }
+    }
+}
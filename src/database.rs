@@ -1,69 +1,435 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use rusqlite::{Connection, OptionalExtension};
-use std::path::PathBuf;
+use rusqlite::types::Value;
+use std::cell::OnceCell;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::fs;
 use std::io::{self, Write};
 use fuzzy_matcher::FuzzyMatcher;
-use crate::models::{TaskItem, AideItem, ConfigItem};
-use crate::tfidf::{TfIdfIndex, FuzzyMatchResult, build_tfidf_index, find_fuzzy_match_in_index, FUZZY_MATCH_THRESHOLD};
+use crate::paths;
+use crate::crypto;
+use crate::timing::{self, IndexWork};
+use crate::table::Table;
+use crate::output::emit;
+use crate::models::{TaskItem, AideItem, ConfigItem, DataEntry, EntryFormat, TaskListFormat, CreatedWithin, TaskMove, Outcome, ItemKind, PIN_MARKER, PRIORITY_NAMES, DEFAULT_ENTRY_FORMAT, format_minutes, parse_priority, priority_label};
+use crate::tfidf::{TfIdfIndex, FuzzyMatchResult, build_tfidf_index, find_fuzzy_match_in_index, score_index, match_weights, rank_order, FUZZY_MATCH_THRESHOLD, INDEX_REBUILD_THRESHOLD};
 
-// Helper function to ask user for confirmation
-fn ask_user_confirmation(input_name: &str, suggested_name: &str) -> bool {
-    print!("'{}' not found. Did you mean '{}'? (y/n): ", input_name, suggested_name);
-    io::stdout().flush().unwrap();
+// Helper function to ask user for confirmation, showing the match score when given (--verbose)
+fn ask_user_confirmation(input_name: &str, suggested_name: &str, score: Option<f64>) -> bool {
+    match score {
+        Some(score) => eprint!("'{}' not found. Did you mean '{}' (score {:.2})? (y/n): ", input_name, suggested_name, score),
+        None => eprint!("'{}' not found. Did you mean '{}'? (y/n): ", input_name, suggested_name),
+    }
+    io::stderr().flush().unwrap();
     
     let mut input = String::new();
     io::stdin().read_line(&mut input).unwrap();
     input.trim().to_lowercase() == "y" || input.trim().to_lowercase() == "yes"
 }
 
+// Editor used when the `editor` config key is unset
+const DEFAULT_EDITOR: &str = if cfg!(windows) { "notepad" } else { "vi" };
+
+// Launch an external editor on a file and wait for it to exit.
+// Refuses to run while the TUI owns the terminal, since the editor would
+// fight ratatui over raw mode and the alternate screen.
+fn open_in_editor(editor: &str, path: &Path) -> io::Result<std::process::ExitStatus> {
+    if crate::ui::is_tui_active() {
+        return Err(io::Error::other(
+            "cannot launch an external editor while the TUI is running",
+        ));
+    }
+    Command::new(editor).arg(path).status()
+}
+
+// Longest task, aide or config name accepted
+pub const MAX_NAME_LENGTH: usize = 128;
+
+// Check a new task/aide/config name, returning a message explaining why it's rejected
+pub fn validate_name(kind: &str, name: &str) -> std::result::Result<(), String> {
+    if name.trim().is_empty() {
+        return Err(format!("{} name cannot be empty.", kind));
+    }
+    let length = name.chars().count();
+    if length > MAX_NAME_LENGTH {
+        return Err(format!("{} name is too long ({} characters, maximum {}).", kind, length, MAX_NAME_LENGTH));
+    }
+    Ok(())
+}
+
+// Aide names also name files: `/` separates folders, and every part must work as a file name
+pub fn validate_aide_name(name: &str) -> std::result::Result<(), String> {
+    validate_name("Aide", name)?;
+    paths::aide_file(name).map(|_| ()).map_err(|err| format!("Invalid aide name: {}", err))
+}
+
+// Check a value for one of the keys aide reads itself; set and the TUI both store through this
+pub fn validate_config_value(key: &str, value: &str) -> std::result::Result<(), String> {
+    match key {
+        "entry_format" => EntryFormat::parse(value).map(|_| ()),
+        "llm_base_url" if !(value.starts_with("http://") || value.starts_with("https://")) => {
+            Err("llm_base_url must start with http:// or https://".to_string())
+        }
+        "llm_model" if value.trim().is_empty() => Err("llm_model can't be empty".to_string()),
+        "lock_timeout" if value.trim().parse::<u64>().is_err() => {
+            Err("lock_timeout must be a whole number of minutes (0: don't lock again after startup)".to_string())
+        }
+        "selection_bg" if crate::theme::parse_color(value).is_none() => {
+            Err("selection_bg must be a color name (e.g. blue, lightyellow), an index (0-255) or a hex color (#rrggbb)".to_string())
+        }
+        "highlight_symbol" if value.trim().is_empty() || value.contains('\n') => {
+            Err("highlight_symbol must be a single, non-blank line".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+// Every .txt file under `dir` and its folders, except in the task log and template folders
+// at the top level
+fn collect_aide_files(dir: &Path, top: bool, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            let reserved = path.file_name().and_then(|name| name.to_str())
+                .is_some_and(|name| paths::RESERVED_FOLDERS.contains(&name));
+            if !(top && reserved) {
+                collect_aide_files(&path, false, files)?;
+            }
+        } else if path.extension().is_some_and(|ext| ext == "txt") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+// Add a column to an existing table unless it is already there
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, declaration: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt.query_map([], |row| row.get::<_, String>(1))?;
+    for existing in columns {
+        if existing? == column {
+            return Ok(());
+        }
+    }
+    conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, declaration), [])?;
+    Ok(())
+}
+
+// Recreate `table` from `create_sql` when its stored definition lacks ON DELETE CASCADE.
+// SQLite can't alter a constraint in place, so the rows are copied into a fresh table.
+fn add_cascade_if_missing(conn: &Connection, table: &str, create_sql: &str) -> Result<()> {
+    if table_sql(conn, table)?.to_uppercase().contains("ON DELETE CASCADE") {
+        return Ok(());
+    }
+    recreate_table(conn, table, create_sql)
+}
+
+// Recreate the tasks table with the CHECK on status when it was created without one.
+// Statuses differing only in case or surrounding spaces are normalized first, and any
+// other unknown status becomes 'created' so the copy can't violate the constraint.
+fn add_status_check_if_missing(conn: &Connection) -> Result<()> {
+    if table_sql(conn, "tasks")?.contains("CHECK (status IN") {
+        return Ok(());
+    }
+    conn.execute_batch(
+        "UPDATE tasks SET status = LOWER(TRIM(status));
+         UPDATE tasks SET status = 'created' WHERE status NOT IN ('created', 'in_progress', 'completed');",
+    )?;
+    recreate_table(conn, "tasks", CREATE_TASKS_TABLE)
+}
+
+// Path for a new aide's file: `natural`, or `<stem>-2.txt`, `-3`... beside it when another aide
+// already has that path, since sanitized names can collide ("p:q" and "p_q" are both `p_q.txt`)
+fn unused_aide_file(conn: &Connection, natural: PathBuf) -> Result<PathBuf> {
+    let stem = natural.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    let mut path = natural.clone();
+    let mut suffix = 1;
+    while aide_file_taken(conn, &path)? {
+        suffix += 1;
+        path = natural.with_file_name(format!("{}-{}.txt", stem, suffix));
+    }
+    Ok(path)
+}
+
+// Whether another aide already stores `path` as its file
+fn aide_file_taken(conn: &Connection, path: &Path) -> Result<bool> {
+    Ok(conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM aides WHERE file_path = ?1)",
+        [path.to_string_lossy().as_ref()],
+        |row| row.get(0),
+    )?)
+}
+
+// Record a file for aides from before the file_path column, in creation order. Names without
+// a `/` go first, so their flat files are theirs; a "work/deploy" from before folders then keeps
+// its work_deploy.txt only if that file exists and no other aide claimed it. Names that can't be
+// file names stay without one.
+fn assign_missing_aide_files(conn: &Connection) -> Result<()> {
+    let mut names: Vec<String> = {
+        let mut stmt = conn.prepare("SELECT name FROM aides WHERE file_path IS NULL ORDER BY id")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+    names.sort_by_key(|name| name.contains('/'));
+    for name in names {
+        let Ok(natural) = paths::aide_file(&name) else {
+            continue;
+        };
+        let flat = paths::flat_aide_file(&name)?;
+        let path = if name.contains('/') && !natural.exists() && flat.exists() && !aide_file_taken(conn, &flat)? {
+            flat
+        } else {
+            unused_aide_file(conn, natural)?
+        };
+        conn.execute(
+            "UPDATE aides SET file_path = ?1 WHERE name = ?2",
+            [path.to_string_lossy().as_ref(), name.as_str()],
+        )?;
+    }
+    Ok(())
+}
+
+// The CREATE TABLE statement SQLite stored for `table`
+fn table_sql(conn: &Connection, table: &str) -> Result<String> {
+    Ok(conn.query_row(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |row| row.get(0),
+    )?)
+}
+
+// Copy `table`'s rows into a fresh table created from `create_sql`, which replaces it
+fn recreate_table(conn: &Connection, table: &str, create_sql: &str) -> Result<()> {
+    let columns = {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let names = stmt.query_map([], |row| row.get::<_, String>(1))?;
+        names.collect::<rusqlite::Result<Vec<_>>>()?.join(", ")
+    };
+    let staging = format!("{}_migrating", table);
+    // foreign_keys can't change inside a transaction, and must be off while the table is dropped
+    conn.execute_batch(&format!(
+        "PRAGMA foreign_keys = OFF;
+         BEGIN;
+         {create};
+         INSERT INTO {staging} ({columns}) SELECT {columns} FROM {table};
+         DROP TABLE {table};
+         ALTER TABLE {staging} RENAME TO {table};
+         COMMIT;
+         PRAGMA foreign_keys = ON;",
+        create = create_sql.replacen(table, &staging, 1),
+    ))?;
+    Ok(())
+}
+
+// Time over (+) or under (-) an estimate, with the percentage when there is an estimate to compare to
+fn variance_label(estimate: i64, actual: i64) -> String {
+    let variance = actual - estimate;
+    let sign = if variance > 0 { "+" } else { "" };
+    if estimate == 0 {
+        return format!("{}{}", sign, format_minutes(variance));
+    }
+    format!("{}{} ({}{:.0}%)", sign, format_minutes(variance), sign, variance as f64 * 100.0 / estimate as f64)
+}
+
+// Append a timestamped line to a task's log file, creating the file if needed
+fn append_task_log_entry(task_name: &str, task_log_file: &str, log_text: &str) -> Result<()> {
+    // Read existing content
+    let mut content = if PathBuf::from(task_log_file).exists() {
+        fs::read_to_string(task_log_file)?
+    } else {
+        format!("Task: {}\n\n--- Task Log ---\n", task_name)
+    };
+    
+    // Add timestamp and new log entry
+    let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S");
+    let log_entry = format!("\n[{}] {}", timestamp, log_text);
+    content.push_str(&log_entry);
+    
+    // Write back to file
+    fs::write(task_log_file, content)?;
+    Ok(())
+}
+
+// Split a task log file into its `[timestamp] text` entries. Lines that don't start a new
+// entry continue the previous one; anything before the first entry (the header) is skipped.
+fn parse_task_log(content: &str) -> Vec<(String, String)> {
+    let log = content.split_once("--- Task Log ---").map_or(content, |(_, log)| log);
+    let mut entries: Vec<(String, String)> = Vec::new();
+    for line in log.lines() {
+        let timestamp = line.strip_prefix('[')
+            .and_then(|rest| rest.split_once("] "))
+            .filter(|(ts, _)| chrono::NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S").is_ok());
+        match timestamp {
+            Some((ts, text)) => entries.push((ts.to_string(), text.to_string())),
+            None => {
+                if let Some((_, text)) = entries.last_mut() {
+                    text.push('\n');
+                    text.push_str(line);
+                }
+            }
+        }
+    }
+    for (_, text) in entries.iter_mut() {
+        text.truncate(text.trim_end().len());
+    }
+    entries
+}
+
 pub struct Database {
     conn: Connection,
     task_index: Option<TfIdfIndex>,
     aide_index: Option<TfIdfIndex>,
     config_index: Option<TfIdfIndex>,
+    fuzzy_threshold: f64,
+    // Incremental index changes before a full rebuild (index_rebuild_threshold)
+    index_rebuild_threshold: usize,
+    // Show similarity scores in "Did you mean" prompts (--verbose)
+    pub verbose: bool,
+    // Unsealed on first use, so the passphrase is asked for at most once per run
+    identity: OnceCell<age::x25519::Identity>,
+}
+
+// Settings accepted by `aide aide-config`: (key, default, description)
+const AIDE_SETTINGS: &[(&str, bool, &str)] = &[
+    ("timestamp", true, "Prefix new entries with the date and time"),
+];
+
+// Entries and settings belong to an aide and go away with it (foreign_keys is enabled per connection)
+const CREATE_DATA_TABLE: &str = "CREATE TABLE IF NOT EXISTS data (
+    id INTEGER PRIMARY KEY,
+    aide_id INTEGER NOT NULL,
+    input_text TEXT NOT NULL,
+    command_output TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (aide_id) REFERENCES aides (id) ON DELETE CASCADE
+)";
+
+const CREATE_AIDE_SETTINGS_TABLE: &str = "CREATE TABLE IF NOT EXISTS aide_settings (
+    aide_id INTEGER NOT NULL,
+    key_name TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (aide_id, key_name),
+    FOREIGN KEY (aide_id) REFERENCES aides (id) ON DELETE CASCADE
+)";
+
+// A task's status is one of TASK_STATUSES
+const CREATE_TASKS_TABLE: &str = "CREATE TABLE IF NOT EXISTS tasks (
+    id INTEGER PRIMARY KEY,
+    name TEXT UNIQUE NOT NULL,
+    priority INTEGER NOT NULL DEFAULT 3,
+    status TEXT NOT NULL DEFAULT 'created' CHECK (status IN ('created', 'in_progress', 'completed')),
+    task_log_file_path TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME,
+    position INTEGER,
+    pinned INTEGER NOT NULL DEFAULT 0,
+    estimate_minutes INTEGER,
+    actual_minutes INTEGER
+)";
+
+// `task_id` can't be completed before `depends_on_id`; both go away with either task
+const CREATE_TASK_DEPS_TABLE: &str = "CREATE TABLE IF NOT EXISTS task_deps (
+    task_id INTEGER NOT NULL,
+    depends_on_id INTEGER NOT NULL,
+    PRIMARY KEY (task_id, depends_on_id),
+    FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE,
+    FOREIGN KEY (depends_on_id) REFERENCES tasks (id) ON DELETE CASCADE
+)";
+
+// The key encrypted aides use: the public recipient, and the secret identity sealed with
+// the user's passphrase (see crypto::generate_key). At most one row.
+const CREATE_ENCRYPTION_KEY_TABLE: &str = "CREATE TABLE IF NOT EXISTS encryption_key (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    recipient TEXT NOT NULL,
+    sealed_identity TEXT NOT NULL
+)";
+
+// Config keys under this prefix define command aliases: alias.<name> = <target command>
+pub const ALIAS_PREFIX: &str = "alias.";
+
+// The salted argon2 hash of the TUI passphrase (`aide lock set`). It's kept out of config_data,
+// where `set`, `config-delete` or the TUI could remove the lock without the passphrase.
+const CREATE_TUI_LOCK_TABLE: &str = "CREATE TABLE IF NOT EXISTS tui_lock (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    hash TEXT NOT NULL
+)";
+
+// Minutes without a key press before a passphrase-protected TUI locks again, unless lock_timeout is set
+const DEFAULT_LOCK_TIMEOUT_MINUTES: u64 = 5;
+
+// Statuses a task can have (the tasks table's CHECK constraint lists the same ones)
+const TASK_STATUSES: [&str; 3] = ["created", "in_progress", "completed"];
+
+// A status as typed, compared case-insensitively and without surrounding spaces
+fn normalize_status(status: &str) -> String {
+    status.trim().to_lowercase()
+}
+
+// How much of the latest log entry `aide next` shows
+const NEXT_LOG_SNIPPET_CHARS: usize = 80;
+
+// Matching lines `aide task-search` shows per task, and how much of each
+const TASK_SEARCH_LINES_PER_TASK: usize = 5;
+const TASK_SEARCH_SNIPPET_CHARS: usize = 100;
+
+// Aide that `aide capture` appends to unless inbox_aide is set
+const DEFAULT_INBOX_AIDE: &str = "inbox";
+
+// How stored timestamps are shown in listings unless date_format is set (their stored form)
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+// Reformat a stored "YYYY-MM-DD HH:MM:SS" timestamp with a strftime-style format. Values that
+// don't parse, or an invalid format, leave the timestamp as stored.
+fn format_timestamp(stored: &str, date_format: &str) -> String {
+    let mut items = Vec::new();
+    for item in chrono::format::StrftimeItems::new(date_format) {
+        if item == chrono::format::Item::Error {
+            return stored.to_string();
+        }
+        items.push(item);
+    }
+    match chrono::NaiveDateTime::parse_from_str(stored, "%Y-%m-%d %H:%M:%S") {
+        Ok(time) => time.format_with_items(items.into_iter()).to_string(),
+        Err(_) => stored.to_string(),
+    }
 }
 
+// Config keys seeded by `aide init`: (key, default value, description)
+const DEFAULT_CONFIGS: &[(&str, &str, &str)] = &[
+    ("editor", DEFAULT_EDITOR, "Editor opened by `aide task`, `aide task-edit` and (first choice) `aide write`"),
+    ("fuzzy_threshold", "0.3", "Minimum similarity (0-1) for suggesting a near-match name"),
+    ("date_format", DEFAULT_DATE_FORMAT, "strftime-style format for timestamps in listings, e.g. %d %b %Y %H:%M"),
+    ("index_rebuild_threshold", "200", "Name changes before a fuzzy-match index is rebuilt from scratch (0: never)"),
+    ("inbox_aide", DEFAULT_INBOX_AIDE, "Aide that `aide capture` (or a bare `aide \"text\"`) appends to"),
+    ("write_txt_files", "true", "Mirror aide entries into ~/.aide/<name>.txt (false keeps them only in the database)"),
+    ("entry_format", DEFAULT_ENTRY_FORMAT, "Layout of entries in aide files: an optional {timestamp} line, then a prefix and {content}"),
+];
+
 impl Database {
     pub fn new() -> Result<Self> {
-        let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let db_path = PathBuf::from(home_dir).join(".aide.db");
-        
-        let conn = Connection::open(db_path)?;
+        Self::open(Connection::open(paths::db_path()?)?)
+    }
+    
+    // Set up (or migrate) the schema on an open connection and build the name indexes
+    pub(crate) fn open(conn: Connection) -> Result<Self> {
+        // SQLite only enforces the FOREIGN KEY clauses below when asked to, per connection
+        conn.execute_batch("PRAGMA foreign_keys = ON")?;
         
         // Create tables if they don't exist
         conn.execute(
             "CREATE TABLE IF NOT EXISTS aides (
                 id INTEGER PRIMARY KEY,
-                name TEXT UNIQUE NOT NULL
+                name TEXT UNIQUE NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                pinned INTEGER NOT NULL DEFAULT 0
             )",
             [],
         )?;
         
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS data (
-                id INTEGER PRIMARY KEY,
-                aide_id INTEGER NOT NULL,
-                input_text TEXT NOT NULL,
-                command_output TEXT NOT NULL,
-                FOREIGN KEY (aide_id) REFERENCES aides (id)
-            )",
-            [],
-        )?;
+        conn.execute(CREATE_DATA_TABLE, [])?;
         
-        // Create tasks table
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS tasks (
-                id INTEGER PRIMARY KEY,
-                name TEXT UNIQUE NOT NULL,
-                priority INTEGER NOT NULL DEFAULT 3,
-                status TEXT NOT NULL DEFAULT 'created',
-                task_log_file_path TEXT NOT NULL,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        )?;
+        conn.execute(CREATE_TASKS_TABLE, [])?;
 
         // Create config_data table
         conn.execute(
@@ -78,17 +444,79 @@ impl Database {
             [],
         )?;
         
+        // Per-aide options, e.g. whether entries get timestamps
+        conn.execute(CREATE_AIDE_SETTINGS_TABLE, [])?;
+        
+        conn.execute(CREATE_TASK_DEPS_TABLE, [])?;
+        
+        conn.execute(CREATE_ENCRYPTION_KEY_TABLE, [])?;
+        
+        // The lock hash used to be the tui_lock config key
+        conn.execute(CREATE_TUI_LOCK_TABLE, [])?;
+        conn.execute(
+            "INSERT OR IGNORE INTO tui_lock (id, hash)
+             SELECT 1, value FROM config_data WHERE key_name = 'tui_lock' AND value != ''",
+            [],
+        )?;
+        conn.execute("DELETE FROM config_data WHERE key_name = 'tui_lock'", [])?;
+        
+        // Migrate databases created before these columns existed.
+        // ALTER TABLE can't use a non-constant default, so older rows stay NULL.
+        add_column_if_missing(&conn, "aides", "created_at", "DATETIME")?;
+        add_column_if_missing(&conn, "data", "created_at", "DATETIME")?;
+        add_column_if_missing(&conn, "tasks", "updated_at", "DATETIME")?;
+        add_column_if_missing(&conn, "tasks", "position", "INTEGER")?;
+        add_column_if_missing(&conn, "tasks", "pinned", "INTEGER NOT NULL DEFAULT 0")?;
+        add_column_if_missing(&conn, "aides", "pinned", "INTEGER NOT NULL DEFAULT 0")?;
+        add_column_if_missing(&conn, "tasks", "estimate_minutes", "INTEGER")?;
+        add_column_if_missing(&conn, "tasks", "actual_minutes", "INTEGER")?;
+        add_column_if_missing(&conn, "aides", "encrypted", "INTEGER NOT NULL DEFAULT 0")?;
+        add_column_if_missing(&conn, "aides", "file_path", "TEXT")?;
+        // Older databases declared these foreign keys without cascading deletes
+        add_cascade_if_missing(&conn, "data", CREATE_DATA_TABLE)?;
+        add_cascade_if_missing(&conn, "aide_settings", CREATE_AIDE_SETTINGS_TABLE)?;
+        // ...and allowed any text as a task status
+        add_status_check_if_missing(&conn)?;
+        
+        // Tasks without a manual position go to the end, in creation order
+        conn.execute(
+            "UPDATE tasks SET position = (SELECT COALESCE(MAX(position), 0) FROM tasks) + id
+             WHERE position IS NULL",
+            [],
+        )?;
+        
         // Create default task_log aide if it doesn't exist
         let _ = conn.execute(
             "INSERT OR IGNORE INTO aides (name) VALUES ('task_log')",
             [],
         );
+        // Aides from before file_path (and task_log above) get the file they have been using
+        assign_missing_aide_files(&conn)?;
+        
+        let fuzzy_threshold = conn.query_row(
+            "SELECT value FROM config_data WHERE key_name = 'fuzzy_threshold'",
+            [],
+            |row| row.get::<_, String>(0),
+        ).optional()?
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(FUZZY_MATCH_THRESHOLD);
+        let index_rebuild_threshold = conn.query_row(
+            "SELECT value FROM config_data WHERE key_name = 'index_rebuild_threshold'",
+            [],
+            |row| row.get::<_, String>(0),
+        ).optional()?
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(INDEX_REBUILD_THRESHOLD);
         
         let mut db = Database { 
             conn,
             task_index: None,
             aide_index: None,
             config_index: None,
+            fuzzy_threshold,
+            index_rebuild_threshold,
+            verbose: false,
+            identity: OnceCell::new(),
         };
         
         // Build initial indexes
@@ -101,56 +529,308 @@ impl Database {
     
     // Build TF-IDF index for tasks
     pub fn rebuild_task_index(&mut self) -> Result<()> {
+        let started = std::time::Instant::now();
         let mut stmt = self.conn.prepare("SELECT name FROM tasks")?;
-        let rows = stmt.query_map([], |row| {
-            Ok(row.get::<_, String>(0)?)
-        })?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
         
         let mut task_names = Vec::new();
         for row in rows {
             task_names.push(row?);
         }
         
-        self.task_index = Some(build_tfidf_index(task_names)?);
+        let mut index = build_tfidf_index(task_names)?;
+        index.rebuild_after = self.index_rebuild_threshold;
+        self.task_index = Some(index);
+        timing::record(IndexWork::Build, started);
         Ok(())
     }
     
     // Build TF-IDF index for aides
     pub fn rebuild_aide_index(&mut self) -> Result<()> {
+        let started = std::time::Instant::now();
         let mut stmt = self.conn.prepare("SELECT name FROM aides")?;
-        let rows = stmt.query_map([], |row| {
-            Ok(row.get::<_, String>(0)?)
-        })?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
         
         let mut aide_names = Vec::new();
         for row in rows {
             aide_names.push(row?);
         }
         
-        self.aide_index = Some(build_tfidf_index(aide_names)?);
+        let mut index = build_tfidf_index(aide_names)?;
+        index.rebuild_after = self.index_rebuild_threshold;
+        self.aide_index = Some(index);
+        timing::record(IndexWork::Build, started);
         Ok(())
     }
     
     // Build TF-IDF index for config keys
     pub fn rebuild_config_index(&mut self) -> Result<()> {
+        let started = std::time::Instant::now();
         let mut stmt = self.conn.prepare("SELECT key_name FROM config_data")?;
-        let rows = stmt.query_map([], |row| {
-            Ok(row.get::<_, String>(0)?)
-        })?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
         
         let mut config_keys = Vec::new();
         for row in rows {
             config_keys.push(row?);
         }
         
-        self.config_index = Some(build_tfidf_index(config_keys)?);
+        let mut index = build_tfidf_index(config_keys)?;
+        index.rebuild_after = self.index_rebuild_threshold;
+        self.config_index = Some(index);
+        timing::record(IndexWork::Build, started);
+        Ok(())
+    }
+    
+    // Editor for task log files: the --editor override, else the `editor` config key,
+    // defaulting to vi (notepad on Windows)
+    fn editor_command(&self, editor_override: Option<&str>) -> Result<String> {
+        if let Some(editor) = editor_override {
+            return Ok(editor.to_string());
+        }
+        Ok(self.get_config_value("editor")?.unwrap_or_else(|| DEFAULT_EDITOR.to_string()))
+    }
+    
+    // Scaffold the data directories and seed default config keys.
+    // Safe to run repeatedly; reports what was created and what already existed.
+    pub fn init(&mut self, db_existed: bool) -> Result<()> {
+        let report = |created: bool, what: &str| {
+            emit!("{} {}", if created { "Created       " } else { "Already exists" }, what);
+        };
+        
+        report(!db_existed, &paths::db_path()?.display().to_string());
+        
+        let aide_dir = paths::aide_dir()?;
+        for dir in [aide_dir.clone(), aide_dir.join("tasks"), aide_dir.join("templates")] {
+            let existed = dir.is_dir();
+            fs::create_dir_all(&dir)?;
+            report(!existed, &dir.display().to_string());
+        }
+        
+        for (key, value, description) in DEFAULT_CONFIGS {
+            let inserted = self.conn.execute(
+                "INSERT OR IGNORE INTO config_data (key_name, value, description) VALUES (?1, ?2, ?3)",
+                [key, value, description],
+            )? > 0;
+            if inserted {
+                if let Some(ref mut index) = self.config_index {
+                    index.add_entity(key.to_string())?;
+                }
+            }
+            report(inserted, &format!("config '{}'", key));
+        }
+        
+        Ok(())
+    }
+    
+    // Rebuild all TF-IDF indexes from scratch, e.g. after the database was edited externally
+    pub fn reindex(&mut self) -> Result<()> {
+        self.rebuild_task_index()?;
+        self.rebuild_aide_index()?;
+        self.rebuild_config_index()?;
+        
+        let count = |index: &Option<TfIdfIndex>| index.as_ref().map_or(0, |index| index.total_docs);
+        emit!("Rebuilt indexes:");
+        emit!("  Tasks:   {}", count(&self.task_index));
+        emit!("  Aides:   {}", count(&self.aide_index));
+        emit!("  Configs: {}", count(&self.config_index));
+        
+        Ok(())
+    }
+    
+    // Check that the database and the aide files agree: data/settings rows whose aide is gone,
+    // .txt files in the aide directory with no aide, and aides with no file. With `fix`, orphaned
+    // rows are deleted, stray files are adopted as new aides with the entries parsed from them
+    // (nothing is deleted from disk) and missing files are recreated empty.
+    pub fn fsck(&mut self, fix: bool) -> Result<Outcome> {
+        let mut problems = 0;
+        
+        for table in ["data", "aide_settings"] {
+            let orphans: i64 = self.conn.query_row(
+                &format!("SELECT COUNT(*) FROM {} WHERE aide_id NOT IN (SELECT id FROM aides)", table),
+                [],
+                |row| row.get(0),
+            )?;
+            if orphans > 0 {
+                problems += 1;
+                println!("{} row(s) in '{}' belong to a deleted aide", orphans, table);
+                if fix {
+                    self.conn.execute(
+                        &format!("DELETE FROM {} WHERE aide_id NOT IN (SELECT id FROM aides)", table),
+                        [],
+                    )?;
+                    println!("  Deleted");
+                }
+            }
+        }
+        
+        let aide_names: Vec<String> = {
+            let mut stmt = self.conn.prepare("SELECT name FROM aides ORDER BY name")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        let mut expected_files = std::collections::HashSet::new();
+        let write_txt_files = self.write_txt_files()?;
+        for name in &aide_names {
+            // Names from before validation existed may not map to a file at all
+            let Ok(file_path) = self.aide_file(name) else {
+                problems += 1;
+                println!("Aide '{}' has no usable file name", name);
+                continue;
+            };
+            if write_txt_files && !file_path.exists() {
+                problems += 1;
+                println!("Aide '{}' has no file ({})", name, file_path.display());
+                if fix {
+                    paths::create_parent_dir(&file_path)?;
+                    fs::write(&file_path, self.seal(name, &new_aide_file_content(name))?)?;
+                    println!("  Created");
+                }
+            }
+            expected_files.insert(file_path);
+        }
+        
+        let aide_dir = paths::aide_dir()?;
+        if aide_dir.is_dir() {
+            let mut stray_files = Vec::new();
+            collect_aide_files(&aide_dir, true, &mut stray_files)?;
+            stray_files.retain(|path| !expected_files.contains(path));
+            stray_files.sort();
+            for path in stray_files {
+                problems += 1;
+                println!("File {} has no aide", path.display());
+                // Files in folders are adopted under their folder path, e.g. "work/deploy"
+                let Some(name) = paths::aide_name_for(&path) else {
+                    continue;
+                };
+                let name = name.as_str();
+                if fix && validate_aide_name(name).is_ok() && self.insert_aide(name)? {
+                    // An encrypted file would need its key; it's adopted empty
+                    let imported = if crypto::is_encrypted(&fs::read_to_string(&path)?) {
+                        0
+                    } else {
+                        self.import_entries(name, &self.parse_aide_file(&path)?)?
+                    };
+                    println!("  Added as aide '{}' with {} entr{}", name, imported, if imported == 1 { "y" } else { "ies" });
+                }
+            }
+        }
+        
+        if problems == 0 {
+            println!("No problems found.");
+            Ok(Outcome::Done)
+        } else if fix {
+            Ok(Outcome::Done)
+        } else {
+            println!("Run `aide fsck --fix` to repair.");
+            Ok(Outcome::Failed)
+        }
+    }
+    
+    // Store parsed entries as data rows of an aide, leaving its file as it is.
+    // Returns how many were stored.
+    fn import_entries(&self, aide_name: &str, entries: &[DataEntry]) -> Result<usize> {
+        let aide_id: i64 = self.conn.query_row("SELECT id FROM aides WHERE name = ?1", [aide_name], |row| row.get(0))?;
+        for entry in entries {
+            self.conn.execute(
+                "INSERT INTO data (aide_id, input_text, command_output, created_at)
+                 VALUES (?1, ?2, ?3, COALESCE(?4, CURRENT_TIMESTAMP))",
+                rusqlite::params![aide_id, entry.input_text, entry.command_output, entry.created_at],
+            )?;
+        }
+        Ok(entries.len())
+    }
+    
+    // Rewrite aide files from their data rows, for all aides or the one matching `aide_name`.
+    // Files that already match the database are left alone.
+    pub fn rebuild_files(&self, aide_name: Option<&str>) -> Result<Outcome> {
+        let names: Vec<String> = match aide_name {
+            Some(name) => match self.resolve_aide_name(name)? {
+                Some(actual_aide_name) => vec![actual_aide_name],
+                None => return Ok(Outcome::NotFound),
+            },
+            None => {
+                let mut stmt = self.conn.prepare("SELECT name FROM aides ORDER BY name")?;
+                let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+                rows.collect::<rusqlite::Result<_>>()?
+            }
+        };
+        
+        let mut rebuilt = 0;
+        for name in &names {
+            let Ok(file_path) = self.aide_file(name) else {
+                eprintln!("Skipped '{}': no usable file name", name);
+                continue;
+            };
+            let content = self.render_aide_file(name)?;
+            let existing = fs::read_to_string(&file_path).ok().and_then(|existing| self.reveal(existing).ok());
+            if existing.is_some_and(|existing| existing == content) {
+                continue;
+            }
+            paths::create_parent_dir(&file_path)?;
+            fs::write(&file_path, self.seal(name, &content)?)?;
+            emit!("Regenerated {}", file_path.display());
+            rebuilt += 1;
+        }
+        
+        if rebuilt == 0 {
+            emit!("All aide files are up to date.");
+        }
+        if !self.write_txt_files()? {
+            eprintln!("Note: write_txt_files is off, so new entries won't be added to these files.");
+        }
+        Ok(Outcome::Done)
+    }
+    
+    // Print every task/aide/config name scored against `query`, best first, for tuning fuzzy_threshold
+    pub fn match_debug(&self, kind: &str, query: &str) -> Result<()> {
+        let index = match kind.to_lowercase().trim_end_matches('s') {
+            "task" => &self.task_index,
+            "aide" => &self.aide_index,
+            "config" => &self.config_index,
+            _ => {
+                eprintln!("Invalid type '{}'. Valid types are: task, aide, config", kind);
+                return Ok(());
+            }
+        };
+        let Some(index) = index.as_ref().filter(|index| !index.entity_names.is_empty()) else {
+            println!("No {}s indexed.", kind.to_lowercase().trim_end_matches('s'));
+            return Ok(());
+        };
+        
+        let started = std::time::Instant::now();
+        let mut scores = score_index(query, index);
+        timing::record(IndexWork::Query, started);
+        scores.sort_by(|a, b| rank_order(&a.name, a.combined_score, &b.name, b.combined_score));
+        
+        let (string_weight, tfidf_weight) = match_weights(query);
+        println!("Query: '{}'", query);
+        println!("Weights: string similarity {:.2}, TF-IDF {:.2} | threshold {:.2} (fuzzy_threshold)",
+                 string_weight, tfidf_weight, self.fuzzy_threshold);
+        if index.entity_names.iter().any(|name| name == query) {
+            println!("'{}' is an exact match and is used without fuzzy matching.", query);
+        }
+        println!();
+        println!("  {:<8} | {:<8} | {:<8} | Name", "Combined", "String", "TF-IDF");
+        println!("  {}", "-".repeat(40));
+        for score in &scores {
+            let marker = if score.combined_score >= self.fuzzy_threshold { "*" } else { " " };
+            println!("{} {:<8.3} | {:<8.3} | {:<8.3} | {}",
+                     marker, score.combined_score, score.string_score, score.tfidf_score, score.name);
+        }
+        let passing = scores.iter().filter(|score| score.combined_score >= self.fuzzy_threshold).count();
+        println!();
+        println!("* = passes the threshold ({} of {})", passing, scores.len());
+        
         Ok(())
     }
     
     // Find fuzzy matches for tasks using TF-IDF
     pub fn find_fuzzy_task_match(&self, input_name: &str) -> Result<FuzzyMatchResult> {
         if let Some(index) = &self.task_index {
-            find_fuzzy_match_in_index(input_name, index)
+            let started = std::time::Instant::now();
+            let result = find_fuzzy_match_in_index(input_name, index, self.fuzzy_threshold);
+            timing::record(IndexWork::Query, started);
+            result
         } else {
             Ok(FuzzyMatchResult {
                 exact_match: false,
@@ -163,7 +843,10 @@ impl Database {
     // Find fuzzy matches for aides using TF-IDF
     pub fn find_fuzzy_aide_match(&self, input_name: &str) -> Result<FuzzyMatchResult> {
         if let Some(index) = &self.aide_index {
-            find_fuzzy_match_in_index(input_name, index)
+            let started = std::time::Instant::now();
+            let result = find_fuzzy_match_in_index(input_name, index, self.fuzzy_threshold);
+            timing::record(IndexWork::Query, started);
+            result
         } else {
             Ok(FuzzyMatchResult {
                 exact_match: false,
@@ -176,7 +859,10 @@ impl Database {
     // Find fuzzy matches for config keys using TF-IDF
     pub fn find_fuzzy_config_match(&self, input_name: &str) -> Result<FuzzyMatchResult> {
         if let Some(index) = &self.config_index {
-            find_fuzzy_match_in_index(input_name, index)
+            let started = std::time::Instant::now();
+            let result = find_fuzzy_match_in_index(input_name, index, self.fuzzy_threshold);
+            timing::record(IndexWork::Query, started);
+            result
         } else {
             Ok(FuzzyMatchResult {
                 exact_match: false,
@@ -186,64 +872,179 @@ impl Database {
         }
     }
     
-    pub fn create_aide(&mut self, name: &str) -> Result<()> {
+    // The name a fuzzy lookup settles on: the exact match, or a near match the user confirms.
+    // Err(Outcome::Cancelled) when the suggestion is declined, Err(Outcome::NotFound) when
+    // nothing is close enough.
+    fn confirm_match(&self, input_name: &str, fuzzy_result: FuzzyMatchResult) -> std::result::Result<String, Outcome> {
+        match fuzzy_result {
+            FuzzyMatchResult { exact_match: true, suggested_name: Some(name), .. } => Ok(name),
+            FuzzyMatchResult { suggested_name: Some(suggestion), score: Some(score), .. }
+                if score >= self.fuzzy_threshold => {
+                if ask_user_confirmation(input_name, &suggestion, self.verbose.then_some(score)) {
+                    Ok(suggestion)
+                } else {
+                    Err(Outcome::Cancelled)
+                }
+            }
+            _ => Err(Outcome::NotFound),
+        }
+    }
+    
+    // Like `confirm_match`, but prints why and returns None when there is no name to use
+    fn resolve_name(&self, kind: &str, input_name: &str, fuzzy_result: FuzzyMatchResult) -> Option<String> {
+        match self.confirm_match(input_name, fuzzy_result) {
+            Ok(name) => Some(name),
+            Err(Outcome::Cancelled) => {
+                eprintln!("Operation cancelled.");
+                None
+            }
+            Err(_) => {
+                eprintln!("{} '{}' not found.", kind, input_name);
+                None
+            }
+        }
+    }
+    
+    // Resolve a task name via fuzzy matching, asking the user to confirm suggestions.
+    // Prints why and returns None when nothing suitable is found or the user declines.
+    pub fn resolve_task_name(&self, task_name: &str) -> Result<Option<String>> {
+        Ok(self.resolve_name("Task", task_name, self.find_fuzzy_task_match(task_name)?))
+    }
+    
+    // Resolve an aide name via fuzzy matching, asking the user to confirm suggestions.
+    // Prints why and returns None when nothing suitable is found or the user declines.
+    pub fn resolve_aide_name(&self, aide_name: &str) -> Result<Option<String>> {
+        Ok(self.resolve_name("Aide", aide_name, self.find_fuzzy_aide_match(aide_name)?))
+    }
+    
+    // Resolve a config key via fuzzy matching, asking the user to confirm suggestions.
+    // Prints why and returns None when nothing suitable is found or the user declines.
+    pub fn resolve_config_name(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.resolve_name("Config key", key, self.find_fuzzy_config_match(key)?))
+    }
+    
+    // Task names in list order, for the interactive picker
+    pub fn task_names(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(&format!("SELECT name FROM tasks {}", self.task_order_clause()?))?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+    
+    // Aide names in list order, for the interactive picker
+    pub fn aide_names(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("SELECT name FROM aides ORDER BY pinned DESC, name")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+    
+    pub fn create_aide(&mut self, name: &str) -> Result<Outcome> {
+        if let Err(reason) = validate_aide_name(name) {
+            eprintln!("{}", reason);
+            return Ok(Outcome::Invalid);
+        }
+        if self.insert_aide(name)? {
+            emit!("Aide '{}' created successfully", name);
+        } else {
+            emit!("Aide '{}' already exists", name);
+        }
+        Ok(Outcome::Done)
+    }
+
+    // Create `new_name` as a copy of an aide: its file verbatim, its entries and its settings
+    pub fn clone_aide(&mut self, source: &str, new_name: &str) -> Result<Outcome> {
+        if let Err(reason) = validate_aide_name(new_name) {
+            eprintln!("{}", reason);
+            return Ok(Outcome::Invalid);
+        }
+        let actual_source = match self.resolve_aide_name(source)? {
+            Some(name) => name,
+            None => return Ok(Outcome::NotFound),
+        };
+        let source_file = self.aide_file(&actual_source)?;
+        let new_file = unused_aide_file(&self.conn, paths::aide_file(new_name)?)?;
+        
+        let tx = self.conn.transaction()?;
+        let taken: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM aides WHERE name = ?1)",
+            [new_name],
+            |row| row.get(0),
+        )?;
+        if taken {
+            eprintln!("Aide '{}' already exists. Delete it first or choose another name.", new_name);
+            return Ok(Outcome::Invalid);
+        }
+        // A file no aide owns (e.g. left behind by hand) isn't overwritten
+        if new_file.exists() {
+            eprintln!("{} already exists. Choose another name or remove the file first.", new_file.display());
+            return Ok(Outcome::Invalid);
+        }
+        let source_id: i64 = tx.query_row("SELECT id FROM aides WHERE name = ?1", [&actual_source], |row| row.get(0))?;
+        // Encrypted rows are copied as ciphertext, so the clone stays encrypted too
+        tx.execute(
+            "INSERT INTO aides (name, created_at, encrypted, file_path)
+             SELECT ?1, CURRENT_TIMESTAMP, encrypted, ?3 FROM aides WHERE id = ?2",
+            rusqlite::params![new_name, source_id, new_file.to_string_lossy()],
+        )?;
+        let new_id = tx.last_insert_rowid();
+        let copied = tx.execute(
+            "INSERT INTO data (aide_id, input_text, command_output, created_at)
+             SELECT ?1, input_text, command_output, created_at FROM data WHERE aide_id = ?2 ORDER BY id",
+            [new_id, source_id],
+        )?;
+        tx.execute(
+            "INSERT INTO aide_settings (aide_id, key_name, value)
+             SELECT ?1, key_name, value FROM aide_settings WHERE aide_id = ?2",
+            [new_id, source_id],
+        )?;
+        tx.commit()?;
+        
+        if source_file.exists() {
+            paths::create_parent_dir(&new_file)?;
+            fs::copy(&source_file, &new_file)?;
+        } else if self.write_txt_files()? {
+            paths::create_parent_dir(&new_file)?;
+            fs::write(&new_file, self.seal(new_name, &self.render_aide_file(new_name)?)?)?;
+        }
+        if let Some(ref mut index) = self.aide_index {
+            index.add_entity(new_name.to_string())?;
+        }
+        
+        emit!("Cloned aide '{}' to '{}' ({} entr{})", actual_source, new_name, copied, if copied == 1 { "y" } else { "ies" });
+        Ok(Outcome::Done)
+    }
+    
+    // Create an aide and its file without printing; returns false if it already exists
+    pub fn insert_aide(&mut self, name: &str) -> Result<bool> {
+        // Resolved first so a name that can't be a file name is rejected before the insert
+        let file_path = unused_aide_file(&self.conn, paths::aide_file(name)?)?;
         match self.conn.execute(
-            "INSERT INTO aides (name) VALUES (?1)",
-            [name],
+            "INSERT INTO aides (name, created_at, file_path) VALUES (?1, CURRENT_TIMESTAMP, ?2)",
+            [name, file_path.to_string_lossy().as_ref()],
         ) {
             Ok(_) => {
                 // Create the file for this aide
-                let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-                let aide_dir = PathBuf::from(&home_dir).join(".aide");
-                fs::create_dir_all(&aide_dir)?;
-                
-                let file_path = aide_dir.join(format!("{}.txt", name));
-                if !file_path.exists() {
-                    let initial_content = format!("# {}\n\nCreated: {}\n\n", 
-                                                 name, 
-                                                 chrono::Utc::now().format("%Y-%m-%d %H:%M:%S"));
-                    fs::write(&file_path, initial_content)?;
+                if self.write_txt_files()? && !file_path.exists() {
+                    paths::create_parent_dir(&file_path)?;
+                    fs::write(&file_path, new_aide_file_content(name))?;
                 }
                 
-                println!("Aide '{}' created successfully", name);
                 // Use incremental update instead of full rebuild
                 if let Some(ref mut index) = self.aide_index {
                     index.add_entity(name.to_string())?;
                 }
-                Ok(())
+                Ok(true)
             }
             Err(rusqlite::Error::SqliteFailure(err, _)) 
                 if err.code == rusqlite::ErrorCode::ConstraintViolation => {
-                println!("Aide '{}' already exists", name);
-                Ok(())
+                Ok(false)
             }
             Err(e) => Err(e.into()),
         }
     }
     
-    pub fn add_data(&mut self, name: &str, data: &str, path: Option<&str>) -> Result<()> {
-        // Use fuzzy matching to find the aide
-        let fuzzy_result = self.find_fuzzy_aide_match(name)?;
-        
-        let actual_aide_name = match fuzzy_result {
-            FuzzyMatchResult { exact_match: true, suggested_name: Some(name), .. } => name,
-            FuzzyMatchResult { suggested_name: Some(suggestion), score: Some(score), .. } => {
-                if score >= FUZZY_MATCH_THRESHOLD {
-                    if ask_user_confirmation(name, &suggestion) {
-                        suggestion
-                    } else {
-                        println!("Operation cancelled.");
-                        return Ok(());
-                    }
-                } else {
-                    println!("Aide '{}' not found.", name);
-                    return Ok(());
-                }
-            }
-            _ => {
-                println!("Aide '{}' not found.", name);
-                return Ok(());
-            }
+    pub fn add_data(&mut self, name: &str, data: &str, path: Option<&str>) -> Result<Outcome> {
+        let Some(actual_aide_name) = self.resolve_aide_name(name)? else {
+            return Ok(Outcome::NotFound);
         };
         
         // Determine the actual content to add
@@ -251,12 +1052,12 @@ impl Database {
             // Read content from file
             match fs::read_to_string(file_path) {
                 Ok(file_content) => {
-                    println!("Reading content from file: {}", file_path);
+                    emit!("Reading content from file: {}", file_path);
                     file_content.trim().to_string() // Remove trailing whitespace/newlines
                 }
                 Err(e) => {
-                    println!("Error reading file '{}': {}", file_path, e);
-                    return Ok(());
+                    eprintln!("Error reading file '{}': {}", file_path, e);
+                    return Ok(Outcome::Invalid);
                 }
             }
         } else {
@@ -264,67 +1065,457 @@ impl Database {
             data.to_string()
         };
         
-        // Find the aide by name
-        let aide_id: i64 = match self.conn.query_row(
-            "SELECT id FROM aides WHERE name = ?1",
-            [&actual_aide_name],
-            |row| Ok(row.get(0)?),
-        ) {
-            Ok(id) => id,
+        let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let timestamped_data = if self.aide_setting_flag(&actual_aide_name, "timestamp")? {
+            format!("[{}] {}", timestamp, content)
+        } else {
+            content.clone()
+        };
+        if !self.store_aide_entry(&actual_aide_name, &timestamp, &content, &timestamped_data, &content)? {
+            return Ok(Outcome::NotFound);
+        }
+        
+        if path.is_some() {
+            emit!("File content added successfully to aide '{}'", actual_aide_name);
+        } else {
+            emit!("Data added successfully to aide '{}'", actual_aide_name);
+        }
+        Ok(Outcome::Done)
+    }
+    
+    // Quick capture: append text to the inbox aide (config key inbox_aide, default "inbox"),
+    // creating the aide on first use
+    pub fn capture(&mut self, text: &str) -> Result<Outcome> {
+        let inbox = self.get_config_value("inbox_aide")?
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| DEFAULT_INBOX_AIDE.to_string());
+        if text.trim().is_empty() {
+            eprintln!("Nothing to capture.");
+            return Ok(Outcome::Invalid);
+        }
+        if let Err(reason) = validate_aide_name(&inbox) {
+            eprintln!("inbox_aide: {}", reason);
+            return Ok(Outcome::Invalid);
+        }
+        if self.insert_aide(&inbox)? {
+            emit!("Created inbox aide '{}'", inbox);
+        }
+        self.add_data(&inbox, text, None)
+    }
+    
+    // Append an entry to the aide's file and store it as a data row.
+    // Returns false (after printing why) if the aide isn't in the database.
+    fn store_aide_entry(
+        &self,
+        aide_name: &str,
+        timestamp: &str,
+        input_text: &str,
+        command_output: &str,
+        file_text: &str,
+    ) -> Result<bool> {
+        // Find the aide by name
+        let aide_id: i64 = match self.conn.query_row(
+            "SELECT id FROM aides WHERE name = ?1",
+            [aide_name],
+            |row| row.get(0),
+        ) {
+            Ok(id) => id,
             Err(rusqlite::Error::QueryReturnedNoRows) => {
-                println!("Aide '{}' not found in database", actual_aide_name);
-                return Ok(());
+                eprintln!("Aide '{}' not found in database", aide_name);
+                return Ok(false);
             }
             Err(e) => return Err(e.into()),
         };
         
-        let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S");
-        let timestamped_data = format!("[{}] {}", timestamp, content);
+        // Create/append to file for this aide, unless entries live only in the database.
+        // Encrypted aides get their whole file rewritten once the row is stored.
+        let encrypted = self.aide_encrypted(aide_name)?;
+        if self.write_txt_files()? && !encrypted {
+            let file_path = self.aide_file(aide_name)?;
+            paths::create_parent_dir(&file_path)?;
+            
+            // Append to existing file or create new one with better formatting
+            let existing_content = if file_path.exists() {
+                fs::read_to_string(&file_path)?
+            } else {
+                new_aide_file_content(aide_name)
+            };
+            
+            // Without the timestamp line if timestamps are off
+            let timestamp = self.aide_setting_flag(aide_name, "timestamp")?.then_some(timestamp);
+            let new_entry = self.entry_format()?.format_entry(timestamp, file_text);
+            let updated_content = format!("{}{}", existing_content, new_entry);
+            fs::write(&file_path, updated_content)?;
+            emit!("Data appended to file: {}", file_path.display());
+        }
+        
+        // Store in database
+        self.conn.execute(
+            "INSERT INTO data (aide_id, input_text, command_output, created_at) VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)",
+            [&aide_id.to_string(), &self.seal(aide_name, input_text)?, &self.seal(aide_name, command_output)?],
+        )?;
+        if encrypted {
+            self.sync_encrypted_file(aide_name)?;
+        }
         
-        // Create/append to file for this aide
-        let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let aide_dir = PathBuf::from(&home_dir).join(".aide");
-        fs::create_dir_all(&aide_dir)?;
+        Ok(true)
+    }
+    
+    // Whether aide entries are mirrored into .txt files (the write_txt_files config key, default on)
+    pub fn write_txt_files(&self) -> Result<bool> {
+        self.get_config_flag("write_txt_files", true)
+    }
+    
+    // An aide's file content as it would be written from its data rows: the header,
+    // then each entry under its timestamp (or without one if timestamps are off)
+    pub fn render_aide_file(&self, aide_name: &str) -> Result<String> {
+        let created_at: Option<String> = self.conn.query_row(
+            "SELECT created_at FROM aides WHERE name = ?1",
+            [aide_name],
+            |row| row.get(0),
+        ).optional()?.flatten();
+        let mut content = match created_at {
+            Some(created_at) => format!("# {}\n\nCreated: {}\n\n", aide_name, created_at),
+            None => new_aide_file_content(aide_name),
+        };
         
-        let file_path = aide_dir.join(format!("{}.txt", actual_aide_name));
+        let timestamps = self.aide_setting_flag(aide_name, "timestamp")?;
+        let entry_format = self.entry_format()?;
+        for entry in self.get_aide_entries(aide_name)? {
+            // Plain entries carry the timestamp they were written with as a "[...] " prefix
+            let stamp = entry.command_output
+                .strip_prefix('[')
+                .and_then(|rest| rest.split_once("] "))
+                .map(|(stamp, _)| stamp.to_string())
+                .or_else(|| entry.created_at.clone().filter(|_| timestamps));
+            content.push_str(&entry_format.format_entry(stamp.as_deref(), &entry.file_text()));
+        }
+        Ok(content)
+    }
+    
+    // How entries are laid out in aide files (the entry_format config key); an invalid
+    // template falls back to the default "{timestamp}\n* {content}"
+    pub fn entry_format(&self) -> Result<EntryFormat> {
+        Ok(self.get_config_value("entry_format")?
+            .and_then(|template| EntryFormat::parse(&template).ok())
+            .unwrap_or_default())
+    }
+    
+    // Read an aide file back into entries, by the current entry_format. Entries get id 0 and the
+    // timestamp from their timestamp line, if any.
+    pub fn parse_aide_file(&self, path: &Path) -> Result<Vec<DataEntry>> {
+        let text = self.reveal(fs::read_to_string(path)?)?;
+        Ok(parse_entries(&self.entry_format()?, &text))
+    }
+    
+    // An aide's file, as recorded when it was created. A name with no aide gets the path a new
+    // aide of that name would start from.
+    pub fn aide_file(&self, aide_name: &str) -> Result<PathBuf> {
+        let stored: Option<String> = self.conn.query_row(
+            "SELECT file_path FROM aides WHERE name = ?1",
+            [aide_name],
+            |row| row.get(0),
+        ).optional()?.flatten();
+        match stored {
+            Some(path) => Ok(PathBuf::from(path)),
+            None => paths::aide_file(aide_name),
+        }
+    }
+    
+    // The text of an aide: its file when files are written and it exists, otherwise
+    // (or when the file is encrypted) rebuilt from the database
+    pub fn aide_text(&self, aide_name: &str) -> Result<String> {
+        if self.write_txt_files()? && !self.aide_encrypted(aide_name)? {
+            if let Ok(content) = fs::read_to_string(self.aide_file(aide_name)?) {
+                return Ok(content);
+            }
+        }
+        self.render_aide_file(aide_name)
+    }
+    
+    // Whether an aide's entries and file are stored encrypted (`aide encrypt`)
+    pub fn aide_encrypted(&self, aide_name: &str) -> Result<bool> {
+        Ok(self.conn.query_row(
+            "SELECT encrypted FROM aides WHERE name = ?1",
+            [aide_name],
+            |row| row.get(0),
+        ).optional()?.unwrap_or(false))
+    }
+    
+    pub fn has_encrypted_aides(&self) -> Result<bool> {
+        Ok(self.conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM aides WHERE encrypted = 1)",
+            [],
+            |row| row.get(0),
+        )?)
+    }
+    
+    // (recipient, sealed identity), once the first aide has been encrypted
+    fn encryption_key(&self) -> Result<Option<(String, String)>> {
+        Ok(self.conn.query_row(
+            "SELECT recipient, sealed_identity FROM encryption_key WHERE id = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).optional()?)
+    }
+    
+    // The secret key, unsealed with the passphrase on first use. A terminal gets three tries.
+    fn identity(&self) -> Result<&age::x25519::Identity> {
+        if let Some(identity) = self.identity.get() {
+            return Ok(identity);
+        }
+        let Some((_, sealed)) = self.encryption_key()? else {
+            bail!("Found encrypted data but no encryption key in the database.");
+        };
+        if crate::ui::is_tui_active() {
+            bail!("Encrypted aides are locked. Restart the TUI to enter the passphrase.");
+        }
         
-        // Append to existing file or create new one with better formatting
-        let existing_content = if file_path.exists() {
-            fs::read_to_string(&file_path)?
+        let attempts = if std::env::var_os(crypto::PASSPHRASE_ENV).is_some() { 1 } else { 3 };
+        let mut attempt = 1;
+        loop {
+            let passphrase = crypto::read_passphrase("Passphrase for encrypted aides: ")?;
+            match crypto::unseal_identity(&sealed, &passphrase) {
+                Ok(identity) => return Ok(self.identity.get_or_init(|| identity)),
+                Err(err) if attempt == attempts => return Err(err),
+                Err(err) => eprintln!("{} Try again.", err),
+            }
+            attempt += 1;
+        }
+    }
+    
+    // Ask for the passphrase now if any aide is encrypted, e.g. before the TUI takes over the terminal
+    pub fn unlock(&self) -> Result<()> {
+        if self.has_encrypted_aides()? {
+            self.identity()?;
+        }
+        Ok(())
+    }
+    
+    // Decrypt text that was stored encrypted; plain text passes through untouched
+    fn reveal(&self, text: String) -> Result<String> {
+        if crypto::is_encrypted(&text) {
+            crypto::decrypt(self.identity()?, &text)
         } else {
-            format!("# {}\n\nCreated: {}\n\n", 
-                   actual_aide_name, 
-                   chrono::Utc::now().format("%Y-%m-%d %H:%M:%S"))
+            Ok(text)
+        }
+    }
+    
+    // The same for entries joined by GROUP_CONCAT
+    fn reveal_joined(&self, joined: &str) -> Result<String> {
+        let parts = joined
+            .split("|||")
+            .map(|part| self.reveal(part.to_string()))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(parts.join("|||"))
+    }
+    
+    // Text as it's stored for this aide: encrypted if the aide is. Needs no passphrase.
+    fn seal(&self, aide_name: &str, text: &str) -> Result<String> {
+        if !self.aide_encrypted(aide_name)? {
+            return Ok(text.to_string());
+        }
+        let Some((recipient, _)) = self.encryption_key()? else {
+            bail!("Aide '{}' is marked encrypted but there is no encryption key in the database.", aide_name);
+        };
+        crypto::encrypt(&recipient, text)
+    }
+    
+    // Encrypted files can't be appended to or patched, so rewrite the whole file from the rows
+    fn sync_encrypted_file(&self, aide_name: &str) -> Result<()> {
+        if !self.aide_encrypted(aide_name)? || !self.write_txt_files()? {
+            return Ok(());
+        }
+        let file_path = self.aide_file(aide_name)?;
+        paths::create_parent_dir(&file_path)?;
+        fs::write(&file_path, self.seal(aide_name, &self.render_aide_file(aide_name)?)?)?;
+        Ok(())
+    }
+    
+    // Turn encryption on or off for an aide (`aide encrypt` / `aide decrypt`), converting its
+    // rows and file. The first `aide encrypt` sets the passphrase.
+    pub fn set_aide_encryption(&mut self, aide_name: &str, encrypt: bool) -> Result<Outcome> {
+        let actual_aide_name = match self.resolve_aide_name(aide_name)? {
+            Some(name) => name,
+            None => return Ok(Outcome::NotFound),
         };
+        if self.aide_encrypted(&actual_aide_name)? == encrypt {
+            emit!("Aide '{}' is already {}", actual_aide_name, if encrypt { "encrypted" } else { "unencrypted" });
+            return Ok(Outcome::Done);
+        }
+        
+        if encrypt && self.encryption_key()?.is_none() {
+            let passphrase = crypto::read_passphrase("New passphrase for encrypted aides: ")?;
+            if passphrase.is_empty() {
+                eprintln!("The passphrase can't be empty.");
+                return Ok(Outcome::Invalid);
+            }
+            if std::env::var_os(crypto::PASSPHRASE_ENV).is_none()
+                && crypto::read_passphrase("Repeat the passphrase: ")? != passphrase {
+                eprintln!("The passphrases don't match.");
+                return Ok(Outcome::Invalid);
+            }
+            let (recipient, sealed) = crypto::generate_key(&passphrase)?;
+            self.conn.execute(
+                "INSERT INTO encryption_key (id, recipient, sealed_identity) VALUES (1, ?1, ?2)",
+                [&recipient, &sealed],
+            )?;
+            emit!("Created the encryption key. Encrypted aides can't be read without this passphrase.");
+        }
         
-        // Use the new format: date time\n* input
-        let new_entry = format!("{}\n* {}\n", timestamp, content);
-        let updated_content = format!("{}{}", existing_content, new_entry);
-        fs::write(&file_path, updated_content)?;
-        println!("Data appended to file: {}", file_path.display());
+        // Everything is read (and decrypted) up front, then rewritten in one transaction
+        let entries = self.get_aide_entries(&actual_aide_name)?;
+        let recipient = self.encryption_key()?.map(|(recipient, _)| recipient).unwrap_or_default();
+        let store = |text: &str| if encrypt { crypto::encrypt(&recipient, text) } else { Ok(text.to_string()) };
+        let rows = entries
+            .iter()
+            .map(|entry| Ok((entry.id, store(&entry.input_text)?, store(&entry.command_output)?)))
+            .collect::<Result<Vec<_>>>()?;
+        
+        let tx = self.conn.transaction()?;
+        for (id, input_text, command_output) in &rows {
+            tx.execute(
+                "UPDATE data SET input_text = ?1, command_output = ?2 WHERE id = ?3",
+                rusqlite::params![input_text, command_output, id],
+            )?;
+        }
+        tx.execute("UPDATE aides SET encrypted = ?1 WHERE name = ?2", rusqlite::params![encrypt, actual_aide_name])?;
+        tx.commit()?;
+        
+        if self.write_txt_files()? {
+            let file_path = self.aide_file(&actual_aide_name)?;
+            paths::create_parent_dir(&file_path)?;
+            fs::write(&file_path, self.seal(&actual_aide_name, &self.render_aide_file(&actual_aide_name)?)?)?;
+        }
+        
+        emit!("Aide '{}' is now {} ({} entr{})", actual_aide_name,
+              if encrypt { "encrypted" } else { "unencrypted" },
+              rows.len(), if rows.len() == 1 { "y" } else { "ies" });
+        Ok(Outcome::Done)
+    }
+    
+    // Read a boolean per-aide setting, falling back to its default from AIDE_SETTINGS
+    fn aide_setting_flag(&self, aide_name: &str, key: &str) -> Result<bool> {
+        let default = AIDE_SETTINGS.iter().find(|(name, ..)| *name == key).is_none_or(|(_, default, _)| *default);
+        let value: Option<String> = self.conn.query_row(
+            "SELECT s.value FROM aide_settings s JOIN aides a ON s.aide_id = a.id
+             WHERE a.name = ?1 AND s.key_name = ?2",
+            [aide_name, key],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(value.and_then(|v| parse_flag(&v)).unwrap_or(default))
+    }
+    
+    // Show or change an aide's settings (`aide aide-config <name> [<key> <on|off>]`)
+    pub fn configure_aide(&mut self, aide_name: &str, key: Option<&str>, value: Option<&str>) -> Result<Outcome> {
+        let actual_aide_name = match self.resolve_aide_name(aide_name)? {
+            Some(name) => name,
+            None => return Ok(Outcome::NotFound),
+        };
+        
+        let (Some(key), Some(value)) = (key, value) else {
+            println!("Settings for aide '{}':", actual_aide_name);
+            for (name, _, description) in AIDE_SETTINGS {
+                let state = if self.aide_setting_flag(&actual_aide_name, name)? { "on" } else { "off" };
+                println!("  {} = {}  ({})", name, state, description);
+            }
+            return Ok(Outcome::Done);
+        };
+        
+        if !AIDE_SETTINGS.iter().any(|(name, ..)| *name == key) {
+            let known: Vec<&str> = AIDE_SETTINGS.iter().map(|(name, ..)| *name).collect();
+            eprintln!("Unknown aide setting '{}'. Available: {}", key, known.join(", "));
+            return Ok(Outcome::Invalid);
+        }
+        let Some(enabled) = parse_flag(value) else {
+            eprintln!("Invalid value '{}'. Use on or off.", value);
+            return Ok(Outcome::Invalid);
+        };
         
-        // Store in database
         self.conn.execute(
-            "INSERT INTO data (aide_id, input_text, command_output) VALUES (?1, ?2, ?3)",
-            [&aide_id.to_string(), &content, &timestamped_data],
+            "INSERT INTO aide_settings (aide_id, key_name, value)
+             SELECT id, ?2, ?3 FROM aides WHERE name = ?1
+             ON CONFLICT (aide_id, key_name) DO UPDATE SET value = excluded.value",
+            [actual_aide_name.as_str(), key, if enabled { "on" } else { "off" }],
         )?;
+        emit!("Aide '{}': {} = {}", actual_aide_name, key, if enabled { "on" } else { "off" });
+        Ok(Outcome::Done)
+    }
+    
+    // Run a shell command and store the command and its captured output as an entry
+    pub fn add_command_output(&mut self, name: &str, command: &str, assume_yes: bool) -> Result<Outcome> {
+        let actual_aide_name = match self.resolve_aide_name(name)? {
+            Some(name) => name,
+            None => return Ok(Outcome::NotFound),
+        };
         
-        if path.is_some() {
-            println!("File content added successfully to aide '{}'", actual_aide_name);
+        if !assume_yes {
+            eprint!("Run `{}` and store its output in aide '{}'? (y/n): ", command, actual_aide_name);
+            io::stderr().flush()?;
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            let answer = input.trim().to_lowercase();
+            if answer != "y" && answer != "yes" {
+                eprintln!("Operation cancelled.");
+                return Ok(Outcome::Cancelled);
+            }
+        }
+        
+        let output = if cfg!(windows) {
+            Command::new("cmd").args(["/C", command]).output()
         } else {
-            println!("Data added successfully to aide '{}'", actual_aide_name);
+            Command::new("sh").args(["-c", command]).output()
+        };
+        let output = match output {
+            Ok(output) => output,
+            Err(e) => {
+                eprintln!("Failed to run command '{}': {}", command, e);
+                return Ok(Outcome::Failed);
+            }
+        };
+        
+        let mut captured = String::from_utf8_lossy(&output.stdout).trim_end().to_string();
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let code = output.status.code().map(|c| c.to_string()).unwrap_or_else(|| "signal".to_string());
+            eprintln!("Command exited with status {}", code);
+            if !stderr.trim().is_empty() {
+                captured.push_str(&format!("\n{}", stderr.trim_end()));
+            }
+            captured.push_str(&format!("\n[exit status: {}]", code));
         }
-        Ok(())
+        
+        let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let file_text = format!("$ {}\n{}", command, captured);
+        if !self.store_aide_entry(&actual_aide_name, &timestamp, command, &captured, &file_text)? {
+            return Ok(Outcome::NotFound);
+        }
+        emit!("Command output added successfully to aide '{}'", actual_aide_name);
+        Ok(Outcome::Done)
     }
     
-    pub fn search_by_input(&self, input_text: &str) -> Result<()> {
+    // Fuzzy-search entry inputs. By default prints the best match; `count` reports how many
+    // entries scored at least `min_score`, and `top` lists the K best with their scores.
+    // `aide` limits the search to one (fuzzy-resolved) aide.
+    pub fn search_by_input(&self, input_text: &str, aide: Option<&str>, count: bool, top: Option<usize>, min_score: i64) -> Result<Outcome> {
+        let aide_filter = match aide {
+            Some(aide) => match self.resolve_aide_name(aide)? {
+                Some(name) => Some(name),
+                None => return Ok(Outcome::NotFound),
+            },
+            None => None,
+        };
+        
+        // Encrypted aides are only searched when named, so a plain search never asks for the passphrase
         let mut stmt = self.conn.prepare(
             "SELECT d.input_text, d.command_output, a.name 
              FROM data d 
-             JOIN aides a ON d.aide_id = a.id"
+             JOIN aides a ON d.aide_id = a.id
+             WHERE (?1 IS NULL AND a.encrypted = 0) OR a.name = ?1"
         )?;
         
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map([&aide_filter], |row| {
             Ok((
                 row.get::<_, String>(0)?,  // input_text
                 row.get::<_, String>(1)?,  // command_output
@@ -333,319 +1524,1099 @@ impl Database {
         })?;
         
         let matcher = fuzzy_matcher::skim::SkimMatcherV2::default();
-        let mut best_match: Option<(i64, String, String, String)> = None;
+        let mut matches: Vec<(i64, String, String, String)> = Vec::new();
         
         for row in rows {
             let (db_input, output, name) = row?;
+            let (db_input, output) = (self.reveal(db_input)?, self.reveal(output)?);
             if let Some(score) = matcher.fuzzy_match(&db_input, input_text) {
-                if best_match.is_none() || score > best_match.as_ref().unwrap().0 {
-                    best_match = Some((score, db_input, output, name));
+                if score >= min_score {
+                    matches.push((score, db_input, output, name));
+                }
+            }
+        }
+        // Stable sort, so equal scores keep insertion order
+        matches.sort_by_key(|m| std::cmp::Reverse(m.0));
+        
+        if count || top.is_some() {
+            if count {
+                println!("{} match{} for '{}'", matches.len(),
+                         if matches.len() == 1 { "" } else { "es" }, input_text);
+            }
+            if let Some(top) = top {
+                for (rank, (score, matched_input, _output, name)) in matches.iter().take(top).enumerate() {
+                    println!("{:>3}. [{:>4}] {}: {}", rank + 1, score, name, matched_input);
+                }
+                if matches.is_empty() && !count {
+                    eprintln!("No matches found for '{}'", input_text);
                 }
             }
+            return Ok(Outcome::Done);
         }
         
-        match best_match {
+        match matches.into_iter().next() {
             Some((_score, matched_input, output, name)) => {
                 println!("Found match in aide '{}': {}", name, matched_input);
                 println!("Output: {}", output);
             }
             None => {
-                println!("No matches found for '{}'", input_text);
+                eprintln!("No matches found for '{}'", input_text);
             }
         }
         
-        Ok(())
+        Ok(Outcome::Done)
     }
     
-    pub fn search_by_command(&self, input_text: &str) -> Result<()> {
+    // Every entry whose input or output matches the pattern, listed with its aide and 1-based
+    // entry number (as taken by entry-delete)
+    pub fn search_by_regex(&self, pattern: &str, aide: Option<&str>, count: bool, top: Option<usize>) -> Result<Outcome> {
+        let regex = match regex::Regex::new(pattern) {
+            Ok(regex) => regex,
+            Err(err) => {
+                eprintln!("Error: Invalid regular expression: {}", err);
+                return Ok(Outcome::Invalid);
+            }
+        };
+        let aide_filter = match aide {
+            Some(aide) => match self.resolve_aide_name(aide)? {
+                Some(name) => Some(name),
+                None => return Ok(Outcome::NotFound),
+            },
+            None => None,
+        };
+        
         let mut stmt = self.conn.prepare(
             "SELECT d.input_text, d.command_output, a.name 
              FROM data d 
-             JOIN aides a ON d.aide_id = a.id"
+             JOIN aides a ON d.aide_id = a.id
+             WHERE (?1 IS NULL AND a.encrypted = 0) OR a.name = ?1
+             ORDER BY a.name, d.id"
         )?;
-        
-        let rows = stmt.query_map([], |row| {
-            Ok((
-                row.get::<_, String>(0)?,  // input_text
-                row.get::<_, String>(1)?,  // command_output
-                row.get::<_, String>(2)?,  // name
-            ))
+        let rows = stmt.query_map([&aide_filter], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
         })?;
         
-        let matcher = fuzzy_matcher::skim::SkimMatcherV2::default();
-        let mut best_match: Option<(i64, String, String, String)> = None;
-        
+        let mut matches: Vec<(String, usize, String)> = Vec::new();
+        let mut current_aide = String::new();
+        let mut index = 0;
         for row in rows {
             let (db_input, output, name) = row?;
-            let search_text = format!("{} {}", name, db_input);
-            if let Some(score) = matcher.fuzzy_match(&search_text, input_text) {
-                if best_match.is_none() || score > best_match.as_ref().unwrap().0 {
-                    best_match = Some((score, db_input, output, name));
-                }
+            if name != current_aide {
+                current_aide = name.clone();
+                index = 0;
+            }
+            index += 1;
+            let (db_input, output) = (self.reveal(db_input)?, self.reveal(output)?);
+            if regex.is_match(&db_input) || regex.is_match(&output) {
+                matches.push((name, index, db_input));
             }
         }
         
-        match best_match {
-            Some((_score, matched_input, output, name)) => {
-                println!("Found match in aide '{}': {}", name, matched_input);
-                println!("Output: {}", output);
+        if count {
+            println!("{} match{} for /{}/", matches.len(), if matches.len() == 1 { "" } else { "es" }, pattern);
+            if top.is_none() {
+                return Ok(Outcome::Done);
             }
-            None => {
-                println!("No matches found for '{}'", input_text);
+        }
+        if matches.is_empty() {
+            if !count {
+                eprintln!("No matches found for /{}/", pattern);
+            }
+            return Ok(Outcome::Done);
+        }
+        for (name, index, matched_input) in matches.iter().take(top.unwrap_or(usize::MAX)) {
+            println!("{} #{}: {}", name, index, matched_input);
+        }
+        
+        Ok(Outcome::Done)
+    }
+    
+    // Fuzzy-match every line of every task log, listing the tasks with the best match first
+    // and their matching lines (numbered) underneath. Logs that can't be read are skipped.
+    pub fn search_task_logs(&self, text: &str, min_score: i64) -> Result<Outcome> {
+        let mut stmt = self.conn.prepare("SELECT name, task_log_file_path FROM tasks ORDER BY name")?;
+        let tasks = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        let matcher = fuzzy_matcher::skim::SkimMatcherV2::default();
+        // (best score, task name, [(line number, line)])
+        let mut matches = Vec::new();
+        let mut unreadable = 0;
+        for (name, log_path) in tasks {
+            let Ok(content) = fs::read_to_string(&log_path) else {
+                unreadable += 1;
+                continue;
+            };
+            let mut best = None;
+            let mut lines = Vec::new();
+            for (number, line) in content.lines().enumerate() {
+                let Some(score) = matcher.fuzzy_match(line, text).filter(|&score| score >= min_score) else {
+                    continue;
+                };
+                best = best.max(Some(score));
+                lines.push((number + 1, line.trim().chars().take(TASK_SEARCH_SNIPPET_CHARS).collect::<String>()));
+            }
+            if let Some(best) = best {
+                matches.push((best, name, lines));
             }
         }
+        // Stable sort, so equal scores stay in name order
+        matches.sort_by_key(|m| std::cmp::Reverse(m.0));
         
-        Ok(())
+        if unreadable > 0 {
+            emit!("Skipped {} task{} whose log file is missing or unreadable",
+                  unreadable, if unreadable == 1 { "" } else { "s" });
+        }
+        if matches.is_empty() {
+            eprintln!("No task logs match '{}'", text);
+            return Ok(Outcome::Done);
+        }
+        for (_score, name, lines) in &matches {
+            println!("{} ({} matching line{})", name, lines.len(), if lines.len() == 1 { "" } else { "s" });
+            for (number, line) in lines.iter().take(TASK_SEARCH_LINES_PER_TASK) {
+                println!("  {:>4}: {}", number, line);
+            }
+            if lines.len() > TASK_SEARCH_LINES_PER_TASK {
+                println!("        ... {} more", lines.len() - TASK_SEARCH_LINES_PER_TASK);
+            }
+        }
+        
+        Ok(Outcome::Done)
     }
     
     // Updated functions with TF-IDF fuzzy matching
     
-    pub fn create_task(&mut self, task_name: &str) -> Result<()> {
-        // Use fuzzy matching to check for similar tasks
-        let fuzzy_result = self.find_fuzzy_task_match(task_name)?;
+    pub fn create_task(&mut self, task_name: &str, editor_override: Option<&str>) -> Result<Outcome> {
+        if let Err(reason) = validate_name("Task", task_name) {
+            eprintln!("{}", reason);
+            return Ok(Outcome::Invalid);
+        }
         
-        let actual_task_name = match fuzzy_result {
-            FuzzyMatchResult { exact_match: true, .. } => {
-                println!("Task '{}' already exists. Opening task log file...", task_name);
-                task_name.to_string()
-            }
-            FuzzyMatchResult { suggested_name: Some(suggestion), score: Some(score), .. } => {
-                if score >= FUZZY_MATCH_THRESHOLD {
-                    if ask_user_confirmation(task_name, &suggestion) {
-                        println!("Opening existing task '{}'...", suggestion);
-                        suggestion
-                    } else {
-                        // User wants to create new task with original name
-                        task_name.to_string()
-                    }
+        // An existing task (or a near match the user accepts) opens as typed. Only a new task
+        // gets its priority, and possibly its name, from priority_keywords.
+        let (task_log_file, created, keyword_priority) = match self.confirm_match(task_name, self.find_fuzzy_task_match(task_name)?) {
+            Ok(existing) => {
+                if existing == task_name {
+                    emit!("Task '{}' already exists. Opening task log file...", existing);
                 } else {
-                    task_name.to_string()
+                    emit!("Opening existing task '{}'...", existing);
+                }
+                let (task_log_file, _) = self.create_task_no_editor(&existing, None)?;
+                (task_log_file, None, None)
+            }
+            Err(_) => {
+                let (new_name, keyword_priority) = self.priority_from_keywords(task_name)?;
+                let (task_log_file, created) = self.create_task_no_editor(&new_name, keyword_priority)?;
+                if !created {
+                    emit!("Task '{}' already exists. Opening task log file...", new_name);
                 }
+                (task_log_file, created.then_some(new_name), keyword_priority)
             }
-            _ => task_name.to_string()
         };
-        
-        // Create tasks directory if it doesn't exist
-        let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let tasks_dir = PathBuf::from(&home_dir).join(".aide").join("tasks");
-        fs::create_dir_all(&tasks_dir)?;
-        
-        // Create task log file path
-        let task_log_file = tasks_dir.join(format!("{}.txt", actual_task_name));
-        
-        // Check if task already exists in database
-        let exists = self.conn.query_row(
-            "SELECT 1 FROM tasks WHERE name = ?1",
-            [&actual_task_name],
-            |_| Ok(()),
-        );
-        
-        if exists.is_err() {
-            // Create new task
-            self.conn.execute(
-                "INSERT INTO tasks (name, priority, status, task_log_file_path) VALUES (?1, 3, 'created', ?2)",
-                [&actual_task_name, &task_log_file.to_string_lossy().to_string()],
-            )?;
-            
-            // Create initial task log content
-            let initial_content = format!(
-                "Task: {}\nStatus: created\nPriority: 3\nCreated: {}\n\n--- Task Log ---\n",
-                actual_task_name,
-                chrono::Utc::now().format("%Y-%m-%d %H:%M:%S")
-            );
-            
-            fs::write(&task_log_file, initial_content)?;
-            println!("Task '{}' created successfully!", actual_task_name);
-            
-            // Use incremental update instead of full rebuild
-            if let Some(ref mut index) = self.task_index {
-                index.add_entity(actual_task_name.to_string())?;
+        if let Some(new_name) = created {
+            emit!("Task '{}' created successfully!", new_name);
+            if let Some(priority) = keyword_priority {
+                emit!("Priority set to {} from priority_keywords", priority_label(priority as i32));
             }
         }
         
         // Open the task log file in editor
-        let status = Command::new("vi")
-            .arg(&task_log_file)
-            .status();
+        let editor = self.editor_command(editor_override)?;
+        let status = open_in_editor(&editor, Path::new(&task_log_file));
         
         match status {
             Ok(exit_status) => {
                 if !exit_status.success() {
-                    println!("Editor exited with status: {:?}", exit_status);
+                    eprintln!("Editor exited with status: {:?}", exit_status);
                 }
             }
             Err(e) => {
-                println!("Failed to open vi editor: {}", e);
-                println!("Task log file is at: {}", task_log_file.display());
+                eprintln!("Failed to open {} editor: {}", editor, e);
+                eprintln!("Task log file is at: {}", task_log_file.display());
+                return Ok(Outcome::Failed);
             }
         }
         
-        Ok(())
+        Ok(Outcome::Done)
     }
     
-    pub fn update_task_status(&self, task_name: &str, status: &str) -> Result<()> {
-        let valid_statuses = ["created", "in_progress", "completed"];
-        if !valid_statuses.contains(&status) {
-            println!("Invalid status. Valid statuses are: created, in_progress, completed");
-            return Ok(());
-        }
-        
-        // Use fuzzy matching to find the task
-        let fuzzy_result = self.find_fuzzy_task_match(task_name)?;
+    // Log file recorded for a task, or None if there is no such task
+    pub fn task_log_path(&self, task_name: &str) -> Result<Option<PathBuf>> {
+        let path: Option<String> = self.conn.query_row(
+            "SELECT task_log_file_path FROM tasks WHERE name = ?1",
+            [task_name],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(path.map(PathBuf::from))
+    }
+    
+    // Priority implied by keywords in a new task's name, from the priority_keywords config key
+    // (e.g. "urgent=1,soon=2"; the most urgent match wins). With strip_priority_keywords on,
+    // the matched words are dropped from the name unless nothing else would be left.
+    pub fn priority_from_keywords(&self, task_name: &str) -> Result<(String, Option<u8>)> {
+        let Some(mapping) = self.get_config_value("priority_keywords")? else {
+            return Ok((task_name.to_string(), None));
+        };
+        let keywords: Vec<(String, u8)> = mapping
+            .split(',')
+            .filter_map(|pair| pair.split_once('='))
+            .filter_map(|(word, priority)| {
+                let priority = parse_priority(priority).filter(|p| (1..=5).contains(p))?;
+                Some((word.trim().to_lowercase(), priority))
+            })
+            .filter(|(word, _)| !word.is_empty())
+            .collect();
         
-        let actual_task_name = match fuzzy_result {
-            FuzzyMatchResult { exact_match: true, suggested_name: Some(name), .. } => name,
-            FuzzyMatchResult { suggested_name: Some(suggestion), score: Some(score), .. } => {
-                if score >= FUZZY_MATCH_THRESHOLD {
-                    if ask_user_confirmation(task_name, &suggestion) {
-                        suggestion
-                    } else {
-                        println!("Operation cancelled.");
-                        return Ok(());
-                    }
-                } else {
-                    println!("Task '{}' not found.", task_name);
-                    return Ok(());
-                }
-            }
-            _ => {
-                println!("Task '{}' not found.", task_name);
-                return Ok(());
+        let mut priority: Option<u8> = None;
+        let mut kept = Vec::new();
+        for word in task_name.split_whitespace() {
+            // "URGENT:" and "[urgent]" count as the keyword too
+            let bare = word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+            match keywords.iter().find(|(keyword, _)| *keyword == bare) {
+                Some((_, p)) => priority = Some(priority.map_or(*p, |current| current.min(*p))),
+                None => kept.push(word),
             }
+        }
+        
+        let strip = self.get_config_flag("strip_priority_keywords", false)?;
+        let name = if strip && priority.is_some() && !kept.is_empty() {
+            kept.join(" ")
+        } else {
+            task_name.to_string()
         };
+        Ok((name, priority))
+    }
+    
+    // Create a task and its log file without opening an editor or prompting, at the given
+    // priority (default 3). Returns the log file path and whether the task was newly created.
+    pub fn create_task_no_editor(&mut self, task_name: &str, priority: Option<u8>) -> Result<(PathBuf, bool)> {
+        // Existing tasks keep the log file recorded when they were created
+        if let Some(task_log_file) = self.task_log_path(task_name)? {
+            return Ok((task_log_file, false));
+        }
         
-        let rows_affected = self.conn.execute(
-            "UPDATE tasks SET status = ?1 WHERE name = ?2",
-            [status, &actual_task_name],
-        )?;
+        // Create tasks directory if it doesn't exist
+        fs::create_dir_all(paths::tasks_dir()?)?;
         
-        if rows_affected == 0 {
-            println!("Task '{}' not found in database", actual_task_name);
-        } else {
-            println!("Task '{}' status updated to '{}'", actual_task_name, status);
+        // Sanitized names can collide ("a/b" and "a_b"), so pick a file no other task uses
+        let mut task_log_file = paths::task_file(task_name)?;
+        let stem = paths::file_stem(task_name)?;
+        let mut suffix = 1;
+        while task_log_file.exists() || self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM tasks WHERE task_log_file_path = ?1)",
+            [task_log_file.to_string_lossy().as_ref()],
+            |row| row.get::<_, bool>(0),
+        )? {
+            suffix += 1;
+            task_log_file = paths::tasks_dir()?.join(format!("{}-{}.txt", stem, suffix));
         }
+
+        // Create new task
+        let priority = priority.unwrap_or(3);
+        self.conn.execute(
+            "INSERT INTO tasks (name, priority, status, task_log_file_path, position)
+             VALUES (?1, ?2, 'created', ?3, (SELECT COALESCE(MAX(position), 0) + 1 FROM tasks))",
+            rusqlite::params![task_name, priority, task_log_file.to_string_lossy()],
+        )?;
         
-        Ok(())
+        // Create initial task log content
+        let initial_content = format!(
+            "Task: {}\nStatus: created\nPriority: {}\nCreated: {}\n\n--- Task Log ---\n",
+            task_name,
+            priority,
+            chrono::Utc::now().format("%Y-%m-%d %H:%M:%S")
+        );
+        
+        fs::write(&task_log_file, initial_content)?;
+        
+        // Use incremental update instead of full rebuild
+        if let Some(ref mut index) = self.task_index {
+            index.add_entity(task_name.to_string())?;
+        }
+
+        Ok((task_log_file, true))
     }
     
-    pub fn update_task_priority(&self, task_name: &str, priority: u8) -> Result<()> {
-        if priority < 1 || priority > 5 {
-            println!("Invalid priority. Priority must be between 1 (highest) and 5 (lowest)");
-            return Ok(());
+    pub fn update_task_status(&self, task_name: &str, status: &str) -> Result<Outcome> {
+        let status = normalize_status(status);
+        let status = status.as_str();
+        if !TASK_STATUSES.contains(&status) {
+            eprintln!("Invalid status. Valid statuses are: created, in_progress, completed");
+            return Ok(Outcome::Invalid);
         }
         
-        // Use fuzzy matching to find the task
-        let fuzzy_result = self.find_fuzzy_task_match(task_name)?;
+        let Some(actual_task_name) = self.resolve_task_name(task_name)? else {
+            return Ok(Outcome::NotFound);
+        };
         
-        let actual_task_name = match fuzzy_result {
-            FuzzyMatchResult { exact_match: true, suggested_name: Some(name), .. } => name,
-            FuzzyMatchResult { suggested_name: Some(suggestion), score: Some(score), .. } => {
-                if score >= FUZZY_MATCH_THRESHOLD {
-                    if ask_user_confirmation(task_name, &suggestion) {
-                        suggestion
-                    } else {
-                        println!("Operation cancelled.");
-                        return Ok(());
-                    }
-                } else {
-                    println!("Task '{}' not found.", task_name);
-                    return Ok(());
-                }
+        if status == "completed" {
+            let blockers = self.task_blockers(&actual_task_name)?;
+            if !blockers.is_empty() {
+                eprintln!("Task '{}' can't be completed before: {}", actual_task_name, blockers.join(", "));
+                return Ok(Outcome::Blocked);
             }
-            _ => {
-                println!("Task '{}' not found.", task_name);
-                return Ok(());
+        }
+        
+        let current: Option<(String, String)> = self.conn.query_row(
+            "SELECT status, task_log_file_path FROM tasks WHERE name = ?1",
+            [&actual_task_name],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).optional()?;
+        
+        let (old_status, task_log_file) = match current {
+            Some(current) => current,
+            None => {
+                eprintln!("Task '{}' not found in database", actual_task_name);
+                return Ok(Outcome::NotFound);
             }
         };
         
-        let rows_affected = self.conn.execute(
-            "UPDATE tasks SET priority = ?1 WHERE name = ?2",
-            [&priority.to_string(), &actual_task_name],
-        )?;
-        
-        if rows_affected == 0 {
-            println!("Task '{}' not found in database", actual_task_name);
-        } else {
-            println!("Task '{}' priority updated to {}", actual_task_name, priority);
+        // Optional confirmation; skipped inside the TUI, which can't prompt on stdin
+        if self.get_config_flag("confirm_status_changes", false)? && !crate::ui::is_tui_active() {
+            eprint!("Change '{}' from '{}' to '{}'? (y/n): ", actual_task_name, old_status, status);
+            io::stderr().flush()?;
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            let answer = input.trim().to_lowercase();
+            if answer != "y" && answer != "yes" {
+                eprintln!("Operation cancelled.");
+                return Ok(Outcome::Cancelled);
+            }
         }
         
-        Ok(())
+        self.conn.execute(
+            "UPDATE tasks SET status = ?1, updated_at = CURRENT_TIMESTAMP WHERE name = ?2",
+            [status, &actual_task_name],
+        )?;
+        emit!("Task '{}' status updated to '{}'", actual_task_name, status);
+        
+        if old_status != status && self.get_config_flag("log_status_changes", false)? {
+            append_task_log_entry(
+                &actual_task_name,
+                &task_log_file,
+                &format!("Status changed from '{}' to '{}'", old_status, status),
+            )?;
+        }
+        
+        Ok(Outcome::Done)
     }
     
-    pub fn list_tasks(&self) -> Result<()> {
+
+    // Move every task matching the filters (current status, priority) to `to` in one
+    // transaction, after a confirmation unless `assume_yes`. Tasks that are still blocked are
+    // left out of a move to completed.
+    pub fn update_task_status_all(&mut self, from: Option<&str>, to: &str, priority: Option<u8>, assume_yes: bool) -> Result<Outcome> {
+        let (from, to) = (from.map(normalize_status), normalize_status(to));
+        let (from, to) = (from.as_deref(), to.as_str());
+        for status in from.into_iter().chain([to]) {
+            if !TASK_STATUSES.contains(&status) {
+                eprintln!("Invalid status '{}'. Valid statuses are: created, in_progress, completed", status);
+                return Ok(Outcome::Invalid);
+            }
+        }
+        
         let mut stmt = self.conn.prepare(
-            "SELECT name, priority, status, created_at FROM tasks ORDER BY priority, created_at"
+            "SELECT name, status, task_log_file_path FROM tasks
+             WHERE (?1 IS NULL OR status = ?1) AND (?2 IS NULL OR priority = ?2) AND status != ?3
+             ORDER BY name",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![from, priority, to], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })?;
+        let mut tasks: Vec<(String, String, String)> = rows.collect::<rusqlite::Result<_>>()?;
+        drop(stmt);
+        
+        if to == "completed" {
+            let mut blocked = Vec::new();
+            for (name, _, _) in &tasks {
+                if !self.task_blockers(name)?.is_empty() {
+                    blocked.push(name.clone());
+                }
+            }
+            if !blocked.is_empty() {
+                eprintln!("Skipping blocked task{}: {}", if blocked.len() == 1 { "" } else { "s" }, blocked.join(", "));
+                tasks.retain(|(name, _, _)| !blocked.contains(name));
+            }
+        }
+        if tasks.is_empty() {
+            eprintln!("No tasks match.");
+            return Ok(Outcome::Done);
+        }
+        
+        if !assume_yes {
+            eprint!("Change {} task{} to '{}'? (y/n): ", tasks.len(), if tasks.len() == 1 { "" } else { "s" }, to);
+            io::stderr().flush()?;
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            let answer = input.trim().to_lowercase();
+            if answer != "y" && answer != "yes" {
+                eprintln!("Operation cancelled.");
+                return Ok(Outcome::Cancelled);
+            }
+        }
+        
+        let tx = self.conn.transaction()?;
+        for (name, _, _) in &tasks {
+            tx.execute(
+                "UPDATE tasks SET status = ?1, updated_at = CURRENT_TIMESTAMP WHERE name = ?2",
+                [to, name],
+            )?;
+        }
+        tx.commit()?;
+        
+        if self.get_config_flag("log_status_changes", false)? {
+            for (name, old_status, task_log_file) in &tasks {
+                append_task_log_entry(name, task_log_file, &format!("Status changed from '{}' to '{}'", old_status, to))?;
+            }
+        }
+        emit!("Changed {} task{} to '{}'", tasks.len(), if tasks.len() == 1 { "" } else { "s" }, to);
+        Ok(Outcome::Done)
+    }
+    pub fn update_task_priority(&self, task_name: &str, priority: u8) -> Result<Outcome> {
+        if !(1..=5).contains(&priority) {
+            eprintln!("Invalid priority. Priority must be between 1 (highest) and 5 (lowest), or one of: {}",
+                      PRIORITY_NAMES.join(", "));
+            return Ok(Outcome::Invalid);
+        }
+        
+        let Some(actual_task_name) = self.resolve_task_name(task_name)? else {
+            return Ok(Outcome::NotFound);
+        };
+        
+        let rows_affected = self.conn.execute(
+            "UPDATE tasks SET priority = ?1, updated_at = CURRENT_TIMESTAMP WHERE name = ?2",
+            [&priority.to_string(), &actual_task_name],
+        )?;
+        
+        if rows_affected == 0 {
+            eprintln!("Task '{}' not found in database", actual_task_name);
+            return Ok(Outcome::NotFound);
+        }
+        emit!("Task '{}' priority updated to {}", actual_task_name, priority_label(priority as i32));
+        
+        Ok(Outcome::Done)
+    }
+    
+    pub fn set_task_estimate(&self, task_name: &str, minutes: u32) -> Result<Outcome> {
+        let Some(actual_task_name) = self.resolve_task_name(task_name)? else {
+            return Ok(Outcome::NotFound);
+        };
+        
+        self.conn.execute(
+            "UPDATE tasks SET estimate_minutes = ?1, updated_at = CURRENT_TIMESTAMP WHERE name = ?2",
+            rusqlite::params![minutes, actual_task_name],
+        )?;
+        emit!("Task '{}' estimated at {}", actual_task_name, format_minutes(i64::from(minutes)));
+        
+        Ok(Outcome::Done)
+    }
+    
+    // Add `minutes` (negative to correct a mistake) to the time spent on a task, never going below zero
+    pub fn log_task_time(&self, task_name: &str, minutes: i64) -> Result<Outcome> {
+        let Some(actual_task_name) = self.resolve_task_name(task_name)? else {
+            return Ok(Outcome::NotFound);
+        };
+        
+        self.conn.execute(
+            "UPDATE tasks SET actual_minutes = MAX(COALESCE(actual_minutes, 0) + ?1, 0),
+                              updated_at = CURRENT_TIMESTAMP
+             WHERE name = ?2",
+            rusqlite::params![minutes, actual_task_name],
         )?;
+        let (actual, estimate): (i64, Option<i64>) = self.conn.query_row(
+            "SELECT actual_minutes, estimate_minutes FROM tasks WHERE name = ?1",
+            [&actual_task_name],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        match estimate {
+            Some(estimate) => emit!("Task '{}': {} spent of {} estimated",
+                                    actual_task_name, format_minutes(actual), format_minutes(estimate)),
+            None => emit!("Task '{}': {} spent", actual_task_name, format_minutes(actual)),
+        }
         
+        Ok(Outcome::Done)
+    }
+    
+    // Estimated vs actual time per task, with the variance (actual minus estimate) and totals
+    pub fn task_stats(&self) -> Result<()> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT name, estimate_minutes, actual_minutes FROM tasks
+             WHERE estimate_minutes IS NOT NULL OR actual_minutes IS NOT NULL {}",
+            self.task_order_clause()?
+        ))?;
         let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?, row.get::<_, Option<i64>>(2)?))
+        })?;
+        
+        println!("Task stats:");
+        println!("-----------");
+        let (mut total_estimate, mut total_actual, mut tracked) = (0, 0, 0);
+        for row in rows {
+            let (name, estimate, actual) = row?;
+            let actual = actual.unwrap_or(0);
+            let variance = match estimate {
+                Some(estimate) => {
+                    // Only tasks with an estimate count toward the totals' variance
+                    total_estimate += estimate;
+                    total_actual += actual;
+                    tracked += 1;
+                    variance_label(estimate, actual)
+                }
+                None => "no estimate".to_string(),
+            };
+            println!("{} | Estimate: {} | Actual: {} | Variance: {}",
+                     name, estimate.map_or("-".to_string(), format_minutes), format_minutes(actual), variance);
+        }
+        
+        if tracked == 0 {
+            println!("No estimated tasks. Set one with `aide task-estimate <task> <minutes>`.");
+        } else {
+            println!();
+            println!("Total ({} estimated task(s)) | Estimate: {} | Actual: {} | Variance: {}",
+                     tracked, format_minutes(total_estimate), format_minutes(total_actual),
+                     variance_label(total_estimate, total_actual));
+        }
+        
+        Ok(())
+    }
+    
+    // Whether task_sort is set to "manual" (otherwise tasks sort by priority)
+    fn manual_task_sort(&self) -> Result<bool> {
+        Ok(self.get_config_value("task_sort")?
+            .is_some_and(|value| value.trim().eq_ignore_ascii_case("manual")))
+    }
+    
+    // ORDER BY clause for task listings, chosen by the task_sort config key.
+    // Pinned tasks always come first.
+    fn task_order_clause(&self) -> Result<&'static str> {
+        Ok(if self.manual_task_sort()? {
+            "ORDER BY pinned DESC, position, created_at"
+        } else {
+            "ORDER BY pinned DESC, priority, created_at"
+        })
+    }
+    
+    // Move a task within the manual ordering, renumbering positions 1..n.
+    // Returns the task's new 1-based position, or None if it doesn't exist.
+    pub fn reposition_task(&mut self, task_name: &str, movement: TaskMove) -> Result<Option<usize>> {
+        let mut names = {
+            let mut stmt = self.conn.prepare("SELECT name FROM tasks ORDER BY position, created_at")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+        
+        let Some(current) = names.iter().position(|name| name == task_name) else {
+            return Ok(None);
+        };
+        let target = match movement {
+            TaskMove::Up => current.saturating_sub(1),
+            TaskMove::Down => current + 1,
+            TaskMove::To(position) => position.saturating_sub(1),
+        }
+        .min(names.len() - 1);
+        
+        let name = names.remove(current);
+        names.insert(target, name);
+        
+        let tx = self.conn.transaction()?;
+        for (i, name) in names.iter().enumerate() {
+            tx.execute(
+                "UPDATE tasks SET position = ?1 WHERE name = ?2",
+                rusqlite::params![i as i64 + 1, name],
+            )?;
+        }
+        tx.commit()?;
+        
+        Ok(Some(target + 1))
+    }
+    
+    pub fn move_task(&mut self, task_name: &str, movement: TaskMove) -> Result<Outcome> {
+        let Some(actual_task_name) = self.resolve_task_name(task_name)? else {
+            return Ok(Outcome::NotFound);
+        };
+        
+        let Some(position) = self.reposition_task(&actual_task_name, movement)? else {
+            return Ok(Outcome::NotFound);
+        };
+        emit!("Task '{}' moved to position {}", actual_task_name, position);
+        if !self.manual_task_sort()? {
+            emit!("Run 'aide set task_sort manual' to list tasks in this order.");
+        }
+        
+        Ok(Outcome::Done)
+    }
+    
+    // Pin or unpin a task or aide so it sorts before everything else.
+    // Without `kind`, an exact task or aide name is used, else the closest fuzzy match of either.
+    pub fn set_pinned(&mut self, name: &str, kind: Option<ItemKind>, pinned: bool) -> Result<Outcome> {
+        let exists = |table: &str| -> Result<bool> {
+            Ok(self.conn.query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE name = ?1)", table),
+                [name],
+                |row| row.get(0),
+            )?)
+        };
+        
+        let target = match kind {
+            Some(ItemKind::Task) => self.resolve_task_name(name)?.map(|n| (ItemKind::Task, n)),
+            Some(ItemKind::Aide) => self.resolve_aide_name(name)?.map(|n| (ItemKind::Aide, n)),
+            None => match (exists("tasks")?, exists("aides")?) {
+                (true, true) => {
+                    eprintln!("'{}' is both a task and an aide. Use --task or --aide.", name);
+                    return Ok(Outcome::Invalid);
+                }
+                (true, false) => Some((ItemKind::Task, name.to_string())),
+                (false, true) => Some((ItemKind::Aide, name.to_string())),
+                (false, false) => {
+                    let task_match = self.find_fuzzy_task_match(name)?;
+                    let aide_match = self.find_fuzzy_aide_match(name)?;
+                    let best = [(ItemKind::Task, task_match), (ItemKind::Aide, aide_match)]
+                        .into_iter()
+                        .filter_map(|(kind, m)| Some((kind, m.suggested_name?, m.score?)))
+                        .filter(|(_, _, score)| *score >= self.fuzzy_threshold)
+                        .min_by(|a, b| rank_order(&a.1, a.2, &b.1, b.2));
+                    match best {
+                        Some((kind, suggestion, score)) => {
+                            if !ask_user_confirmation(name, &suggestion, self.verbose.then_some(score)) {
+                                eprintln!("Operation cancelled.");
+                                return Ok(Outcome::Cancelled);
+                            }
+                            Some((kind, suggestion))
+                        }
+                        None => {
+                            eprintln!("No task or aide named '{}' found.", name);
+                            None
+                        }
+                    }
+                }
+            },
+        };
+        let Some((kind, actual_name)) = target else {
+            return Ok(Outcome::NotFound);
+        };
+        
+        let (table, label) = match kind {
+            ItemKind::Task => ("tasks", "Task"),
+            ItemKind::Aide => ("aides", "Aide"),
+        };
+        self.conn.execute(
+            &format!("UPDATE {} SET pinned = ?1 WHERE name = ?2", table),
+            rusqlite::params![pinned, actual_name],
+        )?;
+        emit!("{} '{}' {}", label, actual_name, if pinned { "pinned" } else { "unpinned" });
+        
+        Ok(Outcome::Done)
+    }
+    
+    // Every dependency as (task, task it needs), ordered by name
+    fn task_dependencies(&self) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.name, d.name FROM task_deps
+             JOIN tasks t ON t.id = task_deps.task_id
+             JOIN tasks d ON d.id = task_deps.depends_on_id
+             ORDER BY t.name, d.name",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+    
+    // Names of the tasks `task_name` depends on that aren't completed yet
+    pub fn task_blockers(&self, task_name: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT d.name FROM task_deps
+             JOIN tasks t ON t.id = task_deps.task_id
+             JOIN tasks d ON d.id = task_deps.depends_on_id
+             WHERE t.name = ?1 AND d.status != 'completed'
+             ORDER BY d.name",
+        )?;
+        let rows = stmt.query_map([task_name], |row| row.get::<_, String>(0))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+    
+    // Add (or with `add` false, remove) the dependency of one task on another.
+    // Rejects a dependency that would make a task wait on itself.
+    pub fn set_task_dependency(&mut self, task_name: &str, needs: &str, add: bool) -> Result<Outcome> {
+        let Some(actual_task_name) = self.resolve_task_name(task_name)? else {
+            return Ok(Outcome::NotFound);
+        };
+        let Some(actual_needs) = self.resolve_task_name(needs)? else {
+            return Ok(Outcome::NotFound);
+        };
+        
+        if !add {
+            let removed = self.conn.execute(
+                "DELETE FROM task_deps
+                 WHERE task_id = (SELECT id FROM tasks WHERE name = ?1)
+                   AND depends_on_id = (SELECT id FROM tasks WHERE name = ?2)",
+                [&actual_task_name, &actual_needs],
+            )?;
+            if removed == 0 {
+                eprintln!("Task '{}' doesn't depend on '{}'", actual_task_name, actual_needs);
+                return Ok(Outcome::NotFound);
+            }
+            emit!("Task '{}' no longer needs '{}'", actual_task_name, actual_needs);
+            return Ok(Outcome::Done);
+        }
+        
+        if actual_task_name == actual_needs {
+            eprintln!("A task can't depend on itself.");
+            return Ok(Outcome::Invalid);
+        }
+        // The new edge closes a cycle if `needs` already (transitively) depends on the task
+        let dependencies = self.task_dependencies()?;
+        let mut pending = vec![actual_needs.as_str()];
+        let mut seen = std::collections::HashSet::new();
+        while let Some(current) = pending.pop() {
+            if current == actual_task_name {
+                eprintln!("'{}' already depends on '{}'; adding this would create a cycle.",
+                          actual_needs, actual_task_name);
+                return Ok(Outcome::Invalid);
+            }
+            if seen.insert(current) {
+                pending.extend(dependencies.iter().filter(|(task, _)| task == current).map(|(_, dep)| dep.as_str()));
+            }
+        }
+        
+        self.conn.execute(
+            "INSERT OR IGNORE INTO task_deps (task_id, depends_on_id)
+             SELECT t.id, d.id FROM tasks t, tasks d WHERE t.name = ?1 AND d.name = ?2",
+            [&actual_task_name, &actual_needs],
+        )?;
+        emit!("Task '{}' now needs '{}'", actual_task_name, actual_needs);
+        
+        Ok(Outcome::Done)
+    }
+    
+    // Print each task nobody depends on, with the tasks it needs indented beneath it
+    // The task to do now: the most urgent (then oldest) one that isn't completed and isn't
+    // waiting on an unfinished dependency, with the start of its latest log entry
+    pub fn next_task(&self) -> Result<Outcome> {
+        let next: Option<(String, i32, String, String)> = self.conn.query_row(
+            "SELECT name, priority, status, task_log_file_path FROM tasks t
+             WHERE status != 'completed'
+               AND NOT EXISTS (SELECT 1 FROM task_deps
+                               JOIN tasks d ON d.id = task_deps.depends_on_id
+                               WHERE task_deps.task_id = t.id AND d.status != 'completed')
+             ORDER BY priority, created_at
+             LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        ).optional()?;
+        let Some((name, priority, status, task_log_file)) = next else {
+            eprintln!("No actionable tasks: everything is completed or blocked.");
+            return Ok(Outcome::NotFound);
+        };
+        
+        println!("{}", name);
+        println!("  Priority: {} | Status: {}", priority_label(priority), status);
+        let latest = fs::read_to_string(&task_log_file).ok()
+            .and_then(|content| parse_task_log(&content).pop());
+        if let Some((timestamp, text)) = latest {
+            let first_line = text.lines().next().unwrap_or_default();
+            let snippet: String = first_line.chars().take(NEXT_LOG_SNIPPET_CHARS).collect();
+            let cut = if snippet.len() < text.trim_end().len() { "..." } else { "" };
+            println!("  Latest log [{}]: {}{}", timestamp, snippet, cut);
+        }
+        Ok(Outcome::Done)
+    }
+    
+    pub fn print_task_graph(&self) -> Result<()> {
+        let dependencies = self.task_dependencies()?;
+        if dependencies.is_empty() {
+            println!("No task dependencies. Add one with `aide task-dep <task> --needs <other>`.");
+            return Ok(());
+        }
+        
+        let statuses: std::collections::HashMap<String, String> = {
+            let mut stmt = self.conn.prepare("SELECT name, status FROM tasks")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        let mut roots: Vec<&str> = dependencies.iter()
+            .map(|(task, _)| task.as_str())
+            .filter(|task| !dependencies.iter().any(|(_, dep)| dep == task))
+            .collect();
+        roots.dedup();
+        
+        fn print_node(name: &str, depth: usize, dependencies: &[(String, String)],
+                      statuses: &std::collections::HashMap<String, String>) {
+            let marker = if depth == 0 { "" } else { "└─ " };
+            let status = statuses.get(name).map(String::as_str).unwrap_or_default();
+            println!("{}{}{} [{}]", "   ".repeat(depth.saturating_sub(1)), marker, name, status);
+            for (_, dep) in dependencies.iter().filter(|(task, _)| task == name) {
+                print_node(dep, depth + 1, dependencies, statuses);
+            }
+        }
+        for root in roots {
+            print_node(root, 0, &dependencies, &statuses);
+        }
+        
+        Ok(())
+    }
+    
+    pub fn list_tasks(&self, limit: Option<u32>, offset: u32, format: TaskListFormat, created: Option<CreatedWithin>) -> Result<()> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT name, priority, status, created_at, updated_at, position, task_log_file_path, pinned
+             FROM tasks
+             WHERE ?3 IS NULL OR created_at >= ?3
+             {}
+             LIMIT ?1 OFFSET ?2",
+            self.task_order_clause()?
+        ))?;
+        
+        // SQLite treats a negative LIMIT as "no limit"
+        let sql_limit = limit.map(i64::from).unwrap_or(-1);
+        let since = created.map(CreatedWithin::start);
+        let rows = stmt.query_map(rusqlite::params![sql_limit, i64::from(offset), since], |row| {
             Ok((
-                row.get::<_, String>(0)?,  // name
-                row.get::<_, i32>(1)?,     // priority
-                row.get::<_, String>(2)?,  // status
-                row.get::<_, String>(3)?,  // created_at
+                row.get::<_, String>(0)?,          // name
+                row.get::<_, i32>(1)?,             // priority
+                row.get::<_, String>(2)?,          // status
+                row.get::<_, String>(3)?,          // created_at
+                row.get::<_, Option<String>>(4)?,  // updated_at
+                row.get::<_, Option<i64>>(5)?,     // position
+                row.get::<_, String>(6)?,          // task_log_file_path
+                row.get::<_, bool>(7)?,            // pinned
             ))
         })?;
         
-        println!("Tasks:");
-        println!("------");
+        if format == TaskListFormat::Detailed {
+            println!("Tasks:");
+            println!("------");
+        }
+        let date_format = self.date_format()?;
+        let mut table = Table::new(&["Name", "Priority", "Status", "Created"]);
         for row in rows {
-            let (name, priority, status, created_at) = row?;
-            println!("{} | Priority: {} | Status: {} | Created: {}", 
-                     name, priority, status, created_at);
+            let (name, priority, status, created_at, updated_at, position, log_path, pinned) = row?;
+            let created_at = format_timestamp(&created_at, &date_format);
+            let name = if pinned { format!("{} {}", PIN_MARKER, name) } else { name };
+            match format {
+                TaskListFormat::Table => {
+                    table.add_row(vec![name, priority_label(priority), status, created_at]);
+                }
+                TaskListFormat::Compact => println!("{}", name),
+                TaskListFormat::Detailed => {
+                    println!("{}", name);
+                    println!("  Priority: {}", priority_label(priority));
+                    println!("  Status:   {}", status);
+                    println!("  Created:  {}", created_at);
+                    println!("  Updated:  {}", updated_at.map_or("-".to_string(), |t| format_timestamp(&t, &date_format)));
+                    println!("  Position: {}", position.map_or("-".to_string(), |p| p.to_string()));
+                    println!("  Log file: {}", log_path);
+                    println!();
+                }
+            }
+        }
+        if format == TaskListFormat::Table {
+            table.print();
         }
         
         Ok(())
     }
     
-    pub fn list_aides(&self) -> Result<()> {
+    // Daily streaks of an aide's entries and a heatmap of entries per day over the last `weeks`
+    // weeks, one column per week (Monday first). Days are taken from entry timestamps (UTC).
+    pub fn print_streak(&self, aide_name: &str, weeks: u32) -> Result<Outcome> {
+        let actual_aide_name = match self.resolve_aide_name(aide_name)? {
+            Some(name) => name,
+            None => return Ok(Outcome::NotFound),
+        };
+        
+        let mut per_day: std::collections::BTreeMap<chrono::NaiveDate, usize> = std::collections::BTreeMap::new();
+        for entry in self.get_aide_entries(&actual_aide_name)? {
+            let day = entry.created_at.as_deref()
+                .and_then(|ts| chrono::NaiveDate::parse_from_str(ts.get(..10)?, "%Y-%m-%d").ok());
+            if let Some(day) = day {
+                *per_day.entry(day).or_default() += 1;
+            }
+        }
+        if per_day.is_empty() {
+            println!("Aide '{}' has no dated entries yet.", actual_aide_name);
+            return Ok(Outcome::Done);
+        }
+        
+        let today = chrono::Utc::now().date_naive();
+        let (current, longest) = day_streaks(per_day.keys().copied(), today);
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        println!("Streak for '{}': current {} day{}, longest {} day{} ({} entries on {} days)",
+                 actual_aide_name, current, plural(current), longest, plural(longest),
+                 per_day.values().sum::<usize>(), per_day.len());
+        
+        use chrono::Datelike;
+        let weeks = weeks.max(1) as i64;
+        let this_monday = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+        let first_monday = this_monday - chrono::Duration::weeks(weeks - 1);
+        println!();
+        println!("Last {} week{} ({} to {})", weeks, plural(weeks as usize), first_monday, today);
+        for (row, label) in ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"].iter().enumerate() {
+            let mut line = format!("{} ", label);
+            for week in 0..weeks {
+                let day = first_monday + chrono::Duration::days(week * 7 + row as i64);
+                let cell = if day > today {
+                    ' '
+                } else {
+                    match per_day.get(&day).copied().unwrap_or(0) {
+                        0 => '·',
+                        1 => '░',
+                        2..=3 => '▒',
+                        _ => '▓',
+                    }
+                };
+                line.push(' ');
+                line.push(cell);
+            }
+            println!("{}", line);
+        }
+        println!("    · none  ░ 1  ▒ 2-3  ▓ 4+");
+        
+        Ok(Outcome::Done)
+    }
+    
+    // Combine task logs into one Markdown report, grouped by task in order of first included
+    // entry. `since` (YYYY-MM-DD) drops older entries; `completed_only` limits it to completed tasks.
+    pub fn task_report(&self, since: Option<&str>, completed_only: bool, out: Option<&str>) -> Result<Outcome> {
+        let since = match since {
+            Some(date) => match chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d") {
+                Ok(date) => Some(date.format("%Y-%m-%d 00:00:00").to_string()),
+                Err(_) => {
+                    eprintln!("Invalid date '{}'. Use YYYY-MM-DD.", date);
+                    return Ok(Outcome::Invalid);
+                }
+            },
+            None => None,
+        };
+        
+        let mut stmt = self.conn.prepare(
+            "SELECT name, status, priority, task_log_file_path FROM tasks
+             WHERE ?1 = 0 OR status = 'completed'
+             ORDER BY created_at"
+        )?;
+        let rows = stmt.query_map([completed_only], |row| {
+            Ok((
+                row.get::<_, String>(0)?,  // name
+                row.get::<_, String>(1)?,  // status
+                row.get::<_, i32>(2)?,     // priority
+                row.get::<_, String>(3)?,  // task_log_file_path
+            ))
+        })?;
+        
+        let mut sections = Vec::new();
+        for row in rows {
+            let (name, status, priority, log_path) = row?;
+            let content = fs::read_to_string(&log_path).unwrap_or_default();
+            let mut entries: Vec<(String, String)> = parse_task_log(&content)
+                .into_iter()
+                .filter(|(ts, _)| since.as_ref().is_none_or(|since| ts >= since))
+                .collect();
+            // Timestamps are fixed-width, so string order is chronological
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            if since.is_some() && entries.is_empty() {
+                continue;
+            }
+            sections.push((name, status, priority, entries));
+        }
+        // Tasks without entries keep their creation order, after those with entries
+        sections.sort_by(|a, b| match (a.3.first(), b.3.first()) {
+            (Some(x), Some(y)) => x.0.cmp(&y.0),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        
+        let mut report = String::from("# Task Report\n\n");
+        report.push_str(&format!("Generated {}", chrono::Utc::now().format("%Y-%m-%d %H:%M:%S")));
+        if let Some(since) = &since {
+            report.push_str(&format!(", entries since {}", &since[..10]));
+        }
+        if completed_only {
+            report.push_str(", completed tasks only");
+        }
+        report.push_str("\n\n");
+        if sections.is_empty() {
+            report.push_str("_No matching tasks._\n");
+        }
+        let entry_count: usize = sections.iter().map(|section| section.3.len()).sum();
+        for (name, status, priority, entries) in &sections {
+            report.push_str(&format!("## {}\n\nStatus: {} | Priority: {}\n\n", name, status, priority_label(*priority)));
+            if entries.is_empty() {
+                report.push_str("_No log entries._\n\n");
+            }
+            for (timestamp, text) in entries {
+                report.push_str(&format!("- **{}** {}\n", timestamp, text.replace('\n', "\n  ")));
+            }
+            if !entries.is_empty() {
+                report.push('\n');
+            }
+        }
+        
+        match out {
+            Some(path) => {
+                fs::write(path, &report)?;
+                emit!("Wrote {} entries from {} tasks to {}", entry_count, sections.len(), path);
+            }
+            None => print!("{}", report),
+        }
+        
+        Ok(Outcome::Done)
+    }
+    
+    pub fn list_aides(&self, limit: Option<u32>, offset: u32, created: Option<CreatedWithin>) -> Result<()> {
+        // Aides without a created_at predate the column, so they're never "today" or "this week"
         let mut stmt = self.conn.prepare(
-            "SELECT a.name, COUNT(d.id) as data_count 
+            "SELECT a.name, COUNT(d.id) as data_count, a.pinned
              FROM aides a 
              LEFT JOIN data d ON a.id = d.aide_id 
+             WHERE ?3 IS NULL OR a.created_at >= ?3
              GROUP BY a.name
-             ORDER BY a.name"
+             ORDER BY a.pinned DESC, a.name
+             LIMIT ?1 OFFSET ?2"
         )?;
         
-        let rows = stmt.query_map([], |row| {
+        // SQLite treats a negative LIMIT as "no limit"
+        let sql_limit = limit.map(i64::from).unwrap_or(-1);
+        let since = created.map(CreatedWithin::start);
+        let rows = stmt.query_map(rusqlite::params![sql_limit, i64::from(offset), since], |row| {
             Ok((
                 row.get::<_, String>(0)?,  // name
                 row.get::<_, i32>(1)?,     // data_count
+                row.get::<_, bool>(2)?,    // pinned
             ))
         })?;
         
-        println!("Aides:");
-        println!("------");
+        // Aides in folders are grouped under their top-level folder, after the others
+        let mut ungrouped = Vec::new();
+        let mut groups: std::collections::BTreeMap<String, Vec<(String, i32, bool)>> = std::collections::BTreeMap::new();
         for row in rows {
-            let (name, data_count) = row?;
-            println!("{} | Data entries: {}", name, data_count);
+            let (name, data_count, pinned) = row?;
+            match name.split_once('/') {
+                Some((folder, rest)) => groups.entry(folder.to_string()).or_default().push((rest.to_string(), data_count, pinned)),
+                None => ungrouped.push((name, data_count, pinned)),
+            }
+        }
+        
+        let mut table = Table::new(&["Name", "Entries"]);
+        let add_aide = |table: &mut Table, indent: &str, (name, data_count, pinned): &(String, i32, bool)| {
+            let pin = if *pinned { format!("{} ", PIN_MARKER) } else { String::new() };
+            table.add_row(vec![format!("{}{}{}", indent, pin, name), data_count.to_string()]);
+        };
+        for aide in &ungrouped {
+            add_aide(&mut table, "", aide);
+        }
+        for (folder, aides) in &groups {
+            table.add_row(vec![format!("{}/", folder), String::new()]);
+            for aide in aides {
+                add_aide(&mut table, "  ", aide);
+            }
         }
+        table.print();
         
         Ok(())
     }
     
-    pub fn edit_task(&self, task_name: &str) -> Result<()> {
-        // Use fuzzy matching to find the task
-        let fuzzy_result = self.find_fuzzy_task_match(task_name)?;
-        
-        let actual_task_name = match fuzzy_result {
-            FuzzyMatchResult { exact_match: true, suggested_name: Some(name), .. } => name,
-            FuzzyMatchResult { suggested_name: Some(suggestion), score: Some(score), .. } => {
-                if score >= FUZZY_MATCH_THRESHOLD {
-                    if ask_user_confirmation(task_name, &suggestion) {
-                        suggestion
-                    } else {
-                        println!("Operation cancelled.");
-                        return Ok(());
-                    }
-                } else {
-                    println!("Task '{}' not found.", task_name);
-                    return Ok(());
-                }
-            }
-            _ => {
-                println!("Task '{}' not found.", task_name);
-                return Ok(());
-            }
+    pub fn edit_task(&self, task_name: &str, editor_override: Option<&str>) -> Result<Outcome> {
+        let Some(actual_task_name) = self.resolve_task_name(task_name)? else {
+            return Ok(Outcome::NotFound);
         };
         
         let task_log_file: String = match self.conn.query_row(
@@ -655,92 +2626,64 @@ impl Database {
         ) {
             Ok(path) => path,
             Err(rusqlite::Error::QueryReturnedNoRows) => {
-                println!("Task '{}' not found in database", actual_task_name);
-                return Ok(());
+                eprintln!("Task '{}' not found in database", actual_task_name);
+                return Ok(Outcome::NotFound);
             }
             Err(e) => return Err(e.into()),
         };
         
-        let status = Command::new("vi")
-            .arg(&task_log_file)
-            .status();
+        let editor = self.editor_command(editor_override)?;
+        let status = open_in_editor(&editor, Path::new(&task_log_file));
         
         match status {
             Ok(exit_status) => {
                 if !exit_status.success() {
-                    println!("Editor exited with status: {:?}", exit_status);
+                    eprintln!("Editor exited with status: {:?}", exit_status);
                 }
             }
             Err(e) => {
-                println!("Failed to open vi editor: {}", e);
-                println!("Task log file is at: {}", task_log_file);
+                eprintln!("Failed to open {} editor: {}", editor, e);
+                eprintln!("Task log file is at: {}", task_log_file);
+                return Ok(Outcome::Failed);
             }
         }
         
-        Ok(())
+        Ok(Outcome::Done)
     }
 
     pub fn update_aide_content(&mut self, aide_name: &str, new_content: &str) -> Result<()> {
-        // Use fuzzy matching to find the aide
-        let fuzzy_result = self.find_fuzzy_aide_match(aide_name)?;
-        
-        let actual_aide_name = match fuzzy_result {
-            FuzzyMatchResult { exact_match: true, suggested_name: Some(name), .. } => name,
-            FuzzyMatchResult { suggested_name: Some(suggestion), score: Some(score), .. } => {
-                if score >= FUZZY_MATCH_THRESHOLD {
-                    if ask_user_confirmation(aide_name, &suggestion) {
-                        suggestion
-                    } else {
-                        println!("Operation cancelled.");
-                        return Ok(());
-                    }
-                } else {
-                    println!("Aide '{}' not found.", aide_name);
-                    return Ok(());
-                }
-            }
-            _ => {
-                println!("Aide '{}' not found.", aide_name);
-                return Ok(());
-            }
+        let Some(actual_aide_name) = self.resolve_aide_name(aide_name)? else {
+            return Ok(());
         };
         
         let rows_affected = self.conn.execute(
             "UPDATE data SET command_output = ?1 WHERE aide_id = (SELECT id FROM aides WHERE name = ?2)",
-            [new_content, &actual_aide_name],
+            [&self.seal(&actual_aide_name, new_content)?, &actual_aide_name],
         )?;
         
         if rows_affected == 0 {
             // If no existing data, create a new entry
             self.add_data(&actual_aide_name, "TUI Edit", None)?;
+        } else {
+            self.sync_encrypted_file(&actual_aide_name)?;
         }
         
         Ok(())
     }
 
-    pub fn add_task_log(&self, task_name: &str, log_text: &str) -> Result<()> {
-        // Use fuzzy matching to find the task
-        let fuzzy_result = self.find_fuzzy_task_match(task_name)?;
-        
-        let actual_task_name = match fuzzy_result {
-            FuzzyMatchResult { exact_match: true, suggested_name: Some(name), .. } => name,
-            FuzzyMatchResult { suggested_name: Some(suggestion), score: Some(score), .. } => {
-                if score >= FUZZY_MATCH_THRESHOLD {
-                    if ask_user_confirmation(task_name, &suggestion) {
-                        suggestion
-                    } else {
-                        println!("Operation cancelled.");
-                        return Ok(());
-                    }
-                } else {
-                    println!("Task '{}' not found.", task_name);
-                    return Ok(());
-                }
-            }
-            _ => {
-                println!("Task '{}' not found.", task_name);
-                return Ok(());
+    // Append a log entry to a task. With `create`, a task that doesn't exist (or whose
+    // suggested near-match is declined) is created first, without opening an editor.
+    pub fn add_task_log(&mut self, task_name: &str, log_text: &str, create: bool) -> Result<Outcome> {
+        let actual_task_name = if create {
+            match self.confirm_match(task_name, self.find_fuzzy_task_match(task_name)?) {
+                Ok(name) => name,
+                Err(_) => return self.create_task_for_log(task_name, log_text),
             }
+        } else {
+            let Some(name) = self.resolve_task_name(task_name)? else {
+                return Ok(Outcome::NotFound);
+            };
+            name
         };
         
         let task_log_file: String = match self.conn.query_row(
@@ -750,100 +2693,94 @@ impl Database {
         ) {
             Ok(path) => path,
             Err(rusqlite::Error::QueryReturnedNoRows) => {
-                println!("Task '{}' not found in database", actual_task_name);
-                return Ok(());
+                eprintln!("Task '{}' not found in database", actual_task_name);
+                return Ok(Outcome::NotFound);
             }
             Err(e) => return Err(e.into()),
         };
         
-        // Read existing content
-        let mut content = if PathBuf::from(&task_log_file).exists() {
-            fs::read_to_string(&task_log_file)?
+        append_task_log_entry(&actual_task_name, &task_log_file, log_text)?;
+        emit!("Log entry added to task '{}'", actual_task_name);
+        
+        Ok(Outcome::Done)
+    }
+
+    // The `task-log-update --create` path for a task that isn't there yet
+    fn create_task_for_log(&mut self, task_name: &str, log_text: &str) -> Result<Outcome> {
+        if let Err(reason) = validate_name("Task", task_name) {
+            eprintln!("{}", reason);
+            return Ok(Outcome::Invalid);
+        }
+        let (task_log_file, _) = self.create_task_no_editor(task_name, None)?;
+        emit!("Created task '{}'", task_name);
+        append_task_log_entry(task_name, &task_log_file.to_string_lossy(), log_text)?;
+        emit!("Log entry added to task '{}'", task_name);
+        Ok(Outcome::Done)
+    }
+    
+    pub fn write_aide(&mut self, aide_name: &str, editor_override: Option<&str>) -> Result<()> {
+        let Some(actual_aide_name) = self.resolve_aide_name(aide_name)? else {
+            return Ok(());
+        };
+        
+        // Without .txt files, edit a copy rebuilt from the database and apply the changes afterwards.
+        // Encrypted aides are edited the same way; the decrypted copy is removed after the editor exits.
+        let encrypted = self.aide_encrypted(&actual_aide_name)?;
+        let db_only = !self.write_txt_files()? || encrypted;
+        let file_path = if db_only {
+            let prefix = format!("aide-{}-", paths::file_stem(&actual_aide_name)?);
+            paths::write_private_temp_file(&prefix, &self.render_aide_file(&actual_aide_name)?)?
         } else {
-            format!("Task: {}\n\n--- Task Log ---\n", actual_task_name)
+            self.aide_file(&actual_aide_name)?
         };
         
-        // Add timestamp and new log entry
-        let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S");
-        let log_entry = format!("\n[{}] {}", timestamp, log_text);
-        content.push_str(&log_entry);
+        // Create file if it doesn't exist
+        if !file_path.exists() {
+            paths::create_parent_dir(&file_path)?;
+            fs::write(&file_path, new_aide_file_content(&actual_aide_name))?;
+            emit!("Created new file: {}", file_path.display());
+        }
         
-        // Write back to file
-        fs::write(&task_log_file, content)?;
-        println!("Log entry added to task '{}'", actual_task_name);
-        
-        Ok(())
-    }
-
-    pub fn write_aide(&self, aide_name: &str) -> Result<()> {
-        // Use fuzzy matching to find the aide
-        let fuzzy_result = self.find_fuzzy_aide_match(aide_name)?;
-        
-        let actual_aide_name = match fuzzy_result {
-            FuzzyMatchResult { exact_match: true, suggested_name: Some(name), .. } => name,
-            FuzzyMatchResult { suggested_name: Some(suggestion), score: Some(score), .. } => {
-                if score >= FUZZY_MATCH_THRESHOLD {
-                    if ask_user_confirmation(aide_name, &suggestion) {
-                        suggestion
-                    } else {
-                        println!("Operation cancelled.");
-                        return Ok(());
-                    }
-                } else {
-                    println!("Aide '{}' not found.", aide_name);
-                    return Ok(());
-                }
-            }
-            _ => {
-                println!("Aide '{}' not found.", aide_name);
-                return Ok(());
-            }
-        };
-        
-        // Construct file path (all aides are now files)
-        let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let aide_dir = PathBuf::from(&home_dir).join(".aide");
-        let file_path = aide_dir.join(format!("{}.txt", actual_aide_name));
-        
-        // Create file if it doesn't exist
-        if !file_path.exists() {
-            fs::create_dir_all(&aide_dir)?;
-            let initial_content = format!("# {}\n\nCreated: {}\n\n", 
-                                        actual_aide_name, 
-                                        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S"));
-            fs::write(&file_path, initial_content)?;
-            println!("Created new file: {}", file_path.display());
-        }
-        
-        // Try editors in order of preference: vim, vi, nano
-        let editors = ["vim", "vi", "nano"];
-        let mut editor_found = false;
+        // Try editors in order of preference: the configured editor, then vim, vi, nano
+        // (on Windows: the configured editor, %EDITOR%, then notepad).
+        // An --editor override replaces the whole list.
+        let editors: Vec<String> = if let Some(editor) = editor_override {
+            vec![editor.to_string()]
+        } else {
+            let mut editors: Vec<String> = if cfg!(windows) {
+                std::env::var("EDITOR").into_iter().chain(["notepad".to_string()]).collect()
+            } else {
+                vec!["vim".to_string(), "vi".to_string(), "nano".to_string()]
+            };
+            if let Some(configured) = self.get_config_value("editor")? {
+                editors.retain(|editor| *editor != configured);
+                editors.insert(0, configured);
+            }
+            editors
+        };
+        let mut editor_found = false;
+        // Whether any editor actually ran, including the $EDITOR fallback below
+        let mut edited = false;
         
         for editor in &editors {
             // Check if editor is available
-            if Command::new("which")
-                .arg(editor)
-                .output()
-                .map(|output| output.status.success())
-                .unwrap_or(false)
-            {
-                println!("Opening {} with {}...", file_path.display(), editor);
-                let status = Command::new(editor)
-                    .arg(&file_path)
-                    .status();
+            if paths::is_executable_available(editor) {
+                emit!("Opening {} with {}...", file_path.display(), editor);
+                let status = open_in_editor(editor, &file_path);
                 
                 match status {
                     Ok(exit_status) => {
                         if exit_status.success() {
-                            println!("File edited successfully with {}.", editor);
+                            emit!("File edited successfully with {}.", editor);
                         } else {
-                            println!("Editor {} exited with status: {:?}", editor, exit_status);
+                            eprintln!("Editor {} exited with status: {:?}", editor, exit_status);
                         }
                         editor_found = true;
+                        edited = true;
                         break;
                     }
                     Err(e) => {
-                        println!("Failed to open {} editor: {}", editor, e);
+                        eprintln!("Failed to open {} editor: {}", editor, e);
                         continue;
                     }
                 }
@@ -851,39 +2788,91 @@ impl Database {
         }
         
         if !editor_found {
-            println!("No suitable editor found. Tried: {}", editors.join(", "));
-            println!("File is located at: {}", file_path.display());
-            println!("You can edit it manually with any text editor.");
+            eprintln!("No suitable editor found. Tried: {}", editors.join(", "));
+            if !encrypted {
+                eprintln!("File is located at: {}", file_path.display());
+            }
+            if !db_only {
+                eprintln!("You can edit it manually with any text editor.");
+            }
             
-            // Try to use $EDITOR environment variable as last resort
-            if let Ok(editor_env) = std::env::var("EDITOR") {
-                println!("Trying $EDITOR environment variable: {}", editor_env);
-                let status = Command::new(&editor_env)
-                    .arg(&file_path)
-                    .status();
+            // Try to use $EDITOR environment variable as last resort, unless --editor was explicit
+            let fallback = std::env::var("EDITOR").ok().filter(|e| editor_override.is_none() && !e.is_empty() && !editors.contains(e));
+            if let Some(editor_env) = fallback {
+                eprintln!("Trying $EDITOR environment variable: {}", editor_env);
+                let status = open_in_editor(&editor_env, &file_path);
                     
                 match status {
                     Ok(exit_status) => {
                         if exit_status.success() {
-                            println!("File edited successfully with {}.", editor_env);
+                            emit!("File edited successfully with {}.", editor_env);
                         } else {
-                            println!("Editor {} exited with status: {:?}", editor_env, exit_status);
+                            eprintln!("Editor {} exited with status: {:?}", editor_env, exit_status);
                         }
+                        edited = true;
                     }
                     Err(e) => {
-                        println!("Failed to open {} editor: {}", editor_env, e);
+                        eprintln!("Failed to open {} editor: {}", editor_env, e);
                     }
                 }
             }
         }
         
+        // Without an editor a plaintext copy is kept, so its contents aren't lost; a decrypted
+        // one never stays on disk
+        if encrypted && !edited {
+            let _ = fs::remove_file(&file_path);
+        } else if db_only && !edited {
+            eprintln!("Note: {} is a copy rebuilt from the database; changes to it are not applied.", file_path.display());
+        } else if db_only {
+            let edited_text = fs::read_to_string(&file_path)?;
+            let _ = fs::remove_file(&file_path);
+            let (added, removed) = self.apply_aide_edit(&actual_aide_name, &edited_text)?;
+            if added > 0 || removed > 0 {
+                emit!("Aide '{}': {} entr{} added, {} removed",
+                      actual_aide_name, added, if added == 1 { "y" } else { "ies" }, removed);
+            }
+        }
+        
         Ok(())
     }
+    
+    // Bring an aide's data rows in line with edited file text: entries whose block is gone
+    // are deleted and new "* " bullets are added as entries. Unchanged entries keep their rows.
+    // Returns how many entries were added and removed.
+    pub fn apply_aide_edit(&mut self, aide_name: &str, edited: &str) -> Result<(usize, usize)> {
+        let entry_format = self.entry_format()?;
+        let mut rest = edited.to_string();
+        let mut removed = 0;
+        for entry in self.get_aide_entries(aide_name)? {
+            match remove_entry_block(&entry_format, &rest, &entry.file_text()) {
+                Some(remaining) => rest = remaining,
+                None => {
+                    self.conn.execute("DELETE FROM data WHERE id = ?1", [entry.id])?;
+                    removed += 1;
+                }
+            }
+        }
+        
+        let added = new_bullets(&entry_format, &rest);
+        let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let timestamps = self.aide_setting_flag(aide_name, "timestamp")?;
+        for text in &added {
+            let output = if timestamps { format!("[{}] {}", timestamp, text) } else { text.clone() };
+            self.store_aide_entry(aide_name, &timestamp, text, &output, text)?;
+        }
+        if removed > 0 {
+            self.sync_encrypted_file(aide_name)?;
+        }
+        
+        Ok((added.len(), removed))
+    }
 
     pub fn get_all_tasks(&self) -> Result<Vec<TaskItem>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT name, priority, status, created_at FROM tasks ORDER BY priority, created_at"
-        )?;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT name, priority, status, created_at, pinned, estimate_minutes, actual_minutes FROM tasks {}",
+            self.task_order_clause()?
+        ))?;
         
         let rows = stmt.query_map([], |row| {
             Ok(TaskItem {
@@ -891,12 +2880,18 @@ impl Database {
                 priority: row.get(1)?,
                 status: row.get(2)?,
                 created_at: row.get(3)?,
+                pinned: row.get(4)?,
+                blocked_by: Vec::new(),
+                estimate_minutes: row.get(5)?,
+                actual_minutes: row.get(6)?,
             })
         })?;
         
         let mut tasks = Vec::new();
         for row in rows {
-            tasks.push(row?);
+            let mut task = row?;
+            task.blocked_by = self.task_blockers(&task.name)?;
+            tasks.push(task);
         }
         
         Ok(tasks)
@@ -906,11 +2901,13 @@ impl Database {
         let mut stmt = self.conn.prepare(
             "SELECT a.name,
                     GROUP_CONCAT(d.input_text, '|||') as all_inputs,
-                    GROUP_CONCAT(d.command_output, '|||') as all_outputs
+                    GROUP_CONCAT(d.command_output, '|||') as all_outputs,
+                    a.pinned,
+                    a.created_at
              FROM aides a 
              LEFT JOIN data d ON a.id = d.aide_id 
              GROUP BY a.name
-             ORDER BY a.name"
+             ORDER BY a.pinned DESC, a.name"
         )?;
         
         let rows = stmt.query_map([], |row| {
@@ -918,20 +2915,167 @@ impl Database {
                 name: row.get(0)?,
                 input_text: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
                 command_output: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                pinned: row.get(3)?,
+                created_at: row.get(4)?,
             })
         })?;
         
         let mut aides = Vec::new();
         for row in rows {
-            aides.push(row?);
+            let mut aide = row?;
+            aide.input_text = self.reveal_joined(&aide.input_text)?;
+            aide.command_output = self.reveal_joined(&aide.command_output)?;
+            aides.push(aide);
         }
         
         Ok(aides)
     }
 
+    // All data rows of an aide in insertion order
+    pub fn get_aide_entries(&self, aide_name: &str) -> Result<Vec<DataEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT d.id, d.input_text, d.command_output, d.created_at
+             FROM data d
+             JOIN aides a ON d.aide_id = a.id
+             WHERE a.name = ?1
+             ORDER BY d.id"
+        )?;
+        
+        let rows = stmt.query_map([aide_name], |row| {
+            Ok(DataEntry {
+                id: row.get(0)?,
+                input_text: row.get(1)?,
+                command_output: row.get(2)?,
+                created_at: row.get(3)?,
+            })
+        })?;
+        
+        let mut entries = Vec::new();
+        for row in rows {
+            let mut entry = row?;
+            entry.input_text = self.reveal(entry.input_text)?;
+            entry.command_output = self.reveal(entry.command_output)?;
+            // Rows from before the created_at column carry their timestamp as a "[...] " prefix
+            if entry.created_at.is_none() {
+                entry.created_at = entry.command_output
+                    .strip_prefix('[')
+                    .and_then(|rest| rest.split_once("] "))
+                    .map(|(timestamp, _)| timestamp.to_string());
+            }
+            entries.push(entry);
+        }
+        
+        Ok(entries)
+    }
+
+    // Delete one entry (1-based, in `get_aide_entries` order) from an aide and its file
+    pub fn delete_aide_entry(&mut self, aide_name: &str, index: usize, assume_yes: bool) -> Result<Outcome> {
+        let actual_aide_name = match self.resolve_aide_name(aide_name)? {
+            Some(name) => name,
+            None => return Ok(Outcome::NotFound),
+        };
+        
+        let entries = self.get_aide_entries(&actual_aide_name)?;
+        let Some(entry) = index.checked_sub(1).and_then(|i| entries.get(i)) else {
+            eprintln!("Invalid entry index {}. Aide '{}' has {} entries (1-{}).",
+                      index, actual_aide_name, entries.len(), entries.len());
+            return Ok(Outcome::Invalid);
+        };
+        
+        if !assume_yes {
+            eprint!("Delete entry {} from '{}': \"{}\"? (y/n): ", index, actual_aide_name, entry.input_text);
+            io::stderr().flush()?;
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            let answer = input.trim().to_lowercase();
+            if answer != "y" && answer != "yes" {
+                eprintln!("Operation cancelled.");
+                return Ok(Outcome::Cancelled);
+            }
+        }
+        
+        self.conn.execute("DELETE FROM data WHERE id = ?1", [entry.id])?;
+        
+        let file_path = self.aide_file(&actual_aide_name)?;
+        if self.aide_encrypted(&actual_aide_name)? {
+            self.sync_encrypted_file(&actual_aide_name)?;
+        } else if file_path.exists() {
+            let content = fs::read_to_string(&file_path)?;
+            let entry_format = self.entry_format()?;
+            let updated = remove_entry_block(&entry_format, &content, &entry.file_text())
+                .or_else(|| remove_entry_block(&entry_format, &content, &entry.input_text));
+            match updated {
+                Some(updated) => fs::write(&file_path, updated)?,
+                None => eprintln!("Entry not found in {}; the file was left unchanged.", file_path.display()),
+            }
+        }
+        
+        emit!("Deleted entry {} from aide '{}': {}", index, actual_aide_name, entry.input_text);
+        Ok(Outcome::Done)
+    }
+    
+    // Replace one entry's text, in its data row and in its section of the aide file
+    pub fn update_aide_entry(&mut self, aide_name: &str, entry: &DataEntry, new_text: &str) -> Result<Outcome> {
+        let old_file_text = entry.file_text();
+        let mut updated = entry.clone();
+        updated.input_text = new_text.to_string();
+        // Plain entries keep their "[timestamp] text" output in step with the text
+        if old_file_text == entry.input_text {
+            if let Some((timestamp, _)) = entry.command_output.strip_prefix('[').and_then(|rest| rest.split_once("] ")) {
+                updated.command_output = format!("[{}] {}", timestamp, new_text);
+            }
+        }
+        
+        self.conn.execute(
+            "UPDATE data SET input_text = ?1, command_output = ?2 WHERE id = ?3",
+            rusqlite::params![self.seal(aide_name, &updated.input_text)?, self.seal(aide_name, &updated.command_output)?, entry.id],
+        )?;
+        
+        let file_path = self.aide_file(aide_name)?;
+        if self.aide_encrypted(aide_name)? {
+            self.sync_encrypted_file(aide_name)?;
+        } else if file_path.exists() {
+            let content = fs::read_to_string(&file_path)?;
+            if let Some(new_content) = replace_entry_block(&self.entry_format()?, &content, &old_file_text, &updated.file_text()) {
+                fs::write(&file_path, new_content)?;
+            }
+        }
+        
+        Ok(Outcome::Done)
+    }
+    
+    // Render an aide's entries as a Markdown document
+    pub fn export_aide_markdown(&self, aide_name: &str, out: Option<&str>) -> Result<Outcome> {
+        let actual_aide_name = match self.resolve_aide_name(aide_name)? {
+            Some(name) => name,
+            None => return Ok(Outcome::NotFound),
+        };
+        
+        let entries = self.get_aide_entries(&actual_aide_name)?;
+        
+        let mut markdown = format!("# {}\n\n", actual_aide_name);
+        if entries.is_empty() {
+            markdown.push_str("_No entries yet._\n");
+        }
+        for entry in &entries {
+            let heading = entry.created_at.as_deref().unwrap_or("Undated entry");
+            markdown.push_str(&format!("## {}\n\n{}\n\n", heading, entry.input_text.trim_end()));
+        }
+        
+        match out {
+            Some(path) => {
+                fs::write(path, &markdown)?;
+                emit!("Exported {} entries from aide '{}' to {}", entries.len(), actual_aide_name, path);
+            }
+            None => print!("{}", markdown),
+        }
+        
+        Ok(Outcome::Done)
+    }
+
     pub fn get_all_configs(&self) -> Result<Vec<ConfigItem>> {
         let mut stmt = self.conn.prepare(
-            "SELECT key_name, value, description, created_at, updated_at 
+            "SELECT key_name, value
              FROM config_data 
              ORDER BY key_name"
         )?;
@@ -940,9 +3084,6 @@ impl Database {
             Ok(ConfigItem {
                 key_name: row.get(0)?,
                 value: row.get(1)?,
-                description: row.get::<_, Option<String>>(2)?,
-                created_at: row.get(3)?,
-                updated_at: row.get(4)?,
             })
         })?;
         
@@ -954,36 +3095,24 @@ impl Database {
         Ok(configs)
     }
 
-    pub fn set_config(&mut self, key: &str, value: &str) -> Result<()> {
-        // Use fuzzy matching to find existing config key
-        let fuzzy_result = self.find_fuzzy_config_match(key)?;
+    pub fn set_config(&mut self, key: &str, value: &str) -> Result<Outcome> {
+        if let Err(reason) = validate_name("Config key", key) {
+            eprintln!("{}", reason);
+            return Ok(Outcome::Invalid);
+        }
+        if let Err(reason) = validate_config_value(key, value) {
+            eprintln!("Error: {}", reason);
+            return Ok(Outcome::Invalid);
+        }
         
-        let actual_config_key = match fuzzy_result {
-            FuzzyMatchResult { exact_match: true, suggested_name: Some(name), .. } => {
-                // Exact match found, update existing config
-                println!("Updating existing config key '{}'", name);
+        // Update the matching key, or create a new one when nothing matches or the user declines
+        let actual_config_key = match self.confirm_match(key, self.find_fuzzy_config_match(key)?) {
+            Ok(name) => {
+                emit!("Updating existing config key '{}'", name);
                 name
             }
-            FuzzyMatchResult { suggested_name: Some(suggestion), score: Some(score), .. } => {
-                if score >= FUZZY_MATCH_THRESHOLD {
-                    if ask_user_confirmation(key, &suggestion) {
-                        // User confirmed, update existing config
-                        println!("Updating existing config key '{}'", suggestion);
-                        suggestion
-                    } else {
-                        // User declined, create new config with original key
-                        println!("Creating new config key '{}'", key);
-                        key.to_string()
-                    }
-                } else {
-                    // Score too low, create new config
-                    println!("Creating new config key '{}'", key);
-                    key.to_string()
-                }
-            }
-            _ => {
-                // No suggestions, create new config
-                println!("Creating new config key '{}'", key);
+            Err(_) => {
+                emit!("Creating new config key '{}'", key);
                 key.to_string()
             }
         };
@@ -992,7 +3121,7 @@ impl Database {
         let existing_value: Option<String> = self.conn.query_row(
             "SELECT value FROM config_data WHERE key_name = ?1",
             [&actual_config_key],
-            |row| Ok(row.get(0)?),
+            |row| row.get(0),
         ).optional()?;
 
         if let Some(old_value) = existing_value {
@@ -1001,14 +3130,18 @@ impl Database {
                 "UPDATE config_data SET value = ?1, updated_at = CURRENT_TIMESTAMP WHERE key_name = ?2",
                 [value, &actual_config_key],
             )?;
-            println!("Config '{}' updated from '{}' to '{}'", actual_config_key, old_value, value);
+            if old_value != value && crate::diff::color_enabled() {
+                emit!("Config '{}' updated: {}", actual_config_key, crate::diff::colored_word_diff(&old_value, value));
+            } else {
+                emit!("Config '{}' updated from '{}' to '{}'", actual_config_key, old_value, value);
+            }
         } else {
             // Insert new config
             self.conn.execute(
                 "INSERT INTO config_data (key_name, value) VALUES (?1, ?2)",
                 [&actual_config_key, value],
             )?;
-            println!("Config '{}' set to '{}'", actual_config_key, value);
+            emit!("Config '{}' set to '{}'", actual_config_key, value);
             
             // Use incremental update instead of full rebuild
             if let Some(ref mut index) = self.config_index {
@@ -1016,38 +3149,49 @@ impl Database {
             }
         }
 
+        Ok(Outcome::Done)
+    }
+
+    // (key, value) of every config key starting with `prefix`, by key. `_` and `%` in the
+    // prefix match themselves rather than acting as LIKE wildcards.
+    pub fn get_configs_with_prefix(&self, prefix: &str) -> Result<Vec<(String, String)>> {
+        let pattern = prefix.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+        let mut stmt = self.conn.prepare(
+            "SELECT key_name, value FROM config_data WHERE key_name LIKE ?1 || '%' ESCAPE '\\' ORDER BY key_name",
+        )?;
+        let rows = stmt.query_map([pattern], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+    
+    // `aide config-get-all <prefix>`: the keys in a namespace as key=value lines, or a JSON object
+    pub fn print_configs_with_prefix(&self, prefix: &str, as_json: bool) -> Result<()> {
+        let configs = self.get_configs_with_prefix(prefix)?;
+        if as_json {
+            let object: serde_json::Map<String, serde_json::Value> = configs
+                .into_iter()
+                .map(|(key, value)| (key, serde_json::Value::String(value)))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&object)?);
+            return Ok(());
+        }
+        if configs.is_empty() {
+            eprintln!("No config keys start with '{}'", prefix);
+        }
+        for (key, value) in configs {
+            println!("{}={}", key, value);
+        }
         Ok(())
     }
 
     pub fn get_config(&self, key: &str) -> Result<Option<String>> {
-        // Use fuzzy matching to find config key
-        let fuzzy_result = self.find_fuzzy_config_match(key)?;
-        
-        let actual_config_key = match fuzzy_result {
-            FuzzyMatchResult { exact_match: true, suggested_name: Some(name), .. } => name,
-            FuzzyMatchResult { suggested_name: Some(suggestion), score: Some(score), .. } => {
-                if score >= FUZZY_MATCH_THRESHOLD {
-                    if ask_user_confirmation(key, &suggestion) {
-                        suggestion
-                    } else {
-                        println!("Operation cancelled.");
-                        return Ok(None);
-                    }
-                } else {
-                    println!("Config key '{}' not found.", key);
-                    return Ok(None);
-                }
-            }
-            _ => {
-                println!("Config key '{}' not found.", key);
-                return Ok(None);
-            }
+        let Some(actual_config_key) = self.resolve_config_name(key)? else {
+            return Ok(None);
         };
 
         let value: Option<String> = self.conn.query_row(
             "SELECT value FROM config_data WHERE key_name = ?1",
             [&actual_config_key],
-            |row| Ok(row.get(0)?),
+            |row| row.get(0),
         ).optional()?;
 
         if let Some(ref val) = value {
@@ -1057,6 +3201,28 @@ impl Database {
         Ok(value)
     }
 
+    // Look up a config value by exact key without prompting or printing
+    // Format for timestamps shown in listings (config: date_format)
+    fn date_format(&self) -> Result<String> {
+        Ok(self.get_config_value("date_format")?.unwrap_or_else(|| DEFAULT_DATE_FORMAT.to_string()))
+    }
+
+    pub fn get_config_value(&self, key: &str) -> Result<Option<String>> {
+        let value = self.conn.query_row(
+            "SELECT value FROM config_data WHERE key_name = ?1",
+            [key],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(value)
+    }
+
+    // Read a boolean config key ("true"/"false", "yes"/"no", "on"/"off", "1"/"0")
+    pub fn get_config_flag(&self, key: &str, default: bool) -> Result<bool> {
+        Ok(self.get_config_value(key)?
+            .and_then(|value| parse_flag(&value))
+            .unwrap_or(default))
+    }
+
     pub fn list_configs(&self) -> Result<()> {
         let mut stmt = self.conn.prepare(
             "SELECT key_name, value, description, created_at, updated_at 
@@ -1074,62 +3240,284 @@ impl Database {
             ))
         })?;
         
-        println!("Configuration:");
-        println!("--------------");
+        let date_format = self.date_format()?;
+        let mut table = Table::new(&["Key", "Value", "Created", "Updated", "Description"]);
         for row in rows {
             let (key_name, value, description, created_at, updated_at) = row?;
-            println!("{} = {}", key_name, value);
-            if let Some(desc) = description {
-                println!("  Description: {}", desc);
+            let created_at = format_timestamp(&created_at, &date_format);
+            let updated_at = format_timestamp(&updated_at, &date_format);
+            // Multi-line values (prompts, templates) go below the row, indented
+            if value.contains('\n') {
+                let lines = value.lines().count();
+                table.add_row(vec![key_name, format!("({} lines)", lines), created_at, updated_at, description.unwrap_or_default()]);
+                for line in value.lines() {
+                    table.add_detail(line.to_string());
+                }
+            } else {
+                table.add_row(vec![key_name, value, created_at, updated_at, description.unwrap_or_default()]);
             }
-            println!("  Created: {} | Updated: {}", created_at, updated_at);
-            println!();
         }
+        table.print();
         
         Ok(())
     }
 
-    pub fn delete_config(&mut self, key: &str) -> Result<()> {
-        // Use fuzzy matching to find config key
-        let fuzzy_result = self.find_fuzzy_config_match(key)?;
+    pub fn delete_config(&mut self, key: &str) -> Result<Outcome> {
+        let Some(actual_config_key) = self.resolve_config_name(key)? else {
+            return Ok(Outcome::NotFound);
+        };
+
+        let rows_affected = self.delete_config_key(&actual_config_key)?;
+
+        if rows_affected == 0 {
+            eprintln!("Config '{}' not found in database", actual_config_key);
+            return Ok(Outcome::NotFound);
+        }
+        emit!("Config '{}' deleted successfully", actual_config_key);
+
+        Ok(Outcome::Done)
+    }
+
+    // Rename a config key, keeping its value, description and created_at
+    pub fn rename_config(&mut self, old_key: &str, new_key: &str) -> Result<Outcome> {
+        let new_key = new_key.trim();
+        if let Err(reason) = validate_name("Config key", new_key) {
+            eprintln!("{}", reason);
+            return Ok(Outcome::Invalid);
+        }
+        // Aliases are checked against the built-in commands when added, which a rename would skip
+        if new_key.starts_with(ALIAS_PREFIX) {
+            eprintln!("Error: Keys starting with '{}' are aliases; create them with `aide alias add`.", ALIAS_PREFIX);
+            return Ok(Outcome::Invalid);
+        }
         
-        let actual_config_key = match fuzzy_result {
-            FuzzyMatchResult { exact_match: true, suggested_name: Some(name), .. } => name,
-            FuzzyMatchResult { suggested_name: Some(suggestion), score: Some(score), .. } => {
-                if score >= FUZZY_MATCH_THRESHOLD {
-                    if ask_user_confirmation(key, &suggestion) {
-                        suggestion
-                    } else {
-                        println!("Operation cancelled.");
-                        return Ok(());
-                    }
-                } else {
-                    println!("Config key '{}' not found.", key);
-                    return Ok(());
-                }
+        let Some(actual_config_key) = self.resolve_config_name(old_key)? else {
+            return Ok(Outcome::NotFound);
+        };
+        let value = self.get_config_value(&actual_config_key)?.unwrap_or_default();
+        if let Err(reason) = validate_config_value(new_key, &value) {
+            eprintln!("Error: {}", reason);
+            return Ok(Outcome::Invalid);
+        }
+        
+        if actual_config_key == new_key {
+            eprintln!("Config '{}' already has that name.", actual_config_key);
+            return Ok(Outcome::Done);
+        }
+        
+        let tx = self.conn.transaction()?;
+        let taken: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM config_data WHERE key_name = ?1)",
+            [new_key],
+            |row| row.get(0),
+        )?;
+        if taken {
+            eprintln!("Config '{}' already exists. Delete it first or choose another name.", new_key);
+            return Ok(Outcome::Invalid);
+        }
+        let rows_affected = tx.execute(
+            "UPDATE config_data SET key_name = ?1, updated_at = CURRENT_TIMESTAMP WHERE key_name = ?2",
+            [new_key, actual_config_key.as_str()],
+        )?;
+        tx.commit()?;
+        
+        if rows_affected == 0 {
+            eprintln!("Config '{}' not found in database", actual_config_key);
+            return Ok(Outcome::NotFound);
+        }
+        
+        if let Some(ref mut index) = self.config_index {
+            index.remove_entity(&actual_config_key)?;
+            index.add_entity(new_key.to_string())?;
+        }
+        emit!("Config '{}' renamed to '{}'", actual_config_key, new_key);
+        
+        Ok(Outcome::Done)
+    }
+
+    // The command an alias expands to, if `name` is one
+    pub fn alias_target(&self, name: &str) -> Result<Option<String>> {
+        self.get_config_value(&format!("{}{}", ALIAS_PREFIX, name))
+    }
+    
+    // Define an alias for a subcommand (with optional arguments). Built-in subcommands
+    // can't be shadowed, and the target may not lead back to the alias.
+    pub fn add_alias(&mut self, name: &str, target: &str) -> Result<Outcome> {
+        if let Err(reason) = validate_name("Alias", name) {
+            eprintln!("{}", reason);
+            return Ok(Outcome::Invalid);
+        }
+        if name.starts_with('-') || name.contains(char::is_whitespace) {
+            eprintln!("Alias names can't start with '-' or contain spaces.");
+            return Ok(Outcome::Invalid);
+        }
+        if crate::cli::is_subcommand(name) {
+            eprintln!("'{}' is a built-in command and can't be used as an alias.", name);
+            return Ok(Outcome::Invalid);
+        }
+        
+        // Follow the chain of aliases the target goes through; it must end at a real command
+        let mut seen = vec![name.to_string()];
+        let mut next = target.split_whitespace().next().unwrap_or_default().to_string();
+        while !crate::cli::is_subcommand(&next) {
+            if seen.contains(&next) {
+                eprintln!("Alias '{}' would loop: {} -> {}", name, seen.join(" -> "), next);
+                return Ok(Outcome::Invalid);
             }
-            _ => {
-                println!("Config key '{}' not found.", key);
-                return Ok(());
+            let Some(expansion) = self.alias_target(&next)? else {
+                eprintln!("'{}' is not a command or alias.", next);
+                return Ok(Outcome::Invalid);
+            };
+            seen.push(next);
+            next = expansion.split_whitespace().next().unwrap_or_default().to_string();
+        }
+        
+        let key = format!("{}{}", ALIAS_PREFIX, name);
+        let is_new = self.get_config_value(&key)?.is_none();
+        self.conn.execute(
+            "INSERT INTO config_data (key_name, value, description) VALUES (?1, ?2, 'Command alias')
+             ON CONFLICT(key_name) DO UPDATE SET value = excluded.value, updated_at = CURRENT_TIMESTAMP",
+            [&key, target],
+        )?;
+        if is_new {
+            if let Some(ref mut index) = self.config_index {
+                index.add_entity(key)?;
             }
+        }
+        emit!("Alias '{}' -> '{}'", name, target);
+        
+        Ok(Outcome::Done)
+    }
+    
+    pub fn list_aliases(&self) -> Result<()> {
+        let aliases = self.get_configs_with_prefix(ALIAS_PREFIX)?;
+        for (key, target) in &aliases {
+            println!("{} -> {}", &key[ALIAS_PREFIX.len()..], target);
+        }
+        if aliases.is_empty() {
+            println!("No aliases defined. Add one with `aide alias add <name> <command>`.");
+        }
+        
+        Ok(())
+    }
+    
+    pub fn remove_alias(&mut self, name: &str) -> Result<Outcome> {
+        if self.delete_config_key(&format!("{}{}", ALIAS_PREFIX, name))? == 0 {
+            eprintln!("Alias '{}' not found.", name);
+            return Ok(Outcome::NotFound);
+        }
+        emit!("Alias '{}' removed", name);
+        Ok(Outcome::Done)
+    }
+    
+    // Hash of the passphrase the TUI asks for, if one is set
+    pub fn tui_lock_hash(&self) -> Result<Option<String>> {
+        Ok(self.conn.query_row("SELECT hash FROM tui_lock WHERE id = 1", [], |row| row.get(0)).optional()?)
+    }
+    
+    // How long the TUI may sit idle before locking: lock_timeout minutes, where 0 means never
+    pub fn lock_timeout(&self) -> Result<Option<std::time::Duration>> {
+        let minutes = self.get_config_value("lock_timeout")?
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(DEFAULT_LOCK_TIMEOUT_MINUTES);
+        Ok((minutes > 0).then(|| std::time::Duration::from_secs(minutes * 60)))
+    }
+    
+    // Set (or replace) the TUI passphrase; only its salted hash is stored
+    pub fn set_tui_lock(&mut self) -> Result<Outcome> {
+        let passphrase = crypto::prompt_hidden("New TUI passphrase: ")?;
+        if passphrase.is_empty() {
+            eprintln!("The passphrase can't be empty.");
+            return Ok(Outcome::Invalid);
+        }
+        if crypto::prompt_hidden("Repeat the passphrase: ")? != passphrase {
+            eprintln!("The passphrases don't match.");
+            return Ok(Outcome::Invalid);
+        }
+        
+        let hash = crypto::hash_passphrase(&passphrase)?;
+        self.conn.execute("INSERT OR REPLACE INTO tui_lock (id, hash) VALUES (1, ?1)", [&hash])?;
+        emit!("The TUI now asks for this passphrase when it starts and after it sits idle.");
+        Ok(Outcome::Done)
+    }
+    
+    // Remove the TUI passphrase, after checking the current one
+    pub fn clear_tui_lock(&mut self) -> Result<Outcome> {
+        let Some(hash) = self.tui_lock_hash()? else {
+            emit!("The TUI has no passphrase set.");
+            return Ok(Outcome::Done);
         };
-
+        if !crypto::verify_passphrase(&crypto::prompt_hidden("Current TUI passphrase: ")?, &hash) {
+            eprintln!("Wrong passphrase.");
+            return Ok(Outcome::Invalid);
+        }
+        self.conn.execute("DELETE FROM tui_lock", [])?;
+        emit!("The TUI passphrase was removed.");
+        Ok(Outcome::Done)
+    }
+    
+    // Delete a config key by exact name, returning the number of rows removed
+    pub fn delete_config_key(&mut self, key: &str) -> Result<usize> {
         let rows_affected = self.conn.execute(
             "DELETE FROM config_data WHERE key_name = ?1",
-            [&actual_config_key],
+            [key],
         )?;
 
         if rows_affected > 0 {
-            println!("Config '{}' deleted successfully", actual_config_key);
             // Use incremental removal instead of full rebuild
             if let Some(ref mut index) = self.config_index {
-                index.remove_entity(&actual_config_key)?;
+                index.remove_entity(key)?;
             }
-        } else {
-            println!("Config '{}' not found in database", actual_config_key);
         }
 
-        Ok(())
+        Ok(rows_affected)
+    }
+
+    // Delete a task and its log file by exact name, returning the number of rows removed
+    pub fn delete_task(&mut self, task_name: &str) -> Result<usize> {
+        let task_log_file: Option<String> = self.conn.query_row(
+            "SELECT task_log_file_path FROM tasks WHERE name = ?1",
+            [task_name],
+            |row| row.get(0),
+        ).optional()?;
+
+        let rows_affected = self.conn.execute(
+            "DELETE FROM tasks WHERE name = ?1",
+            [task_name],
+        )?;
+
+        if rows_affected > 0 {
+            if let Some(path) = task_log_file {
+                let _ = fs::remove_file(path);
+            }
+            if let Some(ref mut index) = self.task_index {
+                index.remove_entity(task_name)?;
+            }
+        }
+
+        Ok(rows_affected)
+    }
+
+    // Delete an aide and its file by exact name, returning the number of aides removed.
+    // Its data and settings rows are removed by ON DELETE CASCADE.
+    pub fn delete_aide(&mut self, aide_name: &str) -> Result<usize> {
+        let file_path = self.aide_file(aide_name);
+        let rows_affected = self.conn.execute(
+            "DELETE FROM aides WHERE name = ?1",
+            [aide_name],
+        )?;
+
+        if rows_affected > 0 {
+            let file_path = file_path?;
+            if fs::remove_file(&file_path).is_ok() {
+                paths::remove_empty_folders(&file_path)?;
+            }
+            if let Some(ref mut index) = self.aide_index {
+                index.remove_entity(aide_name)?;
+            }
+        }
+
+        Ok(rows_affected)
     }
 
     pub fn update_config_value(&mut self, key: &str, value: &str) -> Result<()> {
@@ -1139,9 +3527,9 @@ impl Database {
         )?;
 
         if rows_affected > 0 {
-            println!("Config '{}' updated to '{}'", key, value);
+            emit!("Config '{}' updated to '{}'", key, value);
         } else {
-            println!("Config key '{}' not found", key);
+            eprintln!("Config key '{}' not found", key);
         }
 
         Ok(())
@@ -1151,6 +3539,8 @@ impl Database {
     pub fn clear_all_data(&mut self) -> Result<()> {
         // Clear all data from tables
         self.conn.execute("DELETE FROM data", [])?;
+        self.conn.execute("DELETE FROM aide_settings", [])?;
+        self.conn.execute("DELETE FROM task_deps", [])?;
         self.conn.execute("DELETE FROM tasks", [])?;
         self.conn.execute("DELETE FROM aides", [])?;
         self.conn.execute("DELETE FROM config_data", [])?;
@@ -1166,7 +3556,599 @@ impl Database {
         self.rebuild_aide_index()?;
         self.rebuild_config_index()?;
         
-        println!("All data cleared successfully!");
+        emit!("All data cleared successfully!");
+        Ok(())
+    }
+
+    // Show the most recently touched tasks, aides and config keys, newest first
+    pub fn list_recent(&self, count: u32, as_json: bool) -> Result<()> {
+        let mut stmt = self.conn.prepare(
+            "SELECT kind, name, touched_at FROM (
+                 SELECT 'task' AS kind, name, COALESCE(updated_at, created_at) AS touched_at
+                 FROM tasks
+                 UNION ALL
+                 SELECT 'aide' AS kind, a.name,
+                        COALESCE((SELECT MAX(d.created_at) FROM data d WHERE d.aide_id = a.id), a.created_at)
+                 FROM aides a
+                 UNION ALL
+                 SELECT 'config' AS kind, key_name, updated_at
+                 FROM config_data
+             )
+             WHERE touched_at IS NOT NULL
+             ORDER BY touched_at DESC
+             LIMIT ?1"
+        )?;
+        
+        let rows = stmt.query_map([count], |row| {
+            Ok((
+                row.get::<_, String>(0)?,  // kind
+                row.get::<_, String>(1)?,  // name
+                row.get::<_, String>(2)?,  // touched_at
+            ))
+        })?;
+        
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row?);
+        }
+        
+        if as_json {
+            let json_entries: Vec<serde_json::Value> = entries
+                .iter()
+                .map(|(kind, name, touched_at)| serde_json::json!({
+                    "type": kind,
+                    "name": name,
+                    "timestamp": touched_at,
+                }))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json_entries)?);
+            return Ok(());
+        }
+        
+        let date_format = self.date_format()?;
+        println!("Recent activity:");
+        println!("----------------");
+        for (kind, name, touched_at) in entries {
+            println!("{} | {:<6} | {}", format_timestamp(&touched_at, &date_format), kind, name);
+        }
+        
         Ok(())
     }
-}
\ No newline at end of file
+
+    // Run a read-only SQL query and print the rows (power-user escape hatch)
+    pub fn run_sql_query(&self, query: &str, as_json: bool) -> Result<()> {
+        let first_keyword = query.split_whitespace().next().unwrap_or("").to_uppercase();
+        if first_keyword != "SELECT" && first_keyword != "EXPLAIN" {
+            eprintln!("Error: Only SELECT and EXPLAIN statements are allowed.");
+            return Ok(());
+        }
+
+        let mut stmt = self.conn.prepare(query)?;
+        // Double-check with SQLite itself so writes can't sneak in through a SELECT
+        if !stmt.readonly() {
+            eprintln!("Error: Only read-only statements are allowed.");
+            return Ok(());
+        }
+
+        let column_names: Vec<String> = stmt.column_names().iter().map(|name| name.to_string()).collect();
+        let column_count = column_names.len();
+        let rows = stmt.query_map([], |row| {
+            let mut values = Vec::with_capacity(column_count);
+            for i in 0..column_count {
+                values.push(row.get::<_, Value>(i)?);
+            }
+            Ok(values)
+        })?;
+
+        let mut result_rows = Vec::new();
+        for row in rows {
+            result_rows.push(row?);
+        }
+
+        if as_json {
+            let json_rows: Vec<serde_json::Map<String, serde_json::Value>> = result_rows
+                .iter()
+                .map(|row| {
+                    column_names
+                        .iter()
+                        .cloned()
+                        .zip(row.iter().map(sql_value_to_json))
+                        .collect()
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json_rows)?);
+            return Ok(());
+        }
+
+        let cells: Vec<Vec<String>> = result_rows
+            .iter()
+            .map(|row| row.iter().map(sql_value_to_string).collect())
+            .collect();
+
+        let mut widths: Vec<usize> = column_names.iter().map(|name| name.chars().count()).collect();
+        for row in &cells {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(cell.chars().count());
+            }
+        }
+
+        let format_row = |row: &[String]| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = *width))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        };
+
+        println!("{}", format_row(&column_names));
+        println!("{}", widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>().join("-+-"));
+        for row in &cells {
+            println!("{}", format_row(row));
+        }
+        println!("({} row{})", cells.len(), if cells.len() == 1 { "" } else { "s" });
+
+        Ok(())
+    }
+}
+
+// Locate the first entry with this text in aide file content, in the given entry format.
+// Returns (start of the timestamp line, start of the content line, end of the block).
+fn find_entry_block(entry_format: &EntryFormat, content: &str, text: &str) -> Option<(usize, usize, usize)> {
+    let bullet = entry_format.format_entry(None, text);
+    let start = if content.starts_with(&bullet) {
+        0
+    } else {
+        content.find(&format!("\n{}", bullet))? + 1
+    };
+    let end = start + bullet.len();
+    
+    // Include the timestamp line written just above the content
+    let line_start = content[..start.saturating_sub(1)].rfind('\n').map_or(0, |i| i + 1);
+    let previous_line = &content[line_start..start.saturating_sub(1)];
+    let block_start = if start > 0 && entry_format.stamp_of(previous_line).is_some() {
+        line_start
+    } else {
+        start
+    };
+    
+    Some((block_start, start, end))
+}
+
+// Remove an entry's block from aide file content, leaving any hand-edited
+// text around it untouched. Returns None if the entry isn't in the file.
+fn remove_entry_block(entry_format: &EntryFormat, content: &str, text: &str) -> Option<String> {
+    let (block_start, _, end) = find_entry_block(entry_format, content, text)?;
+    Some(format!("{}{}", &content[..block_start], &content[end..]))
+}
+
+// Swap an entry's text in aide file content, keeping its timestamp line
+fn replace_entry_block(entry_format: &EntryFormat, content: &str, old_text: &str, new_text: &str) -> Option<String> {
+    let (_, bullet_start, end) = find_entry_block(entry_format, content, old_text)?;
+    Some(format!("{}{}{}", &content[..bullet_start], entry_format.format_entry(None, new_text), &content[end..]))
+}
+
+// The entries left in edited aide text, each with its continuation lines. Read by
+// parse_entries' rules, so an edit sees the same entries a reload would; loose text outside
+// any entry is left alone rather than stored.
+fn new_bullets(entry_format: &EntryFormat, text: &str) -> Vec<String> {
+    entry_blocks(entry_format, text)
+        .into_iter()
+        .filter(|block| !block.loose)
+        .map(|block| block.text)
+        .collect()
+}
+
+// Entries in aide file text, the inverse of render_aide_file. A "$ command" first line splits
+// off captured output.
+fn parse_entries(entry_format: &EntryFormat, text: &str) -> Vec<DataEntry> {
+    entry_blocks(entry_format, text)
+        .into_iter()
+        .map(|EntryBlock { stamp, text, .. }| {
+            let (input_text, command_output) = match text.strip_prefix("$ ").and_then(|rest| rest.split_once('\n')) {
+                Some((command, output)) => (command.to_string(), output.to_string()),
+                None => {
+                    let output = match &stamp {
+                        Some(stamp) => format!("[{}] {}", stamp, text),
+                        None => text.clone(),
+                    };
+                    (text, output)
+                }
+            };
+            DataEntry { id: 0, input_text, command_output, created_at: stamp }
+        })
+        .collect()
+}
+
+// An entry's text as found in an aide file, with the timestamp line above it
+struct EntryBlock {
+    stamp: Option<String>,
+    text: String,
+    // Text that fits no entry (a legacy or hand-written file), kept as one block per stretch
+    loose: bool,
+}
+
+// Split aide file text into entries: the header is skipped, and each content line starts an
+// entry running up to the next one or the next timestamp line
+fn entry_blocks(entry_format: &EntryFormat, text: &str) -> Vec<EntryBlock> {
+    fn block(stamp: Option<String>, text: &str, loose: bool) -> Option<EntryBlock> {
+        let text = text.trim_end();
+        if text.trim().is_empty() {
+            return None;
+        }
+        Some(EntryBlock { stamp, text: text.to_string(), loose })
+    }
+    
+    let mut blocks = Vec::new();
+    let mut in_header = true;
+    // Text not (yet) part of an entry, and the timestamp line (with its timestamp) waiting
+    // for its content line
+    let mut loose = String::new();
+    let mut stamp: Option<(&str, String)> = None;
+    // The entry being read: its timestamp and content so far
+    let mut current: Option<(Option<String>, String)> = None;
+    for line in text.lines() {
+        if in_header && (line.starts_with("# ") || line.starts_with("Created: ") || line.trim().is_empty()) {
+            continue;
+        }
+        in_header = false;
+        
+        if let Some(content) = entry_format.content_of(line) {
+            blocks.extend(current.take().and_then(|(stamp, content)| block(stamp, &content, false)));
+            blocks.extend(block(None, &std::mem::take(&mut loose), true));
+            current = Some((stamp.take().map(|(_, stamp)| stamp), content.to_string()));
+        } else if let Some(line_stamp) = entry_format.stamp_of(line) {
+            blocks.extend(current.take().and_then(|(stamp, content)| block(stamp, &content, false)));
+            if let Some((orphan, _)) = stamp.replace((line, line_stamp.to_string())) {
+                loose.push_str(orphan);
+                loose.push('\n');
+            }
+        } else if let Some((_, content)) = current.as_mut() {
+            content.push('\n');
+            content.push_str(line);
+        } else {
+            if let Some((orphan, _)) = stamp.take() {
+                loose.push_str(orphan);
+                loose.push('\n');
+            }
+            loose.push_str(line);
+            loose.push('\n');
+        }
+    }
+    blocks.extend(current.and_then(|(stamp, content)| block(stamp, &content, false)));
+    if let Some((orphan, _)) = stamp {
+        loose.push_str(orphan);
+    }
+    blocks.extend(block(None, &loose, true));
+    blocks
+}
+
+// (current, longest) runs of consecutive days among `days` (ascending). The current run ends
+// today, or yesterday when nothing has been written yet today.
+fn day_streaks(days: impl Iterator<Item = chrono::NaiveDate>, today: chrono::NaiveDate) -> (usize, usize) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<chrono::NaiveDate> = None;
+    for day in days {
+        run = match previous {
+            Some(previous) if previous.succ_opt() == Some(day) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+    let current = match previous {
+        Some(last) if last == today || last.succ_opt() == Some(today) => run,
+        _ => 0,
+    };
+    (current, longest)
+}
+
+// Parse on/off style setting values
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "on" | "true" | "yes" | "1" => Some(true),
+        "off" | "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn sql_value_to_string(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Integer(i) => i.to_string(),
+        Value::Real(f) => f.to_string(),
+        Value::Text(s) => s.clone(),
+        Value::Blob(b) => format!("<blob {} bytes>", b.len()),
+    }
+}
+
+// Header written to a newly created aide file
+fn new_aide_file_content(name: &str) -> String {
+    format!("# {}\n\nCreated: {}\n\n", name, chrono::Utc::now().format("%Y-%m-%d %H:%M:%S"))
+}
+
+fn sql_value_to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Integer(i) => serde_json::Value::from(*i),
+        Value::Real(f) => serde_json::Value::from(*f),
+        Value::Text(s) => serde_json::Value::from(s.clone()),
+        Value::Blob(b) => serde_json::Value::from(format!("<blob {} bytes>", b.len())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_timestamp_applies_date_format() {
+        assert_eq!(format_timestamp("2024-03-05 14:07:09", "%d %b %Y %H:%M"), "05 Mar 2024 14:07");
+        assert_eq!(format_timestamp("2024-03-05 14:07:09", DEFAULT_DATE_FORMAT), "2024-03-05 14:07:09");
+    }
+
+    #[test]
+    fn format_timestamp_keeps_stored_form_on_bad_input() {
+        assert_eq!(format_timestamp("2024-03-05 14:07:09", "%Q"), "2024-03-05 14:07:09");
+        assert_eq!(format_timestamp("yesterday", "%d %b %Y"), "yesterday");
+    }
+
+    // A database in memory; files still go to the scratch home directory tests run with
+    fn test_db() -> Database {
+        Database::open(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn task_with_a_slash_gets_a_log_file_directly_in_tasks() {
+        let mut db = test_db();
+        let (path, created) = db.create_task_no_editor("feature/login", None).unwrap();
+        assert!(created);
+        assert_eq!(path.parent().unwrap(), paths::tasks_dir().unwrap());
+        assert_eq!(path.file_name().unwrap(), "feature_login.txt");
+        assert!(path.is_file());
+        assert_eq!(db.task_log_path("feature/login").unwrap(), Some(path));
+    }
+
+    #[test]
+    fn aides_with_colliding_file_names_get_separate_files() {
+        let mut db = test_db();
+        assert!(db.insert_aide("collide:q").unwrap());
+        assert!(db.insert_aide("collide_q").unwrap());
+        let first = db.aide_file("collide:q").unwrap();
+        let second = db.aide_file("collide_q").unwrap();
+        assert_eq!(first.file_name().unwrap(), "collide_q.txt");
+        assert_eq!(second.file_name().unwrap(), "collide_q-2.txt");
+    }
+
+    #[test]
+    fn migrated_slash_aides_keep_only_unclaimed_flat_files() {
+        let db = test_db();
+        // Rows from before the file_path column, each with the flat file it was written to
+        for name in ["legacy/owned", "legacy_owned", "legacy/kept"] {
+            db.conn.execute("INSERT INTO aides (name) VALUES (?1)", [name]).unwrap();
+            let flat = paths::flat_aide_file(name).unwrap();
+            paths::create_parent_dir(&flat).unwrap();
+            fs::write(flat, "* old entry\n").unwrap();
+        }
+        assign_missing_aide_files(&db.conn).unwrap();
+        assert_eq!(db.aide_file("legacy_owned").unwrap(), paths::flat_aide_file("legacy_owned").unwrap());
+        assert_eq!(db.aide_file("legacy/owned").unwrap(), paths::aide_file("legacy/owned").unwrap());
+        assert_eq!(db.aide_file("legacy/kept").unwrap(), paths::flat_aide_file("legacy/kept").unwrap());
+    }
+
+    #[test]
+    fn validate_name_rejects_empty() {
+        assert_eq!(validate_name("Task", ""), Err("Task name cannot be empty.".to_string()));
+    }
+
+    #[test]
+    fn validate_name_rejects_whitespace_only() {
+        assert_eq!(validate_name("Task", " \t\n"), Err("Task name cannot be empty.".to_string()));
+    }
+
+    #[test]
+    fn validate_name_rejects_too_long() {
+        let name = "a".repeat(MAX_NAME_LENGTH + 1);
+        assert_eq!(validate_name("Config key", &name),
+                   Err("Config key name is too long (129 characters, maximum 128).".to_string()));
+    }
+
+    #[test]
+    fn validate_name_counts_characters_not_bytes() {
+        assert!(validate_name("Task", &"é".repeat(MAX_NAME_LENGTH)).is_ok());
+    }
+
+    #[test]
+    fn validate_aide_name_rejects_parent_dir() {
+        assert!(validate_aide_name("..").is_err());
+        assert!(validate_aide_name("work/../deploy").is_err());
+    }
+
+    #[test]
+    fn validate_aide_name_rejects_empty_folder() {
+        assert!(validate_aide_name("work//deploy").is_err());
+    }
+
+    #[test]
+    fn validate_aide_name_rejects_reserved_folder() {
+        assert!(validate_aide_name("tasks/deploy").is_err());
+    }
+
+    #[test]
+    fn rejected_names_are_not_stored() {
+        let mut db = test_db();
+        assert_eq!(db.create_aide("   ").unwrap(), Outcome::Invalid);
+        assert_eq!(db.set_config("", "value").unwrap(), Outcome::Invalid);
+        assert!(db.aide_names().unwrap().iter().all(|name| !name.trim().is_empty()));
+        assert_eq!(db.get_config_value("").unwrap(), None);
+    }
+
+    #[test]
+    fn invalid_llm_settings_are_not_stored() {
+        let mut db = test_db();
+        assert_eq!(db.set_config("llm_base_url", "localhost:11434").unwrap(), Outcome::Invalid);
+        assert_eq!(db.set_config("llm_model", "  ").unwrap(), Outcome::Invalid);
+        assert!(validate_config_value("llm_base_url", "http://localhost:11434").is_ok());
+        assert_eq!(db.get_config_value("llm_base_url").unwrap(), None);
+        assert_eq!(db.get_config_value("llm_model").unwrap(), None);
+    }
+
+    #[test]
+    fn rename_config_checks_the_new_key_like_set() {
+        let mut db = test_db();
+        assert_eq!(db.set_config("zz_url", "localhost").unwrap(), Outcome::Done);
+        assert_eq!(db.rename_config("zz_url", "llm_base_url").unwrap(), Outcome::Invalid);
+        assert_eq!(db.rename_config("zz_url", "alias.ls").unwrap(), Outcome::Invalid);
+        assert_eq!(db.rename_config("zz_url", &"k".repeat(MAX_NAME_LENGTH + 1)).unwrap(), Outcome::Invalid);
+        assert_eq!(db.get_config_value("zz_url").unwrap().as_deref(), Some("localhost"));
+        assert_eq!(db.get_config_value("llm_base_url").unwrap(), None);
+    }
+
+    #[test]
+    fn the_tui_lock_moves_out_of_the_config_keys() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE config_data (id INTEGER PRIMARY KEY, key_name TEXT UNIQUE NOT NULL, value TEXT NOT NULL,
+                 description TEXT, created_at DATETIME DEFAULT CURRENT_TIMESTAMP, updated_at DATETIME DEFAULT CURRENT_TIMESTAMP);
+             INSERT INTO config_data (key_name, value) VALUES ('tui_lock', 'stored-hash');",
+        ).unwrap();
+        let mut db = Database::open(conn).unwrap();
+        assert_eq!(db.tui_lock_hash().unwrap().as_deref(), Some("stored-hash"));
+        assert_eq!(db.get_config_value("tui_lock").unwrap(), None);
+        // A config key of the same name no longer touches the lock
+        db.set_config("tui_lock", "").unwrap();
+        db.delete_config_key("tui_lock").unwrap();
+        assert_eq!(db.tui_lock_hash().unwrap().as_deref(), Some("stored-hash"));
+    }
+
+    #[test]
+    fn lock_timeout_must_be_whole_minutes() {
+        assert!(validate_config_value("lock_timeout", "10").is_ok());
+        assert!(validate_config_value("lock_timeout", "10m").is_err());
+        assert!(validate_config_value("lock_timeout", "-1").is_err());
+    }
+
+    // An aide with a one-line entry, a multi-line one and captured command output
+    fn aide_with_entries(db: &mut Database, aide_name: &str) {
+        assert!(db.insert_aide(aide_name).unwrap());
+        let stamp = "2026-01-02 03:04:05";
+        db.store_aide_entry(aide_name, stamp, "first note", &format!("[{}] first note", stamp), "first note").unwrap();
+        db.store_aide_entry(aide_name, stamp, "two\nlines", &format!("[{}] two\nlines", stamp), "two\nlines").unwrap();
+        db.store_aide_entry(aide_name, stamp, "ls", "a.txt\nb.txt", "$ ls\na.txt\nb.txt").unwrap();
+    }
+
+    // Render the aide's file and read it back, comparing what each entry stores
+    fn assert_round_trip(db: &Database, aide_name: &str) {
+        let stored: Vec<(String, String)> = db.get_aide_entries(aide_name).unwrap().into_iter()
+            .map(|entry| (entry.input_text, entry.command_output))
+            .collect();
+        let parsed: Vec<(String, String)> = parse_entries(&db.entry_format().unwrap(), &db.render_aide_file(aide_name).unwrap())
+            .into_iter()
+            .map(|entry| (entry.input_text, entry.command_output))
+            .collect();
+        assert_eq!(parsed, stored);
+    }
+
+    #[test]
+    fn aide_files_parse_back_into_their_entries() {
+        let mut db = test_db();
+        aide_with_entries(&mut db, "round_trip_default");
+        assert_round_trip(&db, "round_trip_default");
+    }
+
+    #[test]
+    fn aide_files_parse_back_with_a_custom_entry_format() {
+        let mut db = test_db();
+        assert_eq!(db.set_config("entry_format", "[{timestamp}]\\n> {content}").unwrap(), Outcome::Done);
+        aide_with_entries(&mut db, "round_trip_custom");
+        assert!(db.render_aide_file("round_trip_custom").unwrap().contains("\n> first note"));
+        assert_round_trip(&db, "round_trip_custom");
+    }
+
+    #[test]
+    fn legacy_text_is_kept_as_one_entry_but_not_added_by_an_edit() {
+        let text = "# notes\n\nCreated: 2024-01-01\n\nsome old text\nmore of it\n";
+        let entries = parse_entries(&EntryFormat::default(), text);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].input_text, "some old text\nmore of it");
+        assert_eq!(entries[0].created_at, None);
+        assert!(new_bullets(&EntryFormat::default(), text).is_empty());
+    }
+
+    #[test]
+    fn a_command_entry_splits_off_its_output() {
+        let entries = parse_entries(&EntryFormat::default(), "2026-01-02 03:04:05\n* $ ls\na.txt\n");
+        assert_eq!(entries[0].input_text, "ls");
+        assert_eq!(entries[0].command_output, "a.txt");
+        assert_eq!(entries[0].created_at.as_deref(), Some("2026-01-02 03:04:05"));
+        assert_eq!(new_bullets(&EntryFormat::default(), "2026-01-02 03:04:05\n* $ ls\na.txt\n"), ["$ ls\na.txt"]);
+    }
+
+    #[test]
+    fn invalid_tui_look_settings_are_rejected() {
+        let mut db = test_db();
+        assert_eq!(db.set_config("selection_bg", "not-a-color").unwrap(), Outcome::Invalid);
+        assert!(validate_config_value("highlight_symbol", "> \n> ").is_err());
+        assert!(validate_config_value("selection_bg", "#336699").is_ok());
+        assert_eq!(db.get_config_value("selection_bg").unwrap(), None);
+    }
+
+    // Rows in `table` that belong to the aide with id `aide_id`
+    fn rows_for_aide(db: &Database, table: &str, aide_id: i64) -> i64 {
+        db.conn.query_row(&format!("SELECT COUNT(*) FROM {} WHERE aide_id = ?1", table), [aide_id], |row| row.get(0)).unwrap()
+    }
+
+    // Delete `aide_name` and check that its data and aide_settings rows went with it
+    fn assert_delete_cascades(db: &mut Database, aide_name: &str) {
+        let aide_id: i64 = db.conn.query_row("SELECT id FROM aides WHERE name = ?1", [aide_name], |row| row.get(0)).unwrap();
+        assert_eq!(rows_for_aide(db, "data", aide_id), 1);
+        assert_eq!(rows_for_aide(db, "aide_settings", aide_id), 1);
+        assert_eq!(db.delete_aide(aide_name).unwrap(), 1);
+        assert_eq!(rows_for_aide(db, "data", aide_id), 0);
+        assert_eq!(rows_for_aide(db, "aide_settings", aide_id), 0);
+    }
+
+    #[test]
+    fn deleting_an_aide_removes_its_rows() {
+        let mut db = test_db();
+        db.insert_aide("cascade-new").unwrap();
+        db.conn.execute_batch(
+            "INSERT INTO data (aide_id, input_text, command_output)
+             SELECT id, 'note', 'note' FROM aides WHERE name = 'cascade-new';
+             INSERT INTO aide_settings (aide_id, key_name, value)
+             SELECT id, 'timestamp', 'off' FROM aides WHERE name = 'cascade-new';",
+        ).unwrap();
+        assert_delete_cascades(&mut db, "cascade-new");
+    }
+
+    #[test]
+    fn deleting_an_aide_removes_its_rows_after_migrating_an_old_schema() {
+        // The tables as created before the foreign keys cascaded
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE aides (id INTEGER PRIMARY KEY, name TEXT UNIQUE NOT NULL);
+             CREATE TABLE data (
+                 id INTEGER PRIMARY KEY,
+                 aide_id INTEGER NOT NULL,
+                 input_text TEXT NOT NULL,
+                 command_output TEXT NOT NULL,
+                 FOREIGN KEY (aide_id) REFERENCES aides (id)
+             );
+             CREATE TABLE aide_settings (
+                 aide_id INTEGER NOT NULL,
+                 key_name TEXT NOT NULL,
+                 value TEXT NOT NULL,
+                 PRIMARY KEY (aide_id, key_name),
+                 FOREIGN KEY (aide_id) REFERENCES aides (id)
+             );
+             INSERT INTO aides (id, name) VALUES (7, 'cascade-old');
+             INSERT INTO data (aide_id, input_text, command_output) VALUES (7, 'note', 'note');
+             INSERT INTO aide_settings (aide_id, key_name, value) VALUES (7, 'timestamp', 'off');",
+        ).unwrap();
+
+        let mut db = Database::open(conn).unwrap();
+        assert!(table_sql(&db.conn, "data").unwrap().contains("ON DELETE CASCADE"));
+        assert!(table_sql(&db.conn, "aide_settings").unwrap().contains("ON DELETE CASCADE"));
+        assert_delete_cascades(&mut db, "cascade-old");
+    }
+}
@@ -0,0 +1,65 @@
+use std::io::IsTerminal;
+
+// Removed words are also struck through, so the diff reads without telling colors apart
+const REMOVED: &str = "\x1b[31;9m";
+const ADDED: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+// Whether to print ANSI colors: stdout is a terminal and NO_COLOR (https://no-color.org) is unset
+pub fn color_enabled() -> bool {
+    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+// Split into alternating runs of whitespace and non-whitespace, so joining the pieces
+// gives back the original text
+fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((_, c)) = chars.next() {
+        if let Some(&(next_index, next)) = chars.peek() {
+            if next.is_whitespace() != c.is_whitespace() {
+                tokens.push(&text[start..next_index]);
+                start = next_index;
+            }
+        }
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+// `new` with the words removed from `old` in red and the added ones in green,
+// using the longest common subsequence of words to decide what stayed
+pub fn colored_word_diff(old: &str, new: &str) -> String {
+    let (old, new) = (tokens(old), tokens(new));
+    // lcs[i][j]: length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push_str(old[i]);
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push_str(&format!("{}{}{}", REMOVED, old[i], RESET));
+            i += 1;
+        } else {
+            out.push_str(&format!("{}{}{}", ADDED, new[j], RESET));
+            j += 1;
+        }
+    }
+    out
+}
@@ -1,3 +1,30 @@
+use crate::models::EntryFormat;
+
+// Character classes used for word-wise movement
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CharClass {
+    Whitespace,
+    Punctuation,
+    Word,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Whitespace
+    } else if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+// Lines opening with a date, bare as in aide files or bracketed as in task logs
+fn is_timestamp_line(line: &str) -> bool {
+    let line = line.strip_prefix('[').unwrap_or(line);
+    line.get(..10)
+        .is_some_and(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok())
+}
+
 #[derive(Debug, Clone)]
 pub struct TextEditor {
     pub content: Vec<String>,
@@ -6,6 +33,8 @@ pub struct TextEditor {
     pub scroll_offset: usize,
     pub title: String,
     pub is_dirty: bool,
+    // Where aide entries start, for Alt+N / Alt+P
+    pub entry_format: EntryFormat,
 }
 
 impl TextEditor {
@@ -23,6 +52,7 @@ impl TextEditor {
             scroll_offset: 0,
             title,
             is_dirty: false,
+            entry_format: EntryFormat::default(),
         }
     }
     
@@ -118,24 +148,6 @@ impl TextEditor {
         }
     }
     
-    pub fn scroll_up(&mut self) {
-        if self.scroll_offset > 0 {
-            self.scroll_offset -= 1;
-        }
-    }
-    
-    pub fn scroll_down(&mut self, visible_height: usize) {
-        let max_scroll = if self.content.len() > visible_height {
-            self.content.len() - visible_height
-        } else {
-            0
-        };
-        
-        if self.scroll_offset < max_scroll {
-            self.scroll_offset += 1;
-        }
-    }
-    
     pub fn page_up(&mut self, visible_height: usize) {
         if self.cursor_row >= visible_height {
             self.cursor_row -= visible_height;
@@ -174,6 +186,150 @@ impl TextEditor {
         }
     }
     
+    // Insert a copy of the current line below it and move onto the copy
+    pub fn duplicate_line(&mut self) {
+        if self.cursor_row >= self.content.len() {
+            return;
+        }
+        
+        let line = self.content[self.cursor_row].clone();
+        self.content.insert(self.cursor_row + 1, line);
+        self.cursor_row += 1;
+        self.is_dirty = true;
+        self.adjust_scroll();
+    }
+    
+    // Remove the current line entirely, always leaving at least one empty line
+    pub fn delete_line(&mut self) {
+        if self.cursor_row >= self.content.len() {
+            return;
+        }
+        
+        self.content.remove(self.cursor_row);
+        if self.content.is_empty() {
+            self.content.push(String::new());
+        }
+        if self.cursor_row >= self.content.len() {
+            self.cursor_row = self.content.len() - 1;
+        }
+        
+        let line_len = self.content[self.cursor_row].chars().count();
+        if self.cursor_col > line_len {
+            self.cursor_col = line_len;
+        }
+        self.is_dirty = true;
+        self.adjust_scroll();
+    }
+    
+    // Column of the start of the previous word on the current line
+    fn previous_word_col(&self) -> usize {
+        let chars: Vec<char> = self.content[self.cursor_row].chars().collect();
+        let mut col = self.cursor_col.min(chars.len());
+        
+        // Skip whitespace, then the run of same-class characters before it
+        while col > 0 && char_class(chars[col - 1]) == CharClass::Whitespace {
+            col -= 1;
+        }
+        if col > 0 {
+            let class = char_class(chars[col - 1]);
+            while col > 0 && char_class(chars[col - 1]) == class {
+                col -= 1;
+            }
+        }
+        col
+    }
+    
+    pub fn move_word_left(&mut self) {
+        if self.cursor_row >= self.content.len() {
+            return;
+        }
+        if self.cursor_col == 0 {
+            // Wrap to the end of the previous line
+            self.move_cursor_left();
+            return;
+        }
+        self.cursor_col = self.previous_word_col();
+    }
+    
+    pub fn move_word_right(&mut self) {
+        if self.cursor_row >= self.content.len() {
+            return;
+        }
+        let chars: Vec<char> = self.content[self.cursor_row].chars().collect();
+        if self.cursor_col >= chars.len() {
+            // Wrap to the start of the next line
+            self.move_cursor_right();
+            return;
+        }
+        
+        // Skip the current run, then any whitespace, landing on the next word
+        let mut col = self.cursor_col;
+        let class = char_class(chars[col]);
+        if class != CharClass::Whitespace {
+            while col < chars.len() && char_class(chars[col]) == class {
+                col += 1;
+            }
+        }
+        while col < chars.len() && char_class(chars[col]) == CharClass::Whitespace {
+            col += 1;
+        }
+        self.cursor_col = col;
+    }
+    
+    // Delete from the start of the previous word up to the cursor
+    pub fn delete_word(&mut self) {
+        if self.cursor_row >= self.content.len() {
+            return;
+        }
+        if self.cursor_col == 0 {
+            // Nothing before the cursor on this line, join with the previous one
+            self.delete_char();
+            return;
+        }
+        
+        let start_col = self.previous_word_col();
+        let line = &mut self.content[self.cursor_row];
+        let byte_index = |col: usize| line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len());
+        let start = byte_index(start_col);
+        let end = byte_index(self.cursor_col);
+        line.replace_range(start..end, "");
+        
+        self.cursor_col = start_col;
+        self.is_dirty = true;
+    }
+    
+    fn is_stamp_line(&self, line: &str) -> bool {
+        is_timestamp_line(line) || self.entry_format.stamp_of(line).is_some()
+    }
+    
+    // Whether an entry starts on this row: a timestamp line, or an entry's content line that
+    // isn't directly under one (the timestamp and its content are a single entry)
+    fn is_entry_start(&self, row: usize) -> bool {
+        let line = &self.content[row];
+        if self.is_stamp_line(line) {
+            return true;
+        }
+        self.entry_format.content_of(line).is_some() && (row == 0 || !self.is_stamp_line(&self.content[row - 1]))
+    }
+    
+    // Move to the start of the next entry, staying put after the last one
+    pub fn next_entry(&mut self) {
+        if let Some(row) = (self.cursor_row + 1..self.content.len()).find(|&row| self.is_entry_start(row)) {
+            self.cursor_row = row;
+            self.cursor_col = 0;
+            self.adjust_scroll();
+        }
+    }
+    
+    // Move to the start of the previous entry, staying put before the first one
+    pub fn previous_entry(&mut self) {
+        if let Some(row) = (0..self.cursor_row).rev().find(|&row| self.is_entry_start(row)) {
+            self.cursor_row = row;
+            self.cursor_col = 0;
+            self.adjust_scroll();
+        }
+    }
+    
     // Adjust scroll to keep cursor in view
     fn adjust_scroll(&mut self) {
         // This will be called with visible_height from the UI
@@ -198,4 +354,93 @@ impl TextEditor {
     pub fn get_content(&self) -> String {
         self.content.join("\n")
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "let café = foo(bar, 42);  // note";
+
+    fn editor_at(content: &str, row: usize, col: usize) -> TextEditor {
+        let mut editor = TextEditor::new("test".to_string(), content.to_string());
+        editor.cursor_row = row;
+        editor.cursor_col = col;
+        editor
+    }
+
+    #[test]
+    fn move_word_right_stops_at_each_word_and_punctuation_run() {
+        let mut editor = editor_at(SAMPLE, 0, 0);
+        let mut stops = Vec::new();
+        while editor.cursor_col < SAMPLE.chars().count() {
+            editor.move_word_right();
+            stops.push(editor.cursor_col);
+        }
+        assert_eq!(stops, [4, 9, 11, 14, 15, 18, 20, 22, 26, 29, 33]);
+    }
+
+    #[test]
+    fn move_word_left_stops_at_each_word_and_punctuation_run() {
+        let mut editor = editor_at(SAMPLE, 0, SAMPLE.chars().count());
+        let mut stops = Vec::new();
+        while editor.cursor_col > 0 {
+            editor.move_word_left();
+            stops.push(editor.cursor_col);
+        }
+        assert_eq!(stops, [29, 26, 22, 20, 18, 15, 14, 11, 9, 4, 0]);
+    }
+
+    #[test]
+    fn word_movement_wraps_between_lines() {
+        let mut editor = editor_at("first\nsecond", 0, 5);
+        editor.move_word_right();
+        assert_eq!((editor.cursor_row, editor.cursor_col), (1, 0));
+        editor.move_word_left();
+        assert_eq!((editor.cursor_row, editor.cursor_col), (0, 5));
+    }
+
+    #[test]
+    fn delete_word_removes_a_multibyte_word() {
+        // Cursor just after "café"
+        let mut editor = editor_at(SAMPLE, 0, 8);
+        editor.delete_word();
+        assert_eq!(editor.content[0], "let  = foo(bar, 42);  // note");
+        assert_eq!(editor.cursor_col, 4);
+        assert!(editor.is_dirty);
+    }
+
+    #[test]
+    fn delete_word_at_line_start_joins_with_the_previous_line() {
+        let mut editor = editor_at("first\nsecond", 1, 0);
+        editor.delete_word();
+        assert_eq!(editor.content, ["firstsecond"]);
+        assert_eq!((editor.cursor_row, editor.cursor_col), (0, 5));
+    }
+
+    #[test]
+    fn duplicate_last_line_appends_a_copy_and_moves_onto_it() {
+        let mut editor = editor_at("one\ntwo", 1, 2);
+        editor.duplicate_line();
+        assert_eq!(editor.content, ["one", "two", "two"]);
+        assert_eq!((editor.cursor_row, editor.cursor_col), (2, 2));
+        assert!(editor.is_dirty);
+    }
+
+    #[test]
+    fn delete_only_line_leaves_one_empty_line() {
+        let mut editor = editor_at("only", 0, 3);
+        editor.delete_line();
+        assert_eq!(editor.content, [""]);
+        assert_eq!((editor.cursor_row, editor.cursor_col), (0, 0));
+        assert!(editor.is_dirty);
+    }
+
+    #[test]
+    fn delete_last_line_moves_up_and_clamps_the_column() {
+        let mut editor = editor_at("ab\nlonger line", 1, 8);
+        editor.delete_line();
+        assert_eq!(editor.content, ["ab"]);
+        assert_eq!((editor.cursor_row, editor.cursor_col), (0, 2));
+    }
+}
@@ -0,0 +1,114 @@
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+
+// A file or directory under the data directory, with directory sizes summed over their contents
+struct Entry {
+    name: String,
+    path: PathBuf,
+    size: u64,
+    // None for files
+    children: Option<Vec<Entry>>,
+}
+
+// Walk `path` recursively. Symlinks are listed but not followed, so a link back up the tree
+// can't loop; unreadable entries are skipped rather than aborting the listing.
+fn walk(path: &Path, name: String) -> Option<Entry> {
+    let metadata = fs::symlink_metadata(path).ok()?;
+    if !metadata.is_dir() {
+        return Some(Entry { name, path: path.to_path_buf(), size: metadata.len(), children: None });
+    }
+
+    let mut children: Vec<Entry> = fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| walk(&entry.path(), entry.file_name().to_string_lossy().into_owned()))
+                .collect()
+        })
+        .unwrap_or_default();
+    // Directories first, then files, each alphabetically
+    children.sort_by(|a, b| b.children.is_some().cmp(&a.children.is_some()).then_with(|| a.name.cmp(&b.name)));
+    let size = children.iter().map(|child| child.size).sum();
+    Some(Entry { name, path: path.to_path_buf(), size, children: Some(children) })
+}
+
+// Human-readable size: bytes below 1 KB, otherwise one decimal in KB/MB/GB
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+fn print_children(children: &[Entry], indent: &str) {
+    for (i, child) in children.iter().enumerate() {
+        let last = i + 1 == children.len();
+        let (branch, continuation) = if last { ("└── ", "    ") } else { ("├── ", "│   ") };
+        let slash = if child.children.is_some() { "/" } else { "" };
+        println!("{}{}{}{}  {}", indent, branch, child.name, slash, format_size(child.size));
+        if let Some(grandchildren) = &child.children {
+            print_children(grandchildren, &format!("{}{}", indent, continuation));
+        }
+    }
+}
+
+fn to_json(entry: &Entry) -> serde_json::Value {
+    let mut value = serde_json::json!({
+        "name": entry.name,
+        "path": entry.path.to_string_lossy(),
+        "type": if entry.children.is_some() { "dir" } else { "file" },
+        "size": entry.size,
+    });
+    if let Some(children) = &entry.children {
+        value["children"] = children.iter().map(to_json).collect();
+    }
+    value
+}
+
+// Print the database file and a tree of the data directory (aide files, task logs, templates)
+// with sizes, or the same as JSON
+pub fn print_data_files(as_json: bool) -> Result<()> {
+    let roots: Vec<(PathBuf, Option<Entry>)> = [paths::db_path()?, paths::aide_dir()?]
+        .into_iter()
+        .map(|path| {
+            let entry = walk(&path, path.to_string_lossy().into_owned());
+            (path, entry)
+        })
+        .collect();
+
+    if as_json {
+        let json_roots: Vec<serde_json::Value> = roots
+            .iter()
+            .map(|(path, entry)| match entry {
+                Some(entry) => to_json(entry),
+                None => serde_json::json!({ "path": path.to_string_lossy(), "type": "missing" }),
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&json_roots)?);
+        return Ok(());
+    }
+
+    for (path, entry) in &roots {
+        match entry {
+            Some(entry) => {
+                let slash = if entry.children.is_some() { "/" } else { "" };
+                println!("{}{}  {}", entry.name, slash, format_size(entry.size));
+                if let Some(children) = &entry.children {
+                    print_children(children, "");
+                }
+            }
+            None => println!("{}  (missing)", path.display()),
+        }
+    }
+    Ok(())
+}
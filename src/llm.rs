@@ -1,2 +0,0 @@
-pub mod phi_model;
-pub mod command_processor;
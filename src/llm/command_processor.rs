@@ -1,7 +1,18 @@
-use super::phi_model::PhiModel;
-use anyhow::{Result, Context};
+use super::phi_model::{GenerationOptions, LlmBackend, PhiModel};
+use anyhow::{Result, Context, anyhow};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// A generated command together with where it came from
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QueryAnswer {
+    pub query: String,
+    pub command: String,
+    pub model: String,
+    /// True when the answer was served from the in-memory cache instead of the model
+    pub cached: bool,
+}
+
 pub struct CommandProcessor {
     phi_model: PhiModel,
     command_cache: HashMap<String, String>,
@@ -20,15 +31,31 @@ impl CommandProcessor {
         })
     }
     
-    pub async fn process_query(&mut self, query: &str) -> Result<String> {
+    pub fn set_generation_options(&mut self, options: GenerationOptions) {
+        self.phi_model.set_options(options);
+        // Cached answers were produced with the previous settings
+        self.command_cache.clear();
+    }
+    
+    pub fn set_backend(&mut self, backend: LlmBackend, api_key: Option<String>) {
+        self.phi_model.set_backend(backend, api_key);
+        self.command_cache.clear();
+    }
+    
+    /// Check that the server has the model before asking it anything
+    pub async fn ensure_model_available(&self) -> Result<()> {
+        self.phi_model.ensure_model_available().await
+    }
+    
+    pub async fn process_query(&mut self, query: &str) -> Result<QueryAnswer> {
         // Check cache first
         if let Some(cached_command) = self.command_cache.get(query) {
-            return Ok(cached_command.clone());
+            return Ok(self.answer(query, cached_command.clone(), true));
         }
         
-        // Generate command using Ollama
+        // Generate command using the configured backend
         let command = self.phi_model.generate_command(query).await
-            .context("Failed to generate command with Ollama")?;
+            .with_context(|| self.generate_error())?;
         
         // Post-process the command to ensure it's clean
         let cleaned_command = self.clean_command(&command);
@@ -36,7 +63,60 @@ impl CommandProcessor {
         // Cache the result
         self.command_cache.insert(query.to_string(), cleaned_command.clone());
         
-        Ok(cleaned_command)
+        Ok(self.answer(query, cleaned_command, false))
+    }
+    
+    /// Like `process_query`, but injects reference notes into the prompt.
+    /// If the model fails with the notes (e.g. they overflow its context window),
+    /// retries with progressively less of them, ending with the bare question.
+    pub async fn process_query_with_context(&mut self, query: &str, context: &str, max_context_chars: usize) -> Result<QueryAnswer> {
+        let cache_key = format!("{}\u{0}{}", query, context);
+        if let Some(cached_command) = self.command_cache.get(&cache_key) {
+            return Ok(self.answer(query, cached_command.clone(), true));
+        }
+        
+        let context_len = context.chars().count();
+        let first_budget = context_len.min(max_context_chars);
+        let mut budgets = vec![first_budget, first_budget / 2, first_budget / 4, 0];
+        budgets.dedup();
+        
+        let mut last_error = None;
+        for (tier, budget) in budgets.iter().enumerate() {
+            // Keep the most recent notes, which sit at the end of the context
+            let notes: String = context.chars().skip(context_len - budget).collect();
+            match self.phi_model.generate_command_with_context(query, Some(&notes)).await {
+                Ok(command) if !command.trim().is_empty() => {
+                    log::debug!("LLM answered at context tier {} ({} chars of notes)", tier, budget);
+                    let cleaned_command = self.clean_command(&command);
+                    self.command_cache.insert(cache_key, cleaned_command.clone());
+                    return Ok(self.answer(query, cleaned_command, false));
+                }
+                Ok(_) => {
+                    log::debug!("Empty LLM answer with {} chars of notes, retrying with less", budget);
+                    last_error = Some(anyhow!("Model returned an empty response"));
+                }
+                Err(e) => {
+                    log::debug!("LLM failed with {} chars of notes ({}), retrying with less", budget, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        
+        Err(last_error.unwrap_or_else(|| anyhow!("No attempts made")))
+            .with_context(|| self.generate_error())
+    }
+    
+    fn generate_error(&self) -> String {
+        format!("Failed to generate command with {}", self.phi_model.backend().label())
+    }
+    
+    fn answer(&self, query: &str, command: String, cached: bool) -> QueryAnswer {
+        QueryAnswer {
+            query: query.to_string(),
+            command,
+            model: self.phi_model.model_name().to_string(),
+            cached,
+        }
     }
     
     fn clean_command(&self, command: &str) -> String {
@@ -68,14 +148,4 @@ impl CommandProcessor {
         
         cleaned.to_string()
     }
-    
-    pub fn get_cache_stats(&self) -> (usize, Vec<String>) {
-        let count = self.command_cache.len();
-        let queries: Vec<String> = self.command_cache.keys().cloned().collect();
-        (count, queries)
-    }
-    
-    pub fn clear_cache(&mut self) {
-        self.command_cache.clear();
-    }
 }
\ No newline at end of file
@@ -1,33 +1,46 @@
-mod phi_model;
-mod command_processor;
-
+use aide_llm::{command_processor, phi_model};
 use anyhow::Result;
+use crossterm::{cursor, execute, terminal};
 use std::io::{self, Write};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args: Vec<String> = std::env::args().collect();
-    // Read model config from environment or use defaults
-    let base_url = std::env::var("OLLAMA_BASE_URL").unwrap_or_else(|_| "http://localhost:11434".to_string());
-    let model_name = std::env::var("OLLAMA_MODEL_NAME").unwrap_or_else(|_| "qwen2.5-coder:0.5b".to_string());
+    // Same order as `aide ask`: environment, then the stored config keys, then defaults
+    let base_url = std::env::var("OLLAMA_BASE_URL")
+        .ok()
+        .or_else(|| stored_config("llm_base_url"))
+        .unwrap_or_else(|| phi_model::DEFAULT_BASE_URL.to_string());
+    let model_name = std::env::var("OLLAMA_MODEL_NAME")
+        .ok()
+        .or_else(|| stored_config("llm_model"))
+        .unwrap_or_else(|| phi_model::DEFAULT_MODEL_NAME.to_string());
     // Initialize the command processor (connects to Ollama)
-    let mut processor = command_processor::CommandProcessor::new(base_url, model_name).await?;
+    let mut processor = command_processor::CommandProcessor::new(base_url, model_name.clone()).await?;
 
     if args.len() > 1 {
         // Direct query mode - takes input and returns command
         let query = args[1..].join(" ");
-        let command = processor.process_query(&query).await?;
-        println!("{}", command);
+        let answer = processor.process_query(&query).await?;
+        println!("{}", answer.command);
     } else {
         // Interactive mode
-        run_interactive_mode(&mut processor).await?;
+        run_interactive_mode(&mut processor, &model_name).await?;
     }
 
     Ok(())
 }
 
-async fn run_interactive_mode(processor: &mut command_processor::CommandProcessor) -> Result<()> {
-    println!("🤖 Command Helper (powered by qwen2.5-coder:0.5b)");
+// A value set with `aide set`, read straight from ~/.aide.db; None when there is no database yet
+fn stored_config(key: &str) -> Option<String> {
+    let path = dirs::home_dir()?.join(".aide.db");
+    let conn = rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY).ok()?;
+    conn.query_row("SELECT value FROM config_data WHERE key_name = ?1", [key], |row| row.get(0))
+        .ok()
+}
+
+async fn run_interactive_mode(processor: &mut command_processor::CommandProcessor, model_name: &str) -> Result<()> {
+    println!("🤖 Command Helper (powered by {})", model_name);
     println!("Enter what you want to do and get the exact command!");
     println!("Type 'exit' to quit.\n");
 
@@ -49,13 +62,11 @@ async fn run_interactive_mode(processor: &mut command_processor::CommandProcesso
                 print!("🧠 Generating command...");
                 io::stdout().flush()?;
                 
-                match processor.process_query(query).await {
-                    Ok(command) => {
-                        println!("\r✅ Command: {}", command);
-                    }
-                    Err(e) => {
-                        println!("\r❌ Error: {}", e);
-                    }
+                let result = processor.process_query(query).await;
+                clear_status_line()?;
+                match result {
+                    Ok(answer) => print_block("✅ Command:", &answer.command),
+                    Err(e) => print_block("❌ Error:", &e.to_string()),
                 }
             }
         }
@@ -64,3 +75,23 @@ async fn run_interactive_mode(processor: &mut command_processor::CommandProcesso
 
     Ok(())
 }
+
+// Erase the "Generating command..." line so the answer starts on a clean line
+fn clear_status_line() -> Result<()> {
+    let mut stdout = io::stdout();
+    execute!(stdout, cursor::MoveToColumn(0), terminal::Clear(terminal::ClearType::CurrentLine))?;
+    Ok(())
+}
+
+// Print a labelled answer; multi-line text goes below the label, indented, so every line lines up
+fn print_block(label: &str, text: &str) {
+    let text = text.trim_end();
+    if text.contains('\n') {
+        println!("{}", label);
+        for line in text.lines() {
+            println!("   {}", line);
+        }
+    } else {
+        println!("{} {}", label, text);
+    }
+}
@@ -1,12 +1,39 @@
-use anyhow::{Result, Context};
+use anyhow::{Result, Context, bail};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
+/// Used when neither OLLAMA_BASE_URL nor the `llm_base_url` config key is set
+pub const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+/// Used when neither OLLAMA_MODEL_NAME nor the `llm_model` config key is set
+pub const DEFAULT_MODEL_NAME: &str = "qwen2.5-coder:0.5b";
+
+const SYSTEM_PROMPT: &str = "You are a command-line expert. Convert natural language requests into exact shell commands. Return only the command, no explanation.";
+
 #[derive(Serialize)]
 struct OllamaRequest {
     model: String,
     prompt: String,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<GenerationOptions>,
+}
+
+/// Sampling parameters passed through Ollama's `options` field.
+/// Unset values are left out so the model's own defaults apply.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct GenerationOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_predict: Option<i32>,
+}
+
+impl GenerationOptions {
+    pub fn is_empty(&self) -> bool {
+        self.temperature.is_none() && self.top_p.is_none() && self.num_predict.is_none()
+    }
 }
 
 #[derive(Deserialize)]
@@ -14,10 +41,95 @@ struct OllamaResponse {
     response: String,
 }
 
+#[derive(Serialize)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+/// Request body for OpenAI-compatible `/v1/chat/completions` servers
+#[derive(Serialize)]
+struct ChatCompletionRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<i32>,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatResponseMessage {
+    content: String,
+}
+
+/// Ollama's `/api/tags` listing of pulled models
+#[derive(Deserialize)]
+struct OllamaTags {
+    models: Vec<OllamaTag>,
+}
+
+#[derive(Deserialize)]
+struct OllamaTag {
+    name: String,
+}
+
+/// OpenAI-compatible `/v1/models` listing
+#[derive(Deserialize)]
+struct ModelList {
+    data: Vec<ModelListEntry>,
+}
+
+#[derive(Deserialize)]
+struct ModelListEntry {
+    id: String,
+}
+
+/// Which HTTP API the model server speaks
+#[derive(Debug, Clone, PartialEq)]
+pub enum LlmBackend {
+    Ollama,
+    OpenAi,
+}
+
+impl LlmBackend {
+    /// Parse a backend name as used by the `llm_backend` config key
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "ollama" => Some(LlmBackend::Ollama),
+            "openai" => Some(LlmBackend::OpenAi),
+            _ => None,
+        }
+    }
+
+    /// Human-readable name of the server type, for messages
+    pub fn label(&self) -> &'static str {
+        match self {
+            LlmBackend::Ollama => "Ollama",
+            LlmBackend::OpenAi => "the OpenAI-compatible server",
+        }
+    }
+}
+
 pub struct PhiModel {
     client: Client,
     base_url: String,
     model_name: String,
+    options: GenerationOptions,
+    backend: LlmBackend,
+    api_key: Option<String>,
 }
 
 impl PhiModel {
@@ -28,26 +140,127 @@ impl PhiModel {
             client,
             base_url: base_url.into(),
             model_name: model_name.into(),
+            options: GenerationOptions::default(),
+            backend: LlmBackend::Ollama,
+            api_key: None,
         })
     }
     
+    /// Set the sampling parameters used for subsequent requests
+    pub fn set_options(&mut self, options: GenerationOptions) {
+        self.options = options;
+    }
+    
+    /// Switch the API used to talk to the server (Ollama by default)
+    pub fn set_backend(&mut self, backend: LlmBackend, api_key: Option<String>) {
+        self.backend = backend;
+        self.api_key = api_key;
+    }
+    
+    /// API currently used to talk to the server
+    pub fn backend(&self) -> &LlmBackend {
+        &self.backend
+    }
+    
+    /// Name of the model requests are sent to
+    pub fn model_name(&self) -> &str {
+        &self.model_name
+    }
+    
+    /// Names of the models the server can run (for Ollama, the ones that have been pulled)
+    pub async fn list_models(&self) -> Result<Vec<String>> {
+        let base_url = self.base_url.trim_end_matches('/');
+        let url = match self.backend {
+            LlmBackend::Ollama => format!("{}/api/tags", base_url),
+            LlmBackend::OpenAi => format!("{}/v1/models", base_url),
+        };
+        let mut builder = self.client.get(&url);
+        if let (LlmBackend::OpenAi, Some(api_key)) = (&self.backend, &self.api_key) {
+            builder = builder.bearer_auth(api_key);
+        }
+        
+        let response = builder
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach the model server at {}", base_url))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            bail!("Listing models returned {}: {}", status, body.trim());
+        }
+        
+        let names = match self.backend {
+            LlmBackend::Ollama => {
+                let tags: OllamaTags = response.json().await.context("Failed to parse Ollama model list")?;
+                tags.models.into_iter().map(|model| model.name).collect()
+            }
+            LlmBackend::OpenAi => {
+                let list: ModelList = response.json().await.context("Failed to parse OpenAI-compatible model list")?;
+                list.data.into_iter().map(|model| model.id).collect()
+            }
+        };
+        Ok(names)
+    }
+    
+    /// Fail with the available models listed unless the server has this model.
+    /// Ollama names without a tag mean `:latest`, so `llama3.2` matches `llama3.2:latest`.
+    pub async fn ensure_model_available(&self) -> Result<()> {
+        let models = self.list_models().await?;
+        let wanted = self.model_name.as_str();
+        if models.iter().any(|name| name == wanted || name.strip_suffix(":latest") == Some(wanted)) {
+            return Ok(());
+        }
+        
+        let hint = match self.backend {
+            LlmBackend::Ollama => format!(" Pull it with `ollama pull {}`.", wanted),
+            LlmBackend::OpenAi => String::new(),
+        };
+        if models.is_empty() {
+            bail!("Model '{}' isn't available: the server has no models.{}", wanted, hint);
+        }
+        bail!("Model '{}' isn't available.{} Available: {}", wanted, hint, models.join(", "));
+    }
+    
     pub async fn generate_command(&self, prompt: &str) -> Result<String> {
-        let system_prompt = "You are a command-line expert. Convert natural language requests into exact shell commands. Return only the command, no explanation.";
+        self.generate_command_with_context(prompt, None).await
+    }
+    
+    /// Generate a command, giving the model extra reference notes to draw on
+    pub async fn generate_command_with_context(&self, prompt: &str, context: Option<&str>) -> Result<String> {
+        let system_prompt = match context {
+            Some(notes) if !notes.is_empty() => format!("{}\n\nRelevant notes:\n{}", SYSTEM_PROMPT, notes),
+            _ => SYSTEM_PROMPT.to_string(),
+        };
+        match self.backend {
+            LlmBackend::Ollama => self.generate_with_ollama(&system_prompt, prompt).await,
+            LlmBackend::OpenAi => self.generate_with_openai(&system_prompt, prompt).await,
+        }
+    }
+    
+    async fn generate_with_ollama(&self, system_prompt: &str, prompt: &str) -> Result<String> {
         let full_prompt = format!("{}\n\nUser request: {}\nCommand:", system_prompt, prompt);
         
         let request = OllamaRequest {
             model: self.model_name.clone(),
             prompt: full_prompt,
             stream: false,
+            options: if self.options.is_empty() { None } else { Some(self.options.clone()) },
         };
         
         let response = self.client
-            .post(&format!("{}/api/generate", self.base_url))
+            .post(format!("{}/api/generate", self.base_url))
             .json(&request)
             .send()
             .await
             .context("Failed to send request to Ollama")?;
         
+        // Ollama reports problems such as an oversized prompt as a non-2xx status
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            bail!("Ollama returned {}: {}", status, body.trim());
+        }
+        
         let ollama_response: OllamaResponse = response
             .json()
             .await
@@ -55,4 +268,48 @@ impl PhiModel {
         
         Ok(ollama_response.response.trim().to_string())
     }
-}
\ No newline at end of file
+    
+    async fn generate_with_openai(&self, system_prompt: &str, prompt: &str) -> Result<String> {
+        let request = ChatCompletionRequest {
+            model: self.model_name.clone(),
+            messages: vec![
+                ChatMessage { role: "system".to_string(), content: system_prompt.to_string() },
+                ChatMessage { role: "user".to_string(), content: prompt.to_string() },
+            ],
+            temperature: self.options.temperature,
+            top_p: self.options.top_p,
+            max_tokens: self.options.num_predict,
+        };
+        
+        let mut builder = self.client
+            .post(format!("{}/v1/chat/completions", self.base_url.trim_end_matches('/')))
+            .json(&request);
+        if let Some(api_key) = &self.api_key {
+            builder = builder.bearer_auth(api_key);
+        }
+        
+        let response = builder
+            .send()
+            .await
+            .context("Failed to send request to OpenAI-compatible server")?;
+        
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            bail!("OpenAI-compatible server returned {}: {}", status, body.trim());
+        }
+        
+        let chat_response: ChatCompletionResponse = response
+            .json()
+            .await
+            .context("Failed to parse OpenAI-compatible response")?;
+        
+        let content = chat_response.choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .context("OpenAI-compatible response contained no choices")?;
+        
+        Ok(content.trim().to_string())
+    }
+}
@@ -4,83 +4,482 @@ mod database;
 mod ui;
 mod editor;
 mod tfidf;
-mod llm;
+mod clipboard;
+mod theme;
+mod diff;
+mod paths;
+mod output;
+mod picker;
+mod files;
+mod crypto;
+mod watch;
+mod timing;
+mod table;
 
+use aide_llm as llm;
 use anyhow::Result;
+use std::io::{self, Write};
+use std::time::Duration;
 use clap::{CommandFactory, Parser};
-use cli::{Cli, Commands};
+use cli::{AliasAction, Cli, Commands, LockAction};
 use database::Database;
+use models::{CreatedWithin, ItemKind, Outcome, TaskMove};
+use output::emit;
 use ui::run_tui;
 
+// Upper bound on aide notes injected into an `ask` prompt unless llm_max_context_chars is set
+const DEFAULT_MAX_CONTEXT_CHARS: usize = 4000;
+
+// Read a config key and parse it, treating unparseable values as unset
+fn parse_config<T: std::str::FromStr>(db: &Database, key: &str) -> Result<Option<T>> {
+    Ok(db.get_config_value(key)?.and_then(|value| value.trim().parse().ok()))
+}
+
+// Print an `ask` answer, then copy or run its command if asked to
+fn deliver_answer(answer: &llm::command_processor::QueryAnswer, json: bool, copy: bool, exec: bool) -> Result<Outcome> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(answer)?);
+    } else {
+        println!("{}", answer.command);
+    }
+
+    if copy {
+        match clipboard::copy_to_clipboard(&answer.command) {
+            // stdout holds only the JSON document, so it can be piped to jq
+            Ok(()) if json => {
+                if !output::is_quiet() {
+                    eprintln!("Copied to clipboard");
+                }
+            }
+            Ok(()) => emit!("Copied to clipboard"),
+            Err(e) => {
+                eprintln!("Could not copy to clipboard: {}", e);
+                return Ok(Outcome::Failed);
+            }
+        }
+    } else if exec {
+        return run_confirmed(&answer.command);
+    }
+    Ok(Outcome::Done)
+}
+
+// Ask the configured model a question, optionally with an aide's notes as context.
+// Returns the outcome instead of an answer when the question couldn't be asked.
+fn ask_question(db: &Database, question: &str, options: llm::phi_model::GenerationOptions,
+                model: Option<String>, context: Option<String>) -> Result<std::result::Result<llm::command_processor::QueryAnswer, Outcome>> {
+    let backend_name = db.get_config_value("llm_backend")?.unwrap_or_else(|| "ollama".to_string());
+    let backend = match llm::phi_model::LlmBackend::from_name(&backend_name) {
+        Some(backend) => backend,
+        None => {
+            eprintln!("Error: Unknown llm_backend '{}'. Use 'ollama' or 'openai'.", backend_name);
+            return Ok(Err(Outcome::Invalid));
+        }
+    };
+    // The environment wins over the stored key so secrets needn't live in the database
+    let api_key = std::env::var("OPENAI_API_KEY").ok().or(db.get_config_value("llm_api_key")?);
+    
+    let notes = match context {
+        Some(aide_name) => match db.resolve_aide_name(&aide_name)? {
+            Some(actual_aide_name) => Some(
+                db.get_aide_entries(&actual_aide_name)?
+                    .iter()
+                    .map(|entry| format!("- {}", entry.input_text))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            None => return Ok(Err(Outcome::NotFound)),
+        },
+        None => None,
+    };
+    let max_context_chars = parse_config(db, "llm_max_context_chars")?.unwrap_or(DEFAULT_MAX_CONTEXT_CHARS);
+    
+    // The environment wins over the llm_base_url/llm_model keys, and --model over both
+    let base_url = match std::env::var("OLLAMA_BASE_URL") {
+        Ok(base_url) => base_url,
+        Err(_) => db.get_config_value("llm_base_url")?.unwrap_or_else(|| llm::phi_model::DEFAULT_BASE_URL.to_string()),
+    };
+    let model_name = match (model.clone(), std::env::var("OLLAMA_MODEL_NAME")) {
+        (Some(model), _) => model,
+        (None, Ok(model)) => model,
+        (None, Err(_)) => db.get_config_value("llm_model")?.unwrap_or_else(|| llm::phi_model::DEFAULT_MODEL_NAME.to_string()),
+    };
+    
+    // Call LLM
+    let rt = tokio::runtime::Runtime::new()?;
+    let answer = rt.block_on(async {
+        let mut processor = llm::command_processor::CommandProcessor::new(base_url, model_name).await?;
+        processor.set_generation_options(options);
+        processor.set_backend(backend, api_key);
+        // Checked up front, since a typo or an unpulled model otherwise only shows up
+        // as an opaque generation error
+        if model.is_some() {
+            processor.ensure_model_available().await?;
+        }
+        match &notes {
+            Some(notes) => processor.process_query_with_context(question, notes, max_context_chars).await,
+            None => processor.process_query(question).await,
+        }
+    })?;
+    Ok(Ok(answer))
+}
+
+// Keep the answer for `aide ask --last`. Failing to is only worth a warning.
+fn save_last_answer(answer: &llm::command_processor::QueryAnswer) {
+    let saved = paths::last_answer_file().and_then(|path| {
+        paths::create_parent_dir(&path)?;
+        std::fs::write(&path, serde_json::to_string_pretty(answer)?)?;
+        Ok(())
+    });
+    if let Err(e) = saved {
+        eprintln!("Warning: could not save the answer for `aide ask --last`: {}", e);
+    }
+}
+
+// The answer saved by the last `aide ask`, if there was one
+fn load_last_answer() -> Result<Option<llm::command_processor::QueryAnswer>> {
+    let path = paths::last_answer_file()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&std::fs::read_to_string(&path)?)?))
+}
+
+// The --task/--aide restriction of pin/unpin, if any
+fn item_kind(task: bool, aide: bool) -> Option<ItemKind> {
+    if task {
+        Some(ItemKind::Task)
+    } else if aide {
+        Some(ItemKind::Aide)
+    } else {
+        None
+    }
+}
+
+// The --today/--week restriction of task-list and aide-list, if any
+fn created_within(today: bool, week: bool) -> Option<CreatedWithin> {
+    if today {
+        Some(CreatedWithin::Today)
+    } else if week {
+        Some(CreatedWithin::Week)
+    } else {
+        None
+    }
+}
+
+// The text of `aide "<note>"` when clap rejected it as an unknown subcommand. Only a single
+// argument containing whitespace counts, so a mistyped subcommand (`aide task-lst`) still
+// errors instead of being captured as a note.
+fn bare_capture_text(error: &clap::Error, args: &[String]) -> Option<String> {
+    if error.kind() != clap::error::ErrorKind::InvalidSubcommand {
+        return None;
+    }
+    match &args[1..] {
+        [text] if !text.starts_with('-') && text.trim().contains(char::is_whitespace) => Some(text.clone()),
+        _ => None,
+    }
+}
+
+// Replace a leading alias (the first non-flag argument) with the command it stands for,
+// repeatedly, since an alias may point at another alias. Errors on a loop.
+fn expand_aliases(db: &Database, mut args: Vec<String>) -> Result<Vec<String>> {
+    let mut seen = Vec::new();
+    while let Some(position) = args.iter().skip(1).position(|arg| !arg.starts_with('-')).map(|i| i + 1) {
+        let name = &args[position];
+        if cli::is_subcommand(name) {
+            break;
+        }
+        let Some(target) = db.alias_target(name)? else {
+            break;
+        };
+        if seen.contains(name) {
+            anyhow::bail!("alias loop: {} -> {}", seen.join(" -> "), name);
+        }
+        seen.push(name.clone());
+        args.splice(position..=position, target.split_whitespace().map(String::from));
+    }
+    Ok(args)
+}
+
+// Let the user choose a task or aide name when it was left off the command line.
+// Prints why and returns None when there is nothing to pick or the picker is cancelled.
+fn pick_name(kind: &str, names: Vec<String>) -> Result<Option<String>> {
+    if names.is_empty() {
+        let create = if kind == "Task" { "aide task <name>" } else { "aide create <name>" };
+        eprintln!("No {}s yet. Create one with `{}`.", kind.to_lowercase(), create);
+        return Ok(None);
+    }
+    let choice = match picker::pick(kind, &names) {
+        Ok(choice) => choice,
+        Err(err) => {
+            eprintln!("No {} name given and {}", kind.to_lowercase(), err);
+            return Ok(None);
+        }
+    };
+    if choice.is_none() {
+        eprintln!("Operation cancelled.");
+    }
+    Ok(choice)
+}
+
+// Ask before running a generated shell command with the terminal attached, so it can be interactive
+fn run_confirmed(command: &str) -> Result<Outcome> {
+    eprint!("Run `{}`? (y/n): ", command);
+    io::stderr().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let answer = input.trim().to_lowercase();
+    if answer != "y" && answer != "yes" {
+        eprintln!("Operation cancelled.");
+        return Ok(Outcome::Cancelled);
+    }
+    
+    let status = if cfg!(windows) {
+        std::process::Command::new("cmd").args(["/C", command]).status()
+    } else {
+        std::process::Command::new("sh").args(["-c", command]).status()
+    };
+    match status {
+        Ok(status) if status.success() => Ok(Outcome::Done),
+        Ok(status) => {
+            let code = status.code().map(|c| c.to_string()).unwrap_or_else(|| "signal".to_string());
+            eprintln!("Command exited with status {}", code);
+            Ok(Outcome::Failed)
+        }
+        Err(e) => {
+            eprintln!("Failed to run command '{}': {}", command, e);
+            Ok(Outcome::Failed)
+        }
+    }
+}
+
 fn main() -> Result<()> {
-    let cli = Cli::parse();
-    let mut db = Database::new()?;
+    let started = std::time::Instant::now();
+    // Checked before opening, since opening creates the file
+    let db_existed = paths::db_path()?.exists();
+    let mut args: Vec<String> = std::env::args().collect();
+    // Only a first argument that isn't a built-in command can be an alias; the database is
+    // opened early just for that, so `aide --help` and friends still don't create it
+    let mut early_db = None;
+    let first = args.iter().skip(1).find(|arg| !arg.starts_with('-'));
+    if db_existed && first.is_some_and(|first| !cli::is_subcommand(first)) {
+        let db = Database::new()?;
+        args = expand_aliases(&db, args)?;
+        early_db = Some(db);
+    }
+    
+    let mut cli = match Cli::try_parse_from(&args) {
+        Ok(cli) => cli,
+        // `aide "buy milk"` is shorthand for `aide capture "buy milk"`
+        Err(e) => match bare_capture_text(&e, &args) {
+            Some(text) => Cli::parse_from(["aide", "capture", text.as_str()]),
+            None => e.exit(),
+        },
+    };
+    // Kept aside, since default_command below may replace `cli`
+    let no_alt_screen = cli.no_alt_screen;
+    let verbose = cli.verbose;
+    output::set_quiet(cli.quiet);
+    timing::set_enabled(cli.timing);
+    let mut db = match early_db {
+        Some(db) => db,
+        None => Database::new()?,
+    };
+    db.verbose = verbose;
+    
+    // A bare `aide` runs default_command (e.g. "task-list --limit 10") when configured
+    if cli.command.is_none() {
+        if let Some(default_command) = db.get_config_value("default_command")? {
+            let args = std::iter::once("aide").chain(default_command.split_whitespace()).map(String::from);
+            match Cli::try_parse_from(expand_aliases(&db, args.collect())?) {
+                Ok(parsed) => cli = parsed,
+                Err(e) => {
+                    let reason = e.to_string();
+                    eprintln!("Ignoring invalid default_command '{}': {}",
+                              default_command, reason.lines().next().unwrap_or_default());
+                }
+            }
+        }
+    }
     
+    // Commands that can fail in a handled way (not found, cancelled, bad input) report it here
+    let mut outcome = Outcome::Done;
     match cli.command {
         Some(Commands::Create { name }) => {
-            db.create_aide(&name)?;
+            outcome = db.create_aide(&name)?;
         }
-        Some(Commands::Add { name, data, path }) => {
-            // Validate that either data or path is provided
-            match (data.as_deref(), path.as_deref()) {
-                (Some(content), None) => {
+        Some(Commands::Add { name, data, path, run, yes, clipboard }) => {
+            // Validate that exactly one of data, path, run or clipboard is provided
+            match (data.as_deref(), path.as_deref(), run.as_deref(), clipboard) {
+                (Some(content), None, None, false) => {
                     // Use provided data
-                    db.add_data(&name, content, None)?;
+                    outcome = db.add_data(&name, content, None)?;
                 }
-                (None, Some(file_path)) => {
+                (None, Some(file_path), None, false) => {
                     // Use file path
-                    db.add_data(&name, "", Some(file_path))?;
+                    outcome = db.add_data(&name, "", Some(file_path))?;
+                }
+                (None, None, Some(command), false) => {
+                    // Capture command output
+                    outcome = db.add_command_output(&name, command, yes)?;
                 }
-                (Some(_), Some(_)) => {
-                    println!("Error: Cannot specify both data and path. Use either content or -p flag.");
-                    return Ok(());
+                (None, None, None, true) => {
+                    // Pasted text keeps its lines; only surrounding whitespace is dropped
+                    outcome = match clipboard::paste_from_clipboard() {
+                        Ok(text) if text.trim().is_empty() => {
+                            eprintln!("Error: The clipboard is empty.");
+                            Outcome::Invalid
+                        }
+                        Err(arboard::Error::ContentNotAvailable) => {
+                            eprintln!("Error: The clipboard is empty.");
+                            Outcome::Invalid
+                        }
+                        Ok(text) => db.add_data(&name, text.trim(), None)?,
+                        Err(err) => {
+                            eprintln!("Error: Could not read the clipboard: {}", err);
+                            Outcome::Failed
+                        }
+                    };
                 }
-                (None, None) => {
-                    println!("Error: Must provide either content data or -p flag with file path.");
-                    return Ok(());
+                (None, None, None, false) => {
+                    eprintln!("Error: Must provide either content data, -p flag with file path, --run with a command, or --clipboard.");
+                    outcome = Outcome::Invalid;
+                }
+                _ => {
+                    eprintln!("Error: Provide only one of content data, -p flag, --run, or --clipboard.");
+                    outcome = Outcome::Invalid;
                 }
             }
         }
-        Some(Commands::Search { input_text }) => {
-            db.search_by_input(&input_text)?;
+        Some(Commands::Capture { text }) => {
+            outcome = db.capture(&text.join(" "))?;
+        }
+        Some(Commands::Search { input_text, aide, count, top, min_score, regex }) => {
+            outcome = if regex {
+                db.search_by_regex(&input_text, aide.as_deref(), count, top)?
+            } else {
+                db.search_by_input(&input_text, aide.as_deref(), count, top, min_score)?
+            };
         }
 
         Some(Commands::Set { key, value }) => {
-            db.set_config(&key, &value)?;
+            outcome = db.set_config(&key, &value)?;
         }
 
         Some(Commands::Get { key }) => {
             db.get_config(&key)?;
         }
 
+        Some(Commands::ConfigGetAll { prefix, json }) => {
+            db.print_configs_with_prefix(&prefix, json)?;
+        }
+
         Some(Commands::ConfigList) => {
             db.list_configs()?;
         }
 
         Some(Commands::ConfigDelete { key }) => {
-            db.delete_config(&key)?;
+            outcome = db.delete_config(&key)?;
+        }
+        Some(Commands::ConfigRename { old_key, new_key }) => {
+            outcome = db.rename_config(&old_key, &new_key)?;
         }
        
-        Some(Commands::Task { task_name }) => {
-            db.create_task(&task_name)?;
+        Some(Commands::Task { task_name, editor }) => {
+            outcome = db.create_task(&task_name, editor.as_deref())?;
         }
         Some(Commands::TaskStatus { task_name, status }) => {
-            db.update_task_status(&task_name, &status)?;
+            outcome = db.update_task_status(&task_name, &status)?;
+        }
+        Some(Commands::TaskStatusAll { from, to, priority, yes }) => {
+            outcome = db.update_task_status_all(from.as_deref(), &to, priority, yes)?;
         }
         Some(Commands::TaskPriority { task_name, priority }) => {
-            db.update_task_priority(&task_name, priority)?;
+            outcome = db.update_task_priority(&task_name, priority)?;
+        }
+        Some(Commands::TaskEstimate { task_name, minutes }) => {
+            outcome = db.set_task_estimate(&task_name, minutes)?;
+        }
+        Some(Commands::TaskTime { task_name, minutes }) => {
+            outcome = db.log_task_time(&task_name, minutes)?;
+        }
+        Some(Commands::TaskStats { watch, interval }) => {
+            if watch {
+                watch::watch("aide task-stats", Duration::from_secs(interval), || db.task_stats())?;
+            } else {
+                db.task_stats()?;
+            }
+        }
+        Some(Commands::TaskMove { task_name, up, down, to }) => {
+            let movement = match (up, down, to) {
+                (true, _, _) => TaskMove::Up,
+                (_, true, _) => TaskMove::Down,
+                (_, _, Some(position)) => TaskMove::To(position),
+                _ => unreachable!("clap requires one of --up, --down or --to"),
+            };
+            outcome = db.move_task(&task_name, movement)?;
+        }
+        Some(Commands::TaskList { limit, offset, format, today, week, watch, interval }) => {
+            let created = created_within(today, week);
+            if watch {
+                watch::watch("aide task-list", Duration::from_secs(interval), || db.list_tasks(limit, offset, format, created))?;
+            } else {
+                db.list_tasks(limit, offset, format, created)?;
+            }
+        }
+        Some(Commands::Pin { name, task, aide }) => {
+            outcome = db.set_pinned(&name, item_kind(task, aide), true)?;
+        }
+        Some(Commands::Unpin { name, task, aide }) => {
+            outcome = db.set_pinned(&name, item_kind(task, aide), false)?;
+        }
+        Some(Commands::TaskDep { task_name, needs, remove }) => {
+            outcome = db.set_task_dependency(&task_name, &needs, !remove)?;
+        }
+        Some(Commands::TaskGraph) => {
+            db.print_task_graph()?;
+        }
+        Some(Commands::Next) => {
+            outcome = db.next_task()?;
+        }
+        Some(Commands::TaskReport { since, completed, out }) => {
+            outcome = db.task_report(since.as_deref(), completed, out.as_deref())?;
+        }
+        Some(Commands::TaskSearch { text, min_score }) => {
+            outcome = db.search_task_logs(&text, min_score)?;
+        }
+        Some(Commands::TaskEdit { task_name, editor }) => {
+            let task_name = match task_name {
+                Some(name) => Some(name),
+                None => pick_name("Task", db.task_names()?)?,
+            };
+            outcome = match task_name {
+                Some(name) => db.edit_task(&name, editor.as_deref())?,
+                None => Outcome::Cancelled,
+            };
+        }
+        Some(Commands::TaskLogUpdate { task_name, log_text, create }) => {
+            outcome = db.add_task_log(&task_name, &log_text, create)?;
+        }
+        Some(Commands::AideList { limit, offset, today, week }) => {
+            db.list_aides(limit, offset, created_within(today, week))?;
         }
-        Some(Commands::TaskList) => {
-            db.list_tasks()?;
+        Some(Commands::Init) => {
+            db.init(db_existed)?;
         }
-        Some(Commands::TaskEdit { task_name }) => {
-            db.edit_task(&task_name)?;
+        Some(Commands::RebuildFiles { name }) => {
+            outcome = db.rebuild_files(name.as_deref())?;
         }
-        Some(Commands::TaskLogUpdate { task_name, log_text }) => {
-            db.add_task_log(&task_name, &log_text)?;
+        Some(Commands::Reindex) => {
+            db.reindex()?;
         }
-        Some(Commands::AideList) => {
-            db.list_aides()?;
+        Some(Commands::Files { json }) => {
+            files::print_data_files(json)?;
+        }
+        Some(Commands::Fsck { fix }) => {
+            outcome = db.fsck(fix)?;
+        }
+        Some(Commands::MatchDebug { kind, query }) => {
+            db.match_debug(&kind, &query)?;
         }
         Some(Commands::Clear) => {
             db.clear_all_data()?;
@@ -88,46 +487,120 @@ fn main() -> Result<()> {
         Some(Commands::Reset) => {
             db.clear_all_data()?;
         }
-        Some(Commands::Write { aide_name }) => {
-            db.write_aide(&aide_name)?;
+        Some(Commands::AideConfig { name, key, value }) => {
+            outcome = db.configure_aide(&name, key.as_deref(), value.as_deref())?;
+        }
+        Some(Commands::EntryDelete { name, index, yes }) => {
+            outcome = db.delete_aide_entry(&name, index, yes)?;
+        }
+        Some(Commands::ExportMd { name, out }) => {
+            outcome = db.export_aide_markdown(&name, out.as_deref())?;
+        }
+        Some(Commands::Write { aide_name, editor }) => {
+            let aide_name = match aide_name {
+                Some(name) => Some(name),
+                None => pick_name("Aide", db.aide_names()?)?,
+            };
+            match aide_name {
+                Some(name) => db.write_aide(&name, editor.as_deref())?,
+                None => outcome = Outcome::Cancelled,
+            }
+        }
+        Some(Commands::Streak { aide_name, weeks }) => {
+            outcome = db.print_streak(&aide_name, weeks)?;
+        }
+        Some(Commands::Clone { source, new_name }) => {
+            outcome = db.clone_aide(&source, &new_name)?;
+        }
+        Some(Commands::Encrypt { aide_name }) => {
+            outcome = db.set_aide_encryption(&aide_name, true)?;
+        }
+        Some(Commands::Decrypt { aide_name }) => {
+            outcome = db.set_aide_encryption(&aide_name, false)?;
+        }
+        Some(Commands::Tui { no_alt_screen: tui_no_alt_screen }) => {
+            run_tui(db, no_alt_screen || tui_no_alt_screen)?;
+        }
+        Some(Commands::Ask { last: true, json, copy, exec, .. }) => {
+            outcome = match load_last_answer()? {
+                Some(answer) => deliver_answer(&answer, json, copy, exec)?,
+                None => {
+                    eprintln!("No previous `aide ask` command. Ask a question first.");
+                    Outcome::NotFound
+                }
+            };
+        }
+        Some(Commands::Ask { question, temperature, top_p, num_predict, model, context, json, print_only: _, copy, exec, last: false }) => {
+            // Present unless --last, which is handled above
+            let question = question.unwrap_or_default();
+            // Flags take precedence over the llm_* config keys
+            let options = llm::phi_model::GenerationOptions {
+                temperature: temperature.or(parse_config(&db, "llm_temperature")?),
+                top_p: top_p.or(parse_config(&db, "llm_top_p")?),
+                num_predict: num_predict.or(parse_config(&db, "llm_num_predict")?),
+            };
+            
+            outcome = match ask_question(&db, &question, options, model, context)? {
+                Ok(answer) => {
+                    save_last_answer(&answer);
+                    deliver_answer(&answer, json, copy, exec)?
+                }
+                Err(outcome) => outcome,
+            };
         }
-        Some(Commands::Tui) => {
-            run_tui(db)?;
+        Some(Commands::Recent { n, json }) => {
+            db.list_recent(n, json)?;
         }
-        Some(Commands::Ask { question }) => {
-            // Call LLM and print answer
-            let rt = tokio::runtime::Runtime::new()?;
-            rt.block_on(async {
-                let base_url = std::env::var("OLLAMA_BASE_URL").unwrap_or_else(|_| "http://localhost:11434".to_string());
-                let model_name = std::env::var("OLLAMA_MODEL_NAME").unwrap_or_else(|_| "qwen2.5-coder:0.5b".to_string());
-                let mut processor = llm::command_processor::CommandProcessor::new(base_url, model_name).await?;
-                let answer = processor.process_query(&question).await?;
-                println!("{}", answer);
-                Ok::<(), anyhow::Error>(())
-            })?;
+        Some(Commands::Sql { query, json }) => {
+            db.run_sql_query(&query, json)?;
+        }
+        Some(Commands::Alias { action }) => match action {
+            AliasAction::Add { name, target } => {
+                outcome = db.add_alias(&name, &target.join(" "))?;
+            }
+            AliasAction::List => {
+                db.list_aliases()?;
+            }
+            AliasAction::Remove { name } => {
+                outcome = db.remove_alias(&name)?;
+            }
+        },
+        Some(Commands::Lock { action }) => {
+            outcome = match action {
+                LockAction::Set => db.set_tui_lock()?,
+                LockAction::Clear => db.clear_tui_lock()?,
+            };
         }
         Some(Commands::Completions { shell }) => {
             use clap_complete::{generate, Shell};
             let shell = shell.to_lowercase();
             let shell_enum = match shell.as_str() {
-                "bash" => Shell::Bash,
-                "zsh" => Shell::Zsh,
-                "fish" => Shell::Fish,
-                "elvish" => Shell::Elvish,
-                "powershell" => Shell::PowerShell,
-                _ => {
-                    println!("Unsupported shell: {}", shell);
-                    return Ok(());
-                }
+                "bash" => Some(Shell::Bash),
+                "zsh" => Some(Shell::Zsh),
+                "fish" => Some(Shell::Fish),
+                "elvish" => Some(Shell::Elvish),
+                "powershell" => Some(Shell::PowerShell),
+                _ => None,
             };
-            let mut cmd = Cli::command();
-            generate(shell_enum, &mut cmd, "aide", &mut std::io::stdout());
+            match shell_enum {
+                Some(shell_enum) => generate(shell_enum, &mut Cli::command(), "aide", &mut std::io::stdout()),
+                None => {
+                    eprintln!("Unsupported shell: {}", shell);
+                    outcome = Outcome::Invalid;
+                }
+            }
         }
         None => {
             // Default behavior: launch TUI
-            run_tui(db)?;
+            run_tui(db, no_alt_screen)?;
         }
     }
     
+    timing::report(started);
+    // The failure was already explained on stderr; the exit status lets scripts notice it
+    if !outcome.is_done() {
+        std::process::exit(1);
+    }
+    
     Ok(())
 }
@@ -4,6 +4,11 @@ pub struct TaskItem {
     pub priority: i32,
     pub status: String,
     pub created_at: String,
+    pub pinned: bool,
+    // Dependencies that aren't completed yet
+    pub blocked_by: Vec<String>,
+    pub estimate_minutes: Option<i64>,
+    pub actual_minutes: Option<i64>,
 }
 
 #[derive(Debug, Clone)]
@@ -11,15 +16,205 @@ pub struct AideItem {
     pub name: String,
     pub input_text: String,
     pub command_output: String,
+    pub pinned: bool,
+    // NULL for aides from before the column existed
+    pub created_at: Option<String>,
+}
+
+// Names of priority levels 1 (highest) to 5 (lowest)
+pub const PRIORITY_NAMES: [&str; 5] = ["highest", "high", "medium", "low", "lowest"];
+
+// The level's name for a priority, if it's in range
+pub fn priority_name(priority: i32) -> Option<&'static str> {
+    usize::try_from(priority).ok()
+        .and_then(|p| p.checked_sub(1))
+        .and_then(|i| PRIORITY_NAMES.get(i).copied())
+}
+
+// Priority for display, e.g. "2 (high)"
+pub fn priority_label(priority: i32) -> String {
+    match priority_name(priority) {
+        Some(name) => format!("{} ({})", priority, name),
+        None => priority.to_string(),
+    }
+}
+
+// A priority given as a number or as a level name (case-insensitive)
+pub fn parse_priority(input: &str) -> Option<u8> {
+    let input = input.trim();
+    if let Ok(priority) = input.parse::<u8>() {
+        return Some(priority);
+    }
+    PRIORITY_NAMES.iter()
+        .position(|name| name.eq_ignore_ascii_case(input))
+        .map(|i| i as u8 + 1)
+}
+
+// A duration in minutes for display, e.g. "1h 30m" or "45m"
+pub fn format_minutes(minutes: i64) -> String {
+    let sign = if minutes < 0 { "-" } else { "" };
+    let minutes = minutes.abs();
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}{}m", sign, m),
+        (h, 0) => format!("{}{}h", sign, h),
+        (h, m) => format!("{}{}h {}m", sign, h, m),
+    }
+}
+
+// Shown before the name of pinned tasks and aides
+pub const PIN_MARKER: &str = "📌";
+
+// Which kind of item a command acts on when a name could be either
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Task,
+    Aide,
+}
+
+// Layout of entries in aide files unless entry_format is set ("\n" may be written as an escape)
+pub const DEFAULT_ENTRY_FORMAT: &str = "{timestamp}\\n* {content}";
+
+// How an entry is laid out in an aide file: an optional line holding its timestamp, then its
+// content after a fixed prefix, e.g. "{timestamp}\n* {content}" or "## {timestamp}\n- {content}".
+// Entries are written with format_entry and read back with stamp_of / content_of.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntryFormat {
+    // The text before and after {timestamp} on its line, if the template has one
+    stamp: Option<(String, String)>,
+    // The text before {content}
+    bullet: String,
+}
+
+impl EntryFormat {
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let template = template.replace("\\n", "\n");
+        let lines: Vec<&str> = template.lines().collect();
+        let (stamp_line, content_line) = match lines.as_slice() {
+            [content_line] => (None, *content_line),
+            [stamp_line, content_line] => (Some(*stamp_line), *content_line),
+            _ => return Err("The entry format must be a {content} line, optionally after a {timestamp} line.".to_string()),
+        };
+
+        let bullet = match content_line.strip_suffix("{content}") {
+            Some(bullet) if !bullet.trim().is_empty() && !bullet.contains('{') => bullet.to_string(),
+            _ => return Err("The entry format's last line must be a prefix such as \"* \" followed by {content}.".to_string()),
+        };
+        let stamp = match stamp_line {
+            Some(line) => match line.split_once("{timestamp}") {
+                Some((before, after)) if !line.starts_with(&bullet) && !after.contains('{') =>
+                    Some((before.to_string(), after.to_string())),
+                _ => return Err("The entry format's first line must hold {timestamp} and not start like the content line.".to_string()),
+            },
+            None => None,
+        };
+        Ok(EntryFormat { stamp, bullet })
+    }
+
+    // An entry as written to the file, ending in a newline. The timestamp line is left
+    // out when there's no timestamp or the format has no place for one.
+    pub fn format_entry(&self, timestamp: Option<&str>, content: &str) -> String {
+        match (&self.stamp, timestamp) {
+            (Some((before, after)), Some(timestamp)) => format!("{}{}{}\n{}{}\n", before, timestamp, after, self.bullet, content),
+            _ => format!("{}{}\n", self.bullet, content),
+        }
+    }
+
+    // The timestamp on an entry's timestamp line
+    pub fn stamp_of<'a>(&self, line: &'a str) -> Option<&'a str> {
+        let (before, after) = self.stamp.as_ref()?;
+        let stamp = line.trim_end().strip_prefix(before.as_str())?.strip_suffix(after.trim_end())?;
+        chrono::NaiveDateTime::parse_from_str(stamp, "%Y-%m-%d %H:%M:%S").is_ok().then_some(stamp)
+    }
+
+    // The first line of an entry's content, if `line` starts one
+    pub fn content_of<'a>(&self, line: &'a str) -> Option<&'a str> {
+        line.strip_prefix(self.bullet.as_str())
+    }
+}
+
+impl Default for EntryFormat {
+    fn default() -> Self {
+        EntryFormat { stamp: Some((String::new(), String::new())), bullet: "* ".to_string() }
+    }
+}
+
+// A single row of an aide's `data` table
+#[derive(Debug, Clone)]
+pub struct DataEntry {
+    pub id: i64,
+    pub input_text: String,
+    pub command_output: String,
+    pub created_at: Option<String>,
+}
+
+impl DataEntry {
+    // The text written after "* " in the aide file when this entry was added:
+    // plain entries store "[timestamp] text" (or just the text) as output,
+    // `add --run` stores the captured output
+    pub fn file_text(&self) -> String {
+        // Entries added with timestamps turned off store the bare text
+        if self.command_output == self.input_text {
+            return self.input_text.clone();
+        }
+        match self.command_output.strip_prefix('[').and_then(|rest| rest.split_once("] ")) {
+            Some((_, text)) if text == self.input_text => self.input_text.clone(),
+            _ => format!("$ {}\n{}", self.input_text, self.command_output),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct ConfigItem {
     pub key_name: String,
     pub value: String,
-    pub description: Option<String>,
-    pub created_at: String,
-    pub updated_at: String,
+}
+
+// `--today` / `--week` on the list commands, and the TUI's `t` filter: items created since the
+// start of the current day or ISO week (Monday), in UTC like the stored timestamps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreatedWithin {
+    Today,
+    Week,
+}
+
+impl CreatedWithin {
+    // Earliest created_at that's in range, in the stored "YYYY-MM-DD HH:MM:SS" form
+    pub fn start(self) -> String {
+        use chrono::Datelike;
+        let today = chrono::Utc::now().date_naive();
+        let first_day = match self {
+            CreatedWithin::Today => today,
+            CreatedWithin::Week => today - chrono::Duration::days(i64::from(today.weekday().num_days_from_monday())),
+        };
+        format!("{} 00:00:00", first_day.format("%Y-%m-%d"))
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CreatedWithin::Today => "today",
+            CreatedWithin::Week => "this week",
+        }
+    }
+}
+
+// Output layout for `aide task-list --format`
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum TaskListFormat {
+    /// One line per task with priority, status and creation time
+    #[default]
+    Table,
+    /// Task names only
+    Compact,
+    /// Every stored field, one block per task
+    Detailed,
+}
+
+// Where `task-move` should put a task in the manual ordering
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaskMove {
+    Up,
+    Down,
+    To(usize), // 1-based position
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -29,11 +224,39 @@ pub enum PopupMode {
     TaskStatus,
     AideEdit,
     ConfigEdit,
+    ConfirmDelete,
+    NewItem,
+    Filter,
     TextEditor,
 }
 
 #[derive(Debug, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum EditorCallback {
     SaveTask(String),
     SaveAide(String),
-}
\ No newline at end of file
+    SaveEntry(String, i64), // aide name, data row id
+    SaveConfig(String),
+}
+// What a database operation did. The CLI methods print their own messages, so this
+// lets callers (the TUI, main's exit status) tell a handled failure from success.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Done,
+    // The named task/aide/config doesn't exist, or the fuzzy suggestion was declined
+    NotFound,
+    // The user answered "no" to a confirmation prompt
+    Cancelled,
+    // The input was rejected, e.g. an unknown status or out-of-range priority
+    Invalid,
+    // The action was attempted but didn't work, e.g. the editor or command couldn't be launched
+    Failed,
+    // A task can't be completed while tasks it depends on are unfinished
+    Blocked,
+}
+
+impl Outcome {
+    pub fn is_done(self) -> bool {
+        self == Outcome::Done
+    }
+}
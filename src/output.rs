@@ -0,0 +1,23 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Set by --quiet. Drops informational messages; command output and errors still print.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+// println! for status lines such as "Aide 'x' created successfully" that --quiet suppresses.
+// Errors go through eprintln! and a command's actual output through println! instead.
+macro_rules! emit {
+    ($($arg:tt)*) => {
+        if !$crate::output::is_quiet() {
+            println!($($arg)*);
+        }
+    };
+}
+pub(crate) use emit;
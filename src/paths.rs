@@ -0,0 +1,202 @@
+use anyhow::{anyhow, bail, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+// Resolved once per run; None if no home directory could be determined
+static HOME_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+// The user's home directory, as found by the `dirs` crate: $HOME on Unix, the profile folder on
+// Windows. Errors instead of falling back to the current directory, which would scatter
+// `.aide.db` and `.aide/` wherever the command happened to be run.
+pub fn home_dir() -> Result<PathBuf> {
+    HOME_DIR
+        .get_or_init(resolve_home)
+        .clone()
+        .ok_or_else(|| anyhow!("Could not determine your home directory. Set HOME (or USERPROFILE on Windows)."))
+}
+
+#[cfg(not(test))]
+fn resolve_home() -> Option<PathBuf> {
+    dirs::home_dir()
+}
+
+// Tests get a scratch home directory, so they never touch the real data. It is one directory
+// under target/, emptied when a test run starts: a TempDir kept in a static would never be
+// dropped (statics aren't), so a fresh temp directory per run would pile up in /tmp.
+#[cfg(test)]
+fn resolve_home() -> Option<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("target").join("test-home");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).ok()?;
+    Some(dir)
+}
+
+// SQLite database file
+pub fn db_path() -> Result<PathBuf> {
+    Ok(home_dir()?.join(".aide.db"))
+}
+
+// Directory holding aide files, with task logs in its `tasks` subdirectory
+pub fn aide_dir() -> Result<PathBuf> {
+    Ok(home_dir()?.join(".aide"))
+}
+
+// Folders of the aide directory that aren't available as aide folders
+pub const RESERVED_FOLDERS: [&str; 2] = ["tasks", "templates"];
+
+pub fn tasks_dir() -> Result<PathBuf> {
+    Ok(aide_dir()?.join("tasks"))
+}
+
+// Default file for a new aide; existing aides use the path stored in the database.
+// A `/` in the name makes folders, so "work/deploy" is `work/deploy.txt`; each part
+// goes through file_stem, which rejects `..` and empty parts.
+pub fn aide_file(aide_name: &str) -> Result<PathBuf> {
+    let dir = aide_dir()?;
+    let Some((folders, name)) = aide_name.rsplit_once('/') else {
+        return flat_aide_file(aide_name);
+    };
+
+    let mut path = dir;
+    for (depth, folder) in folders.split('/').enumerate() {
+        let folder = file_stem(folder).map_err(|_| anyhow!("'{}' has an empty or unusable folder name", aide_name))?;
+        if depth == 0 && RESERVED_FOLDERS.iter().any(|reserved| folder.eq_ignore_ascii_case(reserved)) {
+            bail!("'{}' can't go in the {} folder, which aide uses itself", aide_name, folder);
+        }
+        path.push(folder);
+    }
+    path.push(format!("{}.txt", file_stem(name)?));
+    Ok(path)
+}
+
+// The file an aide had before names made folders: "work/deploy" was `work_deploy.txt`
+pub fn flat_aide_file(aide_name: &str) -> Result<PathBuf> {
+    Ok(aide_dir()?.join(format!("{}.txt", file_stem(aide_name)?)))
+}
+
+// Create the folder a file is about to be written to
+pub fn create_parent_dir(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    Ok(())
+}
+
+// After deleting or moving an aide file, remove the folders it leaves empty, up to the aide directory
+pub fn remove_empty_folders(path: &Path) -> Result<()> {
+    let dir = aide_dir()?;
+    let mut folder = path.parent();
+    while let Some(current) = folder.filter(|current| *current != dir && current.starts_with(&dir)) {
+        // Fails, ending the walk, once a folder isn't empty
+        if std::fs::remove_dir(current).is_err() {
+            break;
+        }
+        folder = current.parent();
+    }
+    Ok(())
+}
+
+// Aide name for a file under the aide directory: its path without `.txt`, with `/` between folders
+pub fn aide_name_for(path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(aide_dir().ok()?).ok()?.with_extension("");
+    let parts: Option<Vec<&str>> = relative.components().map(|part| part.as_os_str().to_str()).collect();
+    Some(parts?.join("/"))
+}
+
+// The last answer of `aide ask`, for `aide ask --last`
+pub fn last_answer_file() -> Result<PathBuf> {
+    Ok(aide_dir()?.join("last_ask.json"))
+}
+
+// Write `contents` to a new `<prefix><random>.txt` in the temp directory. The file is created
+// exclusively and readable only by the current user, so another user can't read it or plant a
+// symlink in its place. It is kept; callers remove it when they are done with it.
+pub fn write_private_temp_file(prefix: &str, contents: &str) -> Result<PathBuf> {
+    let mut file = tempfile::Builder::new().prefix(prefix).suffix(".txt").tempfile()?;
+    file.write_all(contents.as_bytes())?;
+    let (_, path) = file.keep()?;
+    Ok(path)
+}
+
+// Default log file for a new task; existing tasks use the path stored in the database
+pub fn task_file(task_name: &str) -> Result<PathBuf> {
+    Ok(tasks_dir()?.join(format!("{}.txt", file_stem(task_name)?)))
+}
+
+// File name (without extension) for a task or aide name. Path separators and characters
+// Windows rejects become `_`, and leading/trailing dots and spaces are dropped, so a name like
+// "feature/login" or ".." can't escape the data directory.
+pub fn file_stem(name: &str) -> Result<String> {
+    let replaced: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let stem = replaced.trim_matches(|c: char| c == '.' || c.is_whitespace());
+    if stem.is_empty() {
+        bail!("'{}' can't be used as a file name", name);
+    }
+    Ok(stem.to_string())
+}
+
+// Whether `program` can be launched: either a path to an existing file or a name found on PATH.
+// Replaces shelling out to `which`, which doesn't exist on Windows; there the PATHEXT
+// extensions (.exe, .cmd, ...) are tried as well, so `notepad` finds `notepad.exe`.
+pub fn is_executable_available(program: &str) -> bool {
+    let candidate = Path::new(program);
+    if candidate.components().count() > 1 {
+        return candidate.is_file();
+    }
+    let extensions: Vec<String> = if cfg!(windows) {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+            .split(';')
+            .filter(|ext| !ext.is_empty())
+            .map(|ext| ext.to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let Some(path_var) = std::env::var_os("PATH") else {
+        return false;
+    };
+    std::env::split_paths(&path_var).any(|dir| {
+        dir.join(program).is_file()
+            || extensions
+                .iter()
+                .any(|ext| dir.join(format!("{}{}", program, ext)).is_file())
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backslashes_stay_inside_the_tasks_dir() {
+        let path = task_file(r"..\..\evil").unwrap();
+        assert_eq!(path.parent().unwrap(), tasks_dir().unwrap());
+        assert_eq!(path.file_name().unwrap(), "_.._evil.txt");
+    }
+
+    #[test]
+    fn backslashes_become_underscores() {
+        assert_eq!(file_stem(r"feature\login").unwrap(), "feature_login");
+        assert_eq!(file_stem(r"C:\notes").unwrap(), "C__notes");
+    }
+
+    #[test]
+    fn a_slash_name_never_uses_the_flat_file() {
+        // "x_y" owns x_y.txt, which must not become the file of "x/y"
+        let flat = flat_aide_file("x_y").unwrap();
+        create_parent_dir(&flat).unwrap();
+        std::fs::write(&flat, "* x_y's entry\n").unwrap();
+        assert_eq!(aide_file("x/y").unwrap(), aide_dir().unwrap().join("x").join("y.txt"));
+        assert_eq!(aide_file("x_y").unwrap(), flat);
+    }
+}
@@ -0,0 +1,109 @@
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Print, Stylize},
+    terminal::{self, ClearType},
+};
+use fuzzy_matcher::FuzzyMatcher;
+use std::io::{self, IsTerminal, Write};
+
+// How many matches are shown below the query line at once
+const MAX_ROWS: usize = 10;
+
+// Inline fuzzy picker drawn on stderr: type to filter, ↑/↓ to move, Enter to choose,
+// Esc or Ctrl+C to cancel. Returns None when cancelled or nothing matches.
+pub fn pick(prompt: &str, items: &[String]) -> io::Result<Option<String>> {
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        return Err(io::Error::other("no terminal to pick one from"));
+    }
+
+    terminal::enable_raw_mode()?;
+    let result = run(prompt, items);
+    terminal::disable_raw_mode()?;
+    result
+}
+
+fn run(prompt: &str, items: &[String]) -> io::Result<Option<String>> {
+    let mut out = io::stderr();
+    let matcher = fuzzy_matcher::skim::SkimMatcherV2::default().ignore_case();
+    let mut query = String::new();
+    let mut selected = 0;
+
+    loop {
+        let matches: Vec<&String> = if query.is_empty() {
+            items.iter().collect()
+        } else {
+            let mut scored: Vec<(i64, &String)> = items
+                .iter()
+                .filter_map(|item| matcher.fuzzy_match(item, &query).map(|score| (score, item)))
+                .collect();
+            // Stable sort keeps the list order among equal scores
+            scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+            scored.into_iter().map(|(_, item)| item).collect()
+        };
+        selected = selected.min(matches.len().saturating_sub(1));
+        draw(&mut out, prompt, &query, &matches, selected)?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return finish(&mut out, None),
+            KeyCode::Char('c') if ctrl => return finish(&mut out, None),
+            KeyCode::Enter => return finish(&mut out, matches.get(selected).map(|item| item.to_string())),
+            KeyCode::Up => selected = selected.saturating_sub(1),
+            KeyCode::Char('p') if ctrl => selected = selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Tab => selected += 1,
+            KeyCode::Char('n') if ctrl => selected += 1,
+            KeyCode::Backspace => {
+                query.pop();
+                selected = 0;
+            }
+            KeyCode::Char(c) if !ctrl => {
+                query.push(c);
+                selected = 0;
+            }
+            _ => {}
+        }
+    }
+}
+
+// Redraw the query line and the visible window of matches, leaving the cursor after the query
+fn draw(out: &mut impl Write, prompt: &str, query: &str, matches: &[&String], selected: usize) -> io::Result<()> {
+    // Some pseudo-terminals report a width of 0
+    let width = match terminal::size() {
+        Ok((cols, _)) if cols >= 4 => cols as usize,
+        _ => 80,
+    };
+    let first = selected.saturating_sub(MAX_ROWS - 1);
+    let visible = &matches[first..matches.len().min(first + MAX_ROWS)];
+
+    let header = format!("{} ({}) > {}", prompt, matches.len(), query);
+    queue!(out, cursor::MoveToColumn(0), terminal::Clear(ClearType::FromCursorDown), Print(&header))?;
+    for (offset, item) in visible.iter().enumerate() {
+        // Truncate so no row wraps, otherwise moving back up would land on the wrong line
+        let row: String = format!("  {}", item).chars().take(width - 1).collect();
+        if first + offset == selected {
+            queue!(out, Print("\r\n"), Print(row.reverse()))?;
+        } else {
+            queue!(out, Print("\r\n"), Print(row))?;
+        }
+    }
+    if !visible.is_empty() {
+        queue!(out, cursor::MoveUp(visible.len() as u16))?;
+    }
+    let column = header.chars().count().min(width - 1);
+    queue!(out, cursor::MoveToColumn(column as u16))?;
+    out.flush()
+}
+
+fn finish(out: &mut impl Write, choice: Option<String>) -> io::Result<Option<String>> {
+    queue!(out, cursor::MoveToColumn(0), terminal::Clear(ClearType::FromCursorDown))?;
+    out.flush()?;
+    Ok(choice)
+}
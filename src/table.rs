@@ -0,0 +1,84 @@
+use unicode_width::UnicodeWidthStr;
+
+// Widest a cell may get before it's cut with an ellipsis, so one long name or value
+// can't push every other column across the screen
+const MAX_CELL_WIDTH: usize = 40;
+
+// Gap between columns
+const COLUMN_GAP: &str = "  ";
+
+// Plain-text table for list output: columns padded to their widest cell, under a header
+// and a dashed rule. A row can carry extra lines printed below it, indented.
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<(Vec<String>, Vec<String>)>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Self { headers: headers.iter().map(|header| header.to_string()).collect(), rows: Vec::new() }
+    }
+
+    pub fn add_row(&mut self, cells: Vec<String>) {
+        self.rows.push((cells.into_iter().map(|cell| truncate(&cell)).collect(), Vec::new()));
+    }
+
+    // A line printed under the last row, not aligned to the columns
+    pub fn add_detail(&mut self, line: String) {
+        if let Some((_, details)) = self.rows.last_mut() {
+            details.push(line);
+        }
+    }
+
+    pub fn print(&self) {
+        let mut widths: Vec<usize> = self.headers.iter().map(|header| width(header)).collect();
+        for (cells, _) in &self.rows {
+            for (column, cell) in cells.iter().enumerate() {
+                if let Some(max) = widths.get_mut(column) {
+                    *max = (*max).max(width(cell));
+                }
+            }
+        }
+
+        println!("{}", format_row(&self.headers, &widths));
+        let rules: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+        println!("{}", format_row(&rules, &widths));
+        for (cells, details) in &self.rows {
+            println!("{}", format_row(cells, &widths));
+            for line in details {
+                println!("    {}", line);
+            }
+        }
+    }
+}
+
+// Cells padded to the column widths, without trailing spaces
+fn format_row(cells: &[String], widths: &[usize]) -> String {
+    cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, &column_width)| format!("{}{}", cell, " ".repeat(column_width.saturating_sub(width(cell)))))
+        .collect::<Vec<_>>()
+        .join(COLUMN_GAP)
+        .trim_end()
+        .to_string()
+}
+
+// Columns the text takes up in a terminal (the 📌 pin marker takes two)
+fn width(text: &str) -> usize {
+    text.width()
+}
+
+fn truncate(cell: &str) -> String {
+    if width(cell) <= MAX_CELL_WIDTH {
+        return cell.to_string();
+    }
+    let mut cut = String::new();
+    for c in cell.chars() {
+        if width(&cut) + width(&c.to_string()) >= MAX_CELL_WIDTH {
+            break;
+        }
+        cut.push(c);
+    }
+    format!("{}…", cut)
+}
@@ -2,7 +2,11 @@ use std::collections::HashMap;
 use anyhow::Result;
 
 // TF-IDF constants
-pub const FUZZY_MATCH_THRESHOLD: f64 = 0.3; // Minimum similarity score to suggest
+pub const FUZZY_MATCH_THRESHOLD: f64 = 0.3; // Default minimum similarity score to suggest (config: fuzzy_threshold)
+pub const STRING_SIMILARITY_WEIGHT: f64 = 0.7; // Share of the combined score from string similarity
+pub const TFIDF_WEIGHT: f64 = 0.3; // Share of the combined score from TF-IDF cosine similarity
+pub const MULTI_TOKEN_TFIDF_WEIGHT: f64 = 0.6; // TF-IDF share for queries of several words
+pub const INDEX_REBUILD_THRESHOLD: usize = 200; // Incremental changes before a full rebuild (config: index_rebuild_threshold)
 
 // TF-IDF vector index structure
 #[derive(Debug, Clone)]
@@ -12,6 +16,11 @@ pub struct TfIdfIndex {
     pub tfidf_vectors: Vec<HashMap<usize, f64>>,
     pub entity_names: Vec<String>, // Task names or aide names
     pub total_docs: usize,
+    // add_entity/remove_entity calls since the index was built. Incremental IDF updates
+    // drift slightly from a fresh build, so after `rebuild_after` of them (0: never) the
+    // index is rebuilt from its names.
+    pub changes_since_build: usize,
+    pub rebuild_after: usize,
 }
 
 // Fuzzy match result structure
@@ -22,6 +31,15 @@ pub struct FuzzyMatchResult {
     pub score: Option<f64>,
 }
 
+// Component scores for one indexed name against a query
+#[derive(Debug)]
+pub struct MatchScore {
+    pub name: String,
+    pub string_score: f64,
+    pub tfidf_score: f64,
+    pub combined_score: f64,
+}
+
 // TF-IDF helper functions
 pub fn tokenize(text: &str) -> Vec<String> {
     text.to_lowercase()
@@ -79,17 +97,19 @@ pub fn build_tfidf_index(names: Vec<String>) -> Result<TfIdfIndex> {
             tfidf_vectors: Vec::new(),
             entity_names: Vec::new(),
             total_docs: 0,
+            changes_since_build: 0,
+            rebuild_after: INDEX_REBUILD_THRESHOLD,
         });
     }
     
-    let documents: Vec<String> = names.iter().map(|name| name.clone()).collect();
+    let documents: Vec<String> = names.to_vec();
     let total_docs = documents.len();
     
     // Build vocabulary
     let mut vocabulary = HashMap::new();
     let mut word_doc_count = HashMap::new();
     
-    for (_, doc) in documents.iter().enumerate() {
+    for doc in documents.iter() {
         let tokens = tokenize(doc);
         let mut unique_tokens = std::collections::HashSet::new();
         
@@ -135,11 +155,13 @@ pub fn build_tfidf_index(names: Vec<String>) -> Result<TfIdfIndex> {
         tfidf_vectors,
         entity_names: names,
         total_docs,
+        changes_since_build: 0,
+        rebuild_after: INDEX_REBUILD_THRESHOLD,
     })
 }
 
 // Core fuzzy matching logic using TF-IDF + string similarity
-pub fn find_fuzzy_match_in_index(input_name: &str, index: &TfIdfIndex) -> Result<FuzzyMatchResult> {
+pub fn find_fuzzy_match_in_index(input_name: &str, index: &TfIdfIndex, threshold: f64) -> Result<FuzzyMatchResult> {
     // Check for exact match first
     if index.entity_names.contains(&input_name.to_string()) {
         return Ok(FuzzyMatchResult {
@@ -149,10 +171,67 @@ pub fn find_fuzzy_match_in_index(input_name: &str, index: &TfIdfIndex) -> Result
         });
     }
     
-    // Use both string similarity and TF-IDF for better matching
-    let mut matches = Vec::new();
+    let mut matches: Vec<(String, f64)> = score_index(input_name, index)
+        .into_iter()
+        .filter(|score| score.combined_score >= threshold)
+        .map(|score| (score.name, score.combined_score))
+        .collect();
+    
+    // Sort by similarity score (descending), with a stable order for ties
+    matches.sort_by(|a, b| rank_order(&a.0, a.1, &b.0, b.1));
+    
+    let suggested_name = matches.first().map(|(name, _)| name.clone());
+    let score = matches.first().map(|(_, score)| *score);
+    
+    Ok(FuzzyMatchResult {
+        exact_match: false,
+        suggested_name,
+        score,
+    })
+}
+
+// Ordering for ranked matches, best first: higher score (NaN counts as lowest), then the
+// shorter name, then alphabetical, so equal scores always suggest the same name
+pub fn rank_order(a_name: &str, a_score: f64, b_name: &str, b_score: f64) -> std::cmp::Ordering {
+    let key = |score: f64| if score.is_nan() { f64::NEG_INFINITY } else { score };
+    key(b_score)
+        .total_cmp(&key(a_score))
+        .then_with(|| a_name.len().cmp(&b_name.len()))
+        .then_with(|| a_name.cmp(b_name))
+}
+
+// (string similarity, TF-IDF) weights for a query. String similarity follows character order,
+// so "deploy prod" scores poorly against "prod deploy checklist"; TF-IDF ignores word order,
+// so it gets the larger share once the query has more than one word.
+pub fn match_weights(input_name: &str) -> (f64, f64) {
+    if tokenize(input_name).len() > 1 {
+        (1.0 - MULTI_TOKEN_TFIDF_WEIGHT, MULTI_TOKEN_TFIDF_WEIGHT)
+    } else {
+        (STRING_SIMILARITY_WEIGHT, TFIDF_WEIGHT)
+    }
+}
+
+// Score every indexed name against the input using both string similarity and TF-IDF,
+// in index order. Exposed separately so `aide match-debug` can show the components.
+pub fn score_index(input_name: &str, index: &TfIdfIndex) -> Vec<MatchScore> {
+    // The query's TF-IDF vector is the same for every name
+    let input_tfidf = if index.vocabulary.is_empty() {
+        HashMap::new()
+    } else {
+        let input_tokens = tokenize(input_name);
+        let input_tf = calculate_tf(&input_tokens, &index.vocabulary);
+        
+        let mut input_tfidf = HashMap::new();
+        for (&word_id, &tf_val) in &input_tf {
+            let df = index.document_frequencies[word_id];
+            let idf = (index.total_docs as f64 / (df + 1.0)).ln();
+            input_tfidf.insert(word_id, tf_val * idf);
+        }
+        input_tfidf
+    };
     
-    for name in &index.entity_names {
+    let (string_weight, tfidf_weight) = match_weights(input_name);
+    index.entity_names.iter().zip(&index.tfidf_vectors).map(|(name, doc_vector)| {
         // Calculate string similarity (for substring matching)
         let string_score = calculate_string_similarity(input_name, name);
         
@@ -160,41 +239,14 @@ pub fn find_fuzzy_match_in_index(input_name: &str, index: &TfIdfIndex) -> Result
         let tfidf_score = if index.vocabulary.is_empty() {
             0.0
         } else {
-            let input_tokens = tokenize(input_name);
-            let input_tf = calculate_tf(&input_tokens, &index.vocabulary);
-            
-            let mut input_tfidf = HashMap::new();
-            for (&word_id, &tf_val) in &input_tf {
-                let df = index.document_frequencies[word_id];
-                let idf = (index.total_docs as f64 / (df + 1.0)).ln();
-                input_tfidf.insert(word_id, tf_val * idf);
-            }
-            
-            // Find the corresponding TF-IDF vector for this name
-            let name_index = index.entity_names.iter().position(|n| n == name).unwrap();
-            let doc_vector = &index.tfidf_vectors[name_index];
             cosine_similarity(&input_tfidf, doc_vector)
         };
         
         // Combine both scores (weighted average)
-        let combined_score = (string_score * 0.7) + (tfidf_score * 0.3);
+        let combined_score = (string_score * string_weight) + (tfidf_score * tfidf_weight);
         
-        if combined_score >= FUZZY_MATCH_THRESHOLD {
-            matches.push((name.clone(), combined_score));
-        }
-    }
-    
-    // Sort by similarity score (descending)
-    matches.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-    
-    let suggested_name = matches.first().map(|(name, _)| name.clone());
-    let score = matches.first().map(|(_, score)| *score);
-    
-    Ok(FuzzyMatchResult {
-        exact_match: false,
-        suggested_name,
-        score,
-    })
+        MatchScore { name: name.clone(), string_score, tfidf_score, combined_score }
+    }).collect()
 }
 
 // Simple string similarity function for substring matching
@@ -297,7 +349,7 @@ impl TfIdfIndex {
             self.recalculate_idf_for_new_words(&new_words)?;
         }
         
-        Ok(())
+        self.count_change()
     }
     
     /// Remove an entity from the index
@@ -324,12 +376,24 @@ impl TfIdfIndex {
             // Recalculate IDF for all remaining documents (since total_docs changed)
             self.recalculate_all_idf()?;
             
+            self.count_change()?;
             Ok(true)
         } else {
             Ok(false)
         }
     }
     
+    // Record an incremental change, rebuilding from scratch once `rebuild_after` have piled up
+    fn count_change(&mut self) -> Result<()> {
+        self.changes_since_build += 1;
+        if self.rebuild_after > 0 && self.changes_since_build >= self.rebuild_after {
+            let rebuild_after = self.rebuild_after;
+            *self = build_tfidf_index(std::mem::take(&mut self.entity_names))?;
+            self.rebuild_after = rebuild_after;
+        }
+        Ok(())
+    }
+    
     fn recalculate_idf_for_new_words(&mut self, new_word_ids: &[usize]) -> Result<()> {
         for tfidf_vector in &mut self.tfidf_vectors {
             for &word_id in new_word_ids {
@@ -358,4 +422,47 @@ impl TfIdfIndex {
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rank_order_breaks_ties_by_length_then_name() {
+        let mut matches = [("deploy b", 0.5), ("deploy", 0.5), ("deploy a", 0.5), ("other", 0.9), ("nan", f64::NAN)];
+        matches.sort_by(|a, b| rank_order(a.0, a.1, b.0, b.1));
+        let names: Vec<&str> = matches.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["other", "deploy", "deploy a", "deploy b", "nan"]);
+    }
+
+    #[test]
+    fn equal_scores_suggest_the_same_name_in_any_index_order() {
+        for names in [vec!["api b", "api a"], vec!["api a", "api b"]] {
+            let index = build_tfidf_index(names.into_iter().map(String::from).collect()).unwrap();
+            let result = find_fuzzy_match_in_index("api", &index, FUZZY_MATCH_THRESHOLD).unwrap();
+            assert_eq!(result.suggested_name.as_deref(), Some("api a"));
+        }
+    }
+
+    // Names a "deploy prod" query could plausibly mean
+    fn deploy_index() -> TfIdfIndex {
+        let names = ["deploy docs", "prod deploy checklist", "deployment prep", "product roadmap"];
+        build_tfidf_index(names.into_iter().map(String::from).collect()).unwrap()
+    }
+
+    #[test]
+    fn multi_word_query_matches_words_in_any_order() {
+        let result = find_fuzzy_match_in_index("deploy prod", &deploy_index(), FUZZY_MATCH_THRESHOLD).unwrap();
+        assert_eq!(result.suggested_name.as_deref(), Some("prod deploy checklist"));
+    }
+
+    #[test]
+    fn single_word_query_keeps_the_original_weights() {
+        assert_eq!(match_weights("deploy"), (STRING_SIMILARITY_WEIGHT, TFIDF_WEIGHT));
+        for score in score_index("deploy", &deploy_index()) {
+            let expected = score.string_score * STRING_SIMILARITY_WEIGHT + score.tfidf_score * TFIDF_WEIGHT;
+            assert_eq!(score.combined_score, expected, "{}", score.name);
+        }
+    }
+}
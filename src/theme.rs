@@ -0,0 +1,114 @@
+use ratatui::style::Color;
+
+// Named color slots used by the TUI. Start from a preset (`theme` config key)
+// and override single slots with `theme_<slot>` keys, e.g. `theme_accent = magenta`.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub text: Color,
+    pub background: Color,
+    pub popup_bg: Color,
+    pub accent: Color,
+    pub highlight: Color,
+    pub muted: Color,
+    pub tag: Color,
+    pub status_created: Color,
+    pub status_in_progress: Color,
+    pub status_completed: Color,
+}
+
+impl Theme {
+    pub const SLOTS: &'static [&'static str] = &[
+        "text",
+        "background",
+        "popup_bg",
+        "accent",
+        "highlight",
+        "muted",
+        "tag",
+        "status_created",
+        "status_in_progress",
+        "status_completed",
+    ];
+
+    pub fn dark() -> Self {
+        Theme {
+            text: Color::White,
+            background: Color::Black,
+            popup_bg: Color::DarkGray,
+            accent: Color::Cyan,
+            highlight: Color::LightGreen,
+            muted: Color::DarkGray,
+            tag: Color::Green,
+            status_created: Color::Blue,
+            status_in_progress: Color::Yellow,
+            status_completed: Color::Green,
+        }
+    }
+
+    // For terminals with a light background, where white text and black fills look wrong
+    pub fn light() -> Self {
+        Theme {
+            text: Color::Black,
+            background: Color::White,
+            popup_bg: Color::Gray,
+            accent: Color::Blue,
+            highlight: Color::LightYellow,
+            muted: Color::Gray,
+            tag: Color::Green,
+            status_created: Color::Blue,
+            status_in_progress: Color::Magenta,
+            status_completed: Color::Green,
+        }
+    }
+
+    // Look up a preset by name, falling back to the dark theme
+    pub fn preset(name: Option<&str>) -> Self {
+        match name.map(|n| n.trim().to_lowercase()).as_deref() {
+            Some("light") => Theme::light(),
+            _ => Theme::dark(),
+        }
+    }
+
+    // Override one slot from a color name ("red", "lightblue"), index ("42") or hex ("#ff8800").
+    // Returns false if the slot or the color isn't recognised.
+    pub fn set(&mut self, slot: &str, value: &str) -> bool {
+        let Some(color) = parse_color(value) else {
+            return false;
+        };
+        let target = match slot {
+            "text" => &mut self.text,
+            "background" => &mut self.background,
+            "popup_bg" => &mut self.popup_bg,
+            "accent" => &mut self.accent,
+            "highlight" => &mut self.highlight,
+            "muted" => &mut self.muted,
+            "tag" => &mut self.tag,
+            "status_created" => &mut self.status_created,
+            "status_in_progress" => &mut self.status_in_progress,
+            "status_completed" => &mut self.status_completed,
+            _ => return false,
+        };
+        *target = color;
+        true
+    }
+
+    pub fn status_color(&self, status: &str) -> Color {
+        match status {
+            "completed" => self.status_completed,
+            "in_progress" => self.status_in_progress,
+            "created" => self.status_created,
+            _ => self.text,
+        }
+    }
+}
+
+// A color name ("red", "lightblue"), index ("42") or hex ("#ff8800")
+pub fn parse_color(value: &str) -> Option<Color> {
+    value.trim().parse().ok()
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
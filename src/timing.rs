@@ -0,0 +1,47 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+// Set by --timing. The index totals below are kept either way, since they're cheap.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// Time spent building and querying the TF-IDF name indexes, in microseconds
+static INDEX_BUILD_MICROS: AtomicU64 = AtomicU64::new(0);
+static INDEX_QUERY_MICROS: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy)]
+pub enum IndexWork {
+    Build,
+    Query,
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+// Add the time since `started` to the index build or query total
+pub fn record(work: IndexWork, started: Instant) {
+    let total = match work {
+        IndexWork::Build => &INDEX_BUILD_MICROS,
+        IndexWork::Query => &INDEX_QUERY_MICROS,
+    };
+    total.fetch_add(started.elapsed().as_micros() as u64, Ordering::Relaxed);
+}
+
+// With --timing, print to stderr how long the command took since `started`, and how much
+// of that went to the indexes
+pub fn report(started: Instant) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let micros = |total: &AtomicU64| Duration::from_micros(total.load(Ordering::Relaxed));
+    eprintln!(
+        "Timing: {} total (index build {}, index lookups {})",
+        format_duration(started.elapsed()),
+        format_duration(micros(&INDEX_BUILD_MICROS)),
+        format_duration(micros(&INDEX_QUERY_MICROS))
+    );
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+}
@@ -1,5 +1,7 @@
 use anyhow::Result;
+use fuzzy_matcher::FuzzyMatcher;
 use crossterm::{
+    cursor,
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -7,22 +9,97 @@ use crossterm::{
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Tabs, Wrap},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs, Wrap},
     Frame, Terminal,
 };
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::panic;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
-use crate::database::Database;
+use crate::crypto;
+use crate::database::{validate_aide_name, validate_config_value, validate_name, Database};
 use crate::editor::TextEditor;
-use crate::models::{TaskItem, AideItem, ConfigItem, PopupMode, EditorCallback};
+use crate::theme::Theme;
+use crate::paths;
+use crate::models::{TaskItem, AideItem, ConfigItem, DataEntry, PopupMode, EditorCallback, TaskMove, Outcome, CreatedWithin, PIN_MARKER, PRIORITY_NAMES, format_minutes, priority_name, priority_label};
+
+// Set while the ratatui terminal is active so code that would spawn an
+// external program on the terminal (e.g. an editor) can refuse to.
+static TUI_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+// Characters of each entry shown in the Aides preview unless `preview_length` is configured
+const DEFAULT_PREVIEW_LENGTH: usize = 100;
+
+// Marker in front of the selected row of every list unless `highlight_symbol` is configured
+const DEFAULT_HIGHLIGHT_SYMBOL: &str = ">> ";
+
+// Lines moved by PageUp/PageDown in the right-hand panels
+const PAGE_SCROLL_LINES: i32 = 10;
+
+// Config values longer than this open in the text editor rather than the one-line popup
+const CONFIG_POPUP_MAX_CHARS: usize = 60;
+
+// Lines of a multi-line config value shown in the Configs list
+const CONFIG_LIST_VALUE_LINES: usize = 3;
+
+// Task statuses offered by the status picker: (stored value, label)
+const TASK_STATUSES: [(&str, &str); 3] = [
+    ("created", "Created"),
+    ("in_progress", "In Progress"),
+    ("completed", "Completed"),
+];
+
+// Tab titles, indexed by `App::current_tab`
+const TAB_NAMES: [&str; 3] = ["Tasks", "Aides", "Configs"];
+
+// Right-hand panel text for an empty list, telling a new user how to get started
+const TASKS_EMPTY_TEXT: &str = "No tasks yet\n\n\
+Create your first task:\n\
+• Press n here and type a name\n\
+• Or from a shell: aide task \"fix login bug\"\n\n\
+Then keep track of it with:\n\
+• aide task-status <name> in_progress\n\
+• aide task-log-update <name> \"what you did\"\n\n\
+Controls:\n• n: New task\n• r: Refresh\n• q: Quit";
+
+const AIDES_EMPTY_TEXT: &str = "No aides yet\n\n\
+An aide is a named collection of notes, commands and snippets.\n\n\
+Create your first aide:\n\
+• Press n here and type a name\n\
+• Or from a shell: aide create notes\n\n\
+Then add to it with:\n\
+• aide add notes \"your content\"\n\
+• aide add notes -p /path/to/file\n\n\
+Controls:\n• n: New aide\n• r: Refresh\n• q: Quit";
+
+const CONFIGS_EMPTY_TEXT: &str = "No configs yet\n\n\
+Seed the defaults (editor, fuzzy_threshold, inbox_aide, ...) with:\n\
+• aide init\n\n\
+Or set a key yourself:\n\
+• aide set <key> <value>\n\n\
+Controls:\n• r: Refresh\n• q: Quit";
+
+// Index into TAB_NAMES for a config value such as "aides" (case-insensitive)
+fn tab_index(name: &str) -> Option<usize> {
+    TAB_NAMES.iter().position(|tab| tab.eq_ignore_ascii_case(name.trim()))
+}
+
+pub fn is_tui_active() -> bool {
+    TUI_ACTIVE.load(Ordering::SeqCst)
+}
 
 pub struct App {
     db: Database,
     pub current_tab: usize,
+    // Tabs shown, in display order, from the `tabs` config key (all tabs if unset)
+    pub visible_tabs: Vec<usize>,
     pub tasks: Vec<TaskItem>,
     pub aides: Vec<AideItem>,
     pub configs: Vec<ConfigItem>,
@@ -35,12 +112,49 @@ pub struct App {
     pub show_status_popup: bool,
     pub show_aide_popup: bool,
     pub show_config_popup: bool,
+    pub show_delete_popup: bool,
+    pub show_new_item_popup: bool,
     pub input_buffer: String,
     pub config_value_buffer: String,
     pub popup_mode: PopupMode,
+    // Highlighted row of the priority/status pickers
+    pub picker_state: ListState,
     // Text editor
     pub text_editor: Option<TextEditor>,
     pub editor_save_callback: Option<EditorCallback>,
+    // Quick filter narrowing the current tab's list
+    pub filter_query: String,
+    pub filter_total: usize, // list length before filtering
+    // Tasks and aides created today or this week only (t cycles it)
+    pub created_filter: Option<CreatedWithin>,
+    // Scroll offsets of the right-hand panels, clamped to the content when rendered
+    pub task_info_scroll: u16,
+    pub aide_preview_scroll: u16,
+    // Entries of the selected aide, browsed in place of the aide list (Aides tab)
+    pub entry_mode: bool,
+    pub aide_entries: Vec<DataEntry>,
+    pub entry_list_state: ListState,
+    // Full-width, read-only view of the selected aide's file (Aides tab)
+    pub aide_focus: bool,
+    pub aide_focus_scroll: u16,
+    // One-off message shown in the status bar until the next key press
+    pub status_message: Option<String>,
+    // Settings read from config
+    pub preview_length: usize,
+    pub highlight_symbol: String,
+    // Aides reached with the 1-9 keys on the Aides tab, in key order
+    pub favorite_aides: Vec<String>,
+    // Folders (the part of an aide name before the first `/`) folded to a single row,
+    // and how many aides each folder holds
+    pub collapsed_folders: HashSet<String>,
+    pub folder_sizes: HashMap<String, usize>,
+    pub theme: Theme,
+    // Passphrase gate (`aide lock set`): while locked only the lock screen is drawn
+    lock_hash: Option<String>,
+    lock_timeout: Option<Duration>,
+    pub locked: bool,
+    lock_input: String,
+    last_key_press: Instant,
 }
 
 impl App {
@@ -48,6 +162,7 @@ impl App {
         let mut app = App {
             db,
             current_tab: 0,
+            visible_tabs: (0..TAB_NAMES.len()).collect(),
             tasks: Vec::new(),
             aides: Vec::new(),
             configs: Vec::new(),
@@ -59,12 +174,43 @@ impl App {
             show_status_popup: false,
             show_aide_popup: false,
             show_config_popup: false,
+            show_delete_popup: false,
+            show_new_item_popup: false,
             input_buffer: String::new(),
             config_value_buffer: String::new(),
             popup_mode: PopupMode::None,
+            picker_state: ListState::default(),
+            favorite_aides: Vec::new(),
+            collapsed_folders: HashSet::new(),
+            folder_sizes: HashMap::new(),
             text_editor: None,
             editor_save_callback: None,
+            filter_query: String::new(),
+            filter_total: 0,
+            created_filter: None,
+            task_info_scroll: 0,
+            aide_preview_scroll: 0,
+            entry_mode: false,
+            aide_entries: Vec::new(),
+            entry_list_state: ListState::default(),
+            aide_focus: false,
+            aide_focus_scroll: 0,
+            status_message: None,
+            preview_length: DEFAULT_PREVIEW_LENGTH,
+            highlight_symbol: DEFAULT_HIGHLIGHT_SYMBOL.to_string(),
+            theme: Theme::default(),
+            lock_hash: None,
+            lock_timeout: None,
+            locked: false,
+            lock_input: String::new(),
+            last_key_press: Instant::now(),
         };
+        app.lock_hash = app.db.tui_lock_hash()?;
+        app.locked = app.lock_hash.is_some();
+        // refresh_data falls back to the first visible tab if default_tab is hidden
+        app.current_tab = app.db.get_config_value("default_tab")?
+            .and_then(|name| tab_index(&name))
+            .unwrap_or(0);
         app.refresh_data()?;
         Ok(app)
     }
@@ -72,19 +218,324 @@ impl App {
     pub fn refresh_data(&mut self) -> Result<()> {
         self.tasks = self.db.get_all_tasks()?;
         self.aides = self.db.get_all_aides()?;
+        self.load_favorite_aides()?;
         self.configs = self.db.get_all_configs()?;
+        self.preview_length = self.db.get_config_value("preview_length")?
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(DEFAULT_PREVIEW_LENGTH);
+        // Kept as typed, so a trailing space can separate the symbol from the row
+        self.highlight_symbol = self.db.get_config_value("highlight_symbol")?
+            .filter(|symbol| !symbol.trim().is_empty() && !symbol.contains('\n'))
+            .unwrap_or_else(|| DEFAULT_HIGHLIGHT_SYMBOL.to_string());
+        self.load_visible_tabs()?;
+        self.lock_timeout = self.db.lock_timeout()?;
+        self.theme = Theme::preset(self.db.get_config_value("theme")?.as_deref());
+        for slot in Theme::SLOTS {
+            if let Some(value) = self.db.get_config_value(&format!("theme_{}", slot))? {
+                self.theme.set(slot, &value);
+            }
+        }
+        // Background of the selected row; the highlight slot under a name that says what it does
+        if let Some(value) = self.db.get_config_value("selection_bg")? {
+            self.theme.set("highlight", &value);
+        }
+        self.apply_created_filter();
+        self.apply_filter();
+        self.group_aides();
+        // Rows may have been deleted since the last refresh, on any tab
+        clamp_selection(&mut self.task_list_state, self.tasks.len());
+        clamp_selection(&mut self.aide_list_state, self.aides.len());
+        clamp_selection(&mut self.config_list_state, self.configs.len());
         Ok(())
     }
 
+    // Lock again once no key has been pressed for lock_timeout
+    fn lock_if_idle(&mut self) {
+        if let Some(timeout) = self.lock_timeout {
+            if self.lock_hash.is_some() && self.last_key_press.elapsed() >= timeout {
+                self.locked = true;
+            }
+        }
+    }
+    
+    // Keys typed on the lock screen: Enter checks the passphrase, Esc clears it, Ctrl+C quits
+    fn handle_lock_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => self.should_quit = true,
+            KeyCode::Char(c) => self.lock_input.push(c),
+            KeyCode::Backspace => {
+                self.lock_input.pop();
+            }
+            KeyCode::Esc => self.lock_input.clear(),
+            KeyCode::Enter => {
+                let hash = self.lock_hash.as_deref().unwrap_or_default();
+                if crypto::verify_passphrase(&self.lock_input, hash) {
+                    self.locked = false;
+                } else {
+                    self.status_message = Some("Wrong passphrase".to_string());
+                }
+                self.lock_input.clear();
+            }
+            _ => {}
+        }
+    }
+
+    // Number of rows in the current tab's list
+    fn current_list_len(&self) -> usize {
+        match self.current_tab {
+            0 => self.tasks.len(),
+            1 => self.aides.len(),
+            _ => self.configs.len(),
+        }
+    }
+
+    // Drop tasks and aides created before the start of created_filter's range. Aides without
+    // a created_at predate the column, so they're dropped too.
+    fn apply_created_filter(&mut self) {
+        let Some(created) = self.created_filter else {
+            return;
+        };
+        let since = created.start();
+        self.tasks.retain(|task| task.created_at >= since);
+        self.aides.retain(|aide| aide.created_at.as_deref().is_some_and(|created_at| created_at >= since.as_str()));
+    }
+
+    // t on the Tasks and Aides tabs: all → created today → created this week → all
+    pub fn cycle_created_filter(&mut self) -> Result<()> {
+        self.created_filter = match self.created_filter {
+            None => Some(CreatedWithin::Today),
+            Some(CreatedWithin::Today) => Some(CreatedWithin::Week),
+            Some(CreatedWithin::Week) => None,
+        };
+        self.status_message = Some(match self.created_filter {
+            Some(created) => format!("Showing tasks and aides created {}", created.label()),
+            None => "Showing all tasks and aides".to_string(),
+        });
+        self.refresh_data()
+    }
+
+    // Narrow the current tab's list to names matching filter_query (fuzzy, case-insensitive)
+    fn apply_filter(&mut self) {
+        let matcher = fuzzy_matcher::skim::SkimMatcherV2::default().ignore_case();
+        let query = self.filter_query.trim();
+        let matches = |name: &str| query.is_empty() || matcher.fuzzy_match(name, query).is_some();
+
+        let (state, len) = match self.current_tab {
+            0 => {
+                self.filter_total = self.tasks.len();
+                self.tasks.retain(|task| matches(&task.name));
+                (&mut self.task_list_state, self.tasks.len())
+            }
+            1 => {
+                self.filter_total = self.aides.len();
+                self.aides.retain(|aide| matches(&aide.name));
+                (&mut self.aide_list_state, self.aides.len())
+            }
+            _ => {
+                self.filter_total = self.configs.len();
+                self.configs.retain(|config| matches(&config.key_name));
+                (&mut self.config_list_state, self.configs.len())
+            }
+        };
+
+        if query.is_empty() {
+            return;
+        }
+        // Keep the selection on a visible row
+        match state.selected() {
+            _ if len == 0 => state.select(None),
+            Some(i) if i < len => {}
+            _ => state.select(Some(0)),
+        }
+    }
+
+    // Keep each folder's aides together, after the aides without one, and fold collapsed
+    // folders to their first row. While filtering every match is shown.
+    fn group_aides(&mut self) {
+        self.aides.sort_by_key(|aide| aide_folder(&aide.name).map(str::to_string));
+        self.folder_sizes.clear();
+        for folder in self.aides.iter().filter_map(|aide| aide_folder(&aide.name)) {
+            *self.folder_sizes.entry(folder.to_string()).or_default() += 1;
+        }
+        if !self.filter_query.trim().is_empty() {
+            return;
+        }
+        let collapsed = &self.collapsed_folders;
+        let mut shown = HashSet::new();
+        self.aides.retain(|aide| match aide_folder(&aide.name) {
+            Some(folder) if collapsed.contains(folder) => shown.insert(folder.to_string()),
+            _ => true,
+        });
+    }
+
+    // The folder an aide row stands for when that folder is collapsed
+    fn collapsed_folder_of(&self, aide: &AideItem) -> Option<String> {
+        aide_folder(&aide.name)
+            .filter(|folder| self.filter_query.trim().is_empty() && self.collapsed_folders.contains(*folder))
+            .map(str::to_string)
+    }
+
+    pub fn selected_collapsed_folder(&self) -> Option<String> {
+        let aide = self.aide_list_state.selected().and_then(|i| self.aides.get(i))?;
+        self.collapsed_folder_of(aide)
+    }
+
+    // Keys that act on one aide skip a collapsed folder's row, which only stands for the
+    // aides hidden under it; says so in the status bar instead
+    pub fn on_collapsed_folder_row(&mut self) -> bool {
+        let folder = if self.current_tab == 1 { self.selected_collapsed_folder() } else { None };
+        if let Some(folder) = &folder {
+            self.status_message = Some(format!("'{}' is collapsed; Enter or Space opens it", folder));
+        }
+        folder.is_some()
+    }
+
+    // Space on the Aides tab: fold or unfold the selected aide's folder, keeping it selected
+    pub fn toggle_aide_folder(&mut self) -> Result<()> {
+        let folder = self.aide_list_state.selected()
+            .and_then(|i| self.aides.get(i))
+            .and_then(|aide| aide_folder(&aide.name))
+            .map(str::to_string);
+        let Some(folder) = folder else {
+            self.status_message = Some("Not in a folder; name aides like work/deploy to group them".to_string());
+            return Ok(());
+        };
+        if !self.collapsed_folders.remove(&folder) {
+            self.collapsed_folders.insert(folder.clone());
+        }
+        self.refresh_data()?;
+        if let Some(i) = self.aides.iter().position(|aide| aide_folder(&aide.name) == Some(folder.as_str())) {
+            self.aide_list_state.select(Some(i));
+        }
+        Ok(())
+    }
+
+    pub fn show_filter_popup(&mut self) {
+        self.popup_mode = PopupMode::Filter;
+    }
+
+    pub fn clear_filter(&mut self) -> Result<()> {
+        if !self.filter_query.is_empty() {
+            self.filter_query.clear();
+            self.refresh_data()?;
+        }
+        Ok(())
+    }
+
+    // Title for the current tab's list, showing the active filter and match count
+    fn list_title(&self, name: &str, shown: usize) -> String {
+        let name = match self.created_filter {
+            Some(created) if self.current_tab != 2 => format!("{} created {}", name, created.label()),
+            _ => name.to_string(),
+        };
+        if self.filter_query.is_empty() && self.popup_mode != PopupMode::Filter {
+            name
+        } else {
+            let cursor = if self.popup_mode == PopupMode::Filter { "_" } else { "" };
+            format!("{} (filter: {}{} - {}/{})", name, self.filter_query, cursor, shown, self.filter_total)
+        }
+    }
+
+    // The favorite_aides config key (comma-separated names), or else the first nine aides as
+    // listed (pinned ones first). Read before filtering, so the numbers don't shift with a filter.
+    fn load_favorite_aides(&mut self) -> Result<()> {
+        let configured: Vec<String> = self.db.get_config_value("favorite_aides")?
+            .unwrap_or_default()
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+        self.favorite_aides = if configured.is_empty() {
+            self.aides.iter().map(|aide| aide.name.clone()).collect()
+        } else {
+            configured
+        };
+        self.favorite_aides.truncate(9);
+        Ok(())
+    }
+    
+    // The 1-9 key assigned to an aide, if it's a favorite
+    pub fn favorite_key(&self, aide_name: &str) -> Option<usize> {
+        self.favorite_aides.iter().position(|name| name == aide_name).map(|i| i + 1)
+    }
+    
+    // Select the aide assigned to a 1-9 key
+    pub fn jump_to_favorite(&mut self, key: usize) {
+        let Some(name) = key.checked_sub(1).and_then(|i| self.favorite_aides.get(i)) else {
+            self.status_message = Some(format!("No aide on key {}", key));
+            return;
+        };
+        // A favorite in a collapsed folder unfolds it
+        if let Some(folder) = aide_folder(name).filter(|folder| self.collapsed_folders.contains(*folder)) {
+            let (folder, name) = (folder.to_string(), name.clone());
+            self.collapsed_folders.remove(&folder);
+            if self.refresh_data().is_err() {
+                return;
+            }
+            return self.select_aide(&name);
+        }
+        let name = name.clone();
+        self.select_aide(&name);
+    }
+
+    fn select_aide(&mut self, name: &str) {
+        match self.aides.iter().position(|aide| aide.name == name) {
+            Some(i) => {
+                self.reset_panel_scroll();
+                self.aide_list_state.select(Some(i));
+            }
+            None => self.status_message = Some(format!("Aide '{}' isn't in the list", name)),
+        }
+    }
+    
+    // Read the `tabs` config key (e.g. "tasks,aides"). Unknown names and duplicates are
+    // ignored, and an empty result shows every tab so the TUI is never left without one.
+    fn load_visible_tabs(&mut self) -> Result<()> {
+        let mut tabs = Vec::new();
+        if let Some(value) = self.db.get_config_value("tabs")? {
+            for index in value.split(',').filter_map(tab_index) {
+                if !tabs.contains(&index) {
+                    tabs.push(index);
+                }
+            }
+        }
+        if tabs.is_empty() {
+            tabs = (0..TAB_NAMES.len()).collect();
+        }
+        if !tabs.contains(&self.current_tab) {
+            self.current_tab = tabs[0];
+        }
+        self.visible_tabs = tabs;
+        Ok(())
+    }
+
+    // Position of the current tab within visible_tabs
+    fn visible_tab_position(&self) -> usize {
+        self.visible_tabs.iter().position(|&tab| tab == self.current_tab).unwrap_or(0)
+    }
+
     pub fn next_tab(&mut self) {
-        self.current_tab = (self.current_tab + 1) % 3;
+        self.switch_tab(1);
     }
 
     pub fn previous_tab(&mut self) {
-        self.current_tab = if self.current_tab == 0 { 2 } else { self.current_tab - 1 };
+        self.switch_tab(self.visible_tabs.len() - 1);
+    }
+
+    // Move `step` places forward through visible_tabs, wrapping around
+    fn switch_tab(&mut self, step: usize) {
+        let _ = self.clear_filter();
+        self.aide_focus = false;
+        self.entry_mode = false;
+        let position = (self.visible_tab_position() + step) % self.visible_tabs.len();
+        self.current_tab = self.visible_tabs[position];
     }
 
     pub fn next_item(&mut self) {
+        self.reset_panel_scroll();
+        if self.current_list_len() == 0 {
+            return;
+        }
         match self.current_tab {
             0 => {
                 let i = match self.task_list_state.selected() {
@@ -130,6 +581,10 @@ impl App {
     }
 
     pub fn previous_item(&mut self) {
+        self.reset_panel_scroll();
+        if self.current_list_len() == 0 {
+            return;
+        }
         match self.current_tab {
             0 => {
                 let i = match self.task_list_state.selected() {
@@ -178,12 +633,84 @@ impl App {
         self.show_priority_popup = true;
         self.popup_mode = PopupMode::TaskPriority;
         self.input_buffer.clear();
+        // Start on the task's current priority
+        let current = self.selected_task()
+            .and_then(|task| usize::try_from(task.priority - 1).ok())
+            .filter(|&i| i < PRIORITY_NAMES.len());
+        self.picker_state.select(Some(current.unwrap_or(0)));
     }
 
     pub fn show_status_popup(&mut self) {
         self.show_status_popup = true;
         self.popup_mode = PopupMode::TaskStatus;
         self.input_buffer.clear();
+        let current = self.selected_task()
+            .and_then(|task| TASK_STATUSES.iter().position(|(status, _)| *status == task.status));
+        self.picker_state.select(Some(current.unwrap_or(0)));
+    }
+
+    fn selected_task(&self) -> Option<&TaskItem> {
+        self.task_list_state.selected().and_then(|i| self.tasks.get(i))
+    }
+
+    // Number of rows in the open picker (0 when no picker is open)
+    fn picker_len(&self) -> usize {
+        match self.popup_mode {
+            PopupMode::TaskPriority => PRIORITY_NAMES.len(),
+            PopupMode::TaskStatus => TASK_STATUSES.len(),
+            _ => 0,
+        }
+    }
+
+    // Move the picker highlight by `delta` rows, wrapping around
+    pub fn move_picker(&mut self, delta: i32) {
+        let len = self.picker_len() as i32;
+        if len == 0 {
+            return;
+        }
+        let current = self.picker_state.selected().unwrap_or(0) as i32;
+        self.picker_state.select(Some((current + delta).rem_euclid(len) as usize));
+    }
+
+    // Apply the picker row `index` to the selected task and close the popup
+    fn apply_picker(&mut self, index: usize) -> Result<()> {
+        if let Some(task_name) = self.selected_task().map(|task| task.name.clone()) {
+            match self.popup_mode {
+                PopupMode::TaskPriority => {
+                    let outcome = self.db.update_task_priority(&task_name, index as u8 + 1)?;
+                    self.report_outcome(outcome, "Priority not changed");
+                }
+                PopupMode::TaskStatus => {
+                    let outcome = self.db.update_task_status(&task_name, TASK_STATUSES[index].0)?;
+                    self.report_outcome(outcome, "Status not changed");
+                }
+                _ => {}
+            }
+            self.refresh_data()?;
+        }
+        self.close_popup();
+        Ok(())
+    }
+
+    // Space on the Tasks tab: move the selected task to the next status, wrapping from
+    // completed back to created
+    pub fn cycle_task_status(&mut self) -> Result<()> {
+        let Some(task) = self.selected_task() else {
+            return Ok(());
+        };
+        let task_name = task.name.clone();
+        let current = TASK_STATUSES.iter().position(|(status, _)| *status == task.status).unwrap_or(0);
+        let (next, label) = TASK_STATUSES[(current + 1) % TASK_STATUSES.len()];
+        let outcome = self.db.update_task_status(&task_name, next)?;
+        if outcome == Outcome::Done {
+            self.status_message = Some(format!("'{}' is now {}", task_name, label));
+        }
+        self.report_outcome(outcome, "Status not changed");
+        self.refresh_data()?;
+        if let Some(i) = self.tasks.iter().position(|task| task.name == task_name) {
+            self.task_list_state.select(Some(i));
+        }
+        Ok(())
     }
 
     pub fn show_aide_popup(&mut self) {
@@ -195,6 +722,12 @@ impl App {
     pub fn show_config_popup(&mut self) {
         if let Some(i) = self.config_list_state.selected() {
             if let Some(config) = self.configs.get(i) {
+                // The one-line popup can't show these usefully, so go straight to the editor
+                if config.value.contains('\n') || config.value.chars().count() > CONFIG_POPUP_MAX_CHARS {
+                    let (key, value) = (config.key_name.clone(), config.value.clone());
+                    self.open_config_editor(key, value);
+                    return;
+                }
                 self.show_config_popup = true;
                 self.popup_mode = PopupMode::ConfigEdit;
                 self.input_buffer.clear();
@@ -204,11 +737,228 @@ impl App {
         }
     }
 
+    // Ctrl+E in the config popup: continue editing the typed value in the full text editor
+    pub fn expand_config_popup(&mut self) {
+        let key = self.config_list_state.selected().and_then(|i| self.configs.get(i)).map(|c| c.key_name.clone());
+        if let Some(key) = key {
+            let value = std::mem::take(&mut self.config_value_buffer);
+            self.close_popup();
+            self.open_config_editor(key, value);
+        }
+    }
+
+    fn open_config_editor(&mut self, key: String, value: String) {
+        self.open_text_editor(format!("Edit Config: {}", key), value, EditorCallback::SaveConfig(key));
+    }
+
+    pub fn show_delete_popup(&mut self) {
+        if self.selected_item_name().is_some() {
+            self.show_delete_popup = true;
+            self.popup_mode = PopupMode::ConfirmDelete;
+            self.input_buffer.clear();
+        }
+    }
+
+    // Name of the selected item in the current tab
+    pub fn selected_item_name(&self) -> Option<String> {
+        match self.current_tab {
+            0 => self.task_list_state.selected().and_then(|i| self.tasks.get(i)).map(|t| t.name.clone()),
+            1 => self.aide_list_state.selected().and_then(|i| self.aides.get(i)).map(|a| a.name.clone()),
+            2 => self.config_list_state.selected().and_then(|i| self.configs.get(i)).map(|c| c.key_name.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn delete_selected_item(&mut self) -> Result<()> {
+        if let Some(name) = self.selected_item_name() {
+            match self.current_tab {
+                0 => { self.db.delete_task(&name)?; }
+                1 => { self.db.delete_aide(&name)?; }
+                2 => { self.db.delete_config_key(&name)?; }
+                _ => {}
+            }
+            // Also keeps the selection inside the now-shorter list
+            self.refresh_data()?;
+        }
+        self.close_popup();
+        Ok(())
+    }
+
+    // Shift the selected task in the manual ordering and keep it selected
+    pub fn move_selected_task(&mut self, movement: TaskMove) -> Result<()> {
+        let Some(name) = self.task_list_state.selected()
+            .and_then(|i| self.tasks.get(i))
+            .map(|task| task.name.clone()) else {
+            return Ok(());
+        };
+        self.db.reposition_task(&name, movement)?;
+        self.refresh_data()?;
+        if let Some(i) = self.tasks.iter().position(|t| t.name == name) {
+            self.task_list_state.select(Some(i));
+        }
+        Ok(())
+    }
+
+    // Text copied by `y`: the task log, the aide file body, or the config value
+    fn selected_item_content(&self) -> Option<String> {
+        match self.current_tab {
+            0 => {
+                let task = self.task_list_state.selected().and_then(|i| self.tasks.get(i))?;
+                fs::read_to_string(self.db.task_log_path(&task.name).ok()??).ok()
+            }
+            1 => {
+                let aide = self.aide_list_state.selected().and_then(|i| self.aides.get(i))?;
+                self.db.aide_text(&aide.name).ok()
+            }
+            _ => {
+                let config = self.config_list_state.selected().and_then(|i| self.configs.get(i))?;
+                Some(config.value.clone())
+            }
+        }
+    }
+
+    // A newly selected item's panel starts at the top
+    fn reset_panel_scroll(&mut self) {
+        self.task_info_scroll = 0;
+        self.aide_preview_scroll = 0;
+    }
+
+    // Scroll the current tab's right-hand panel (or the focused aide view)
+    pub fn scroll_info_panel(&mut self, delta: i32) {
+        let offset = match self.current_tab {
+            0 => &mut self.task_info_scroll,
+            1 if self.aide_focus => {
+                self.scroll_aide_focus(delta);
+                return;
+            }
+            1 => &mut self.aide_preview_scroll,
+            _ => return,
+        };
+        *offset = (*offset as i32 + delta).clamp(0, u16::MAX as i32) as u16;
+    }
+
+    // Switch the Aides tab to the selected aide's entry list
+    pub fn open_entry_list(&mut self) -> Result<()> {
+        let Some(aide_name) = self.selected_item_name() else {
+            return Ok(());
+        };
+        self.aide_entries = self.db.get_aide_entries(&aide_name)?;
+        self.entry_list_state.select(if self.aide_entries.is_empty() { None } else { Some(0) });
+        self.entry_mode = true;
+        Ok(())
+    }
+
+    pub fn close_entry_list(&mut self) {
+        self.entry_mode = false;
+        self.aide_entries.clear();
+    }
+
+    // Move the entry selection by `delta`, wrapping around like the main lists
+    pub fn step_entry(&mut self, delta: isize) {
+        let len = self.aide_entries.len() as isize;
+        if len == 0 {
+            return;
+        }
+        let current = self.entry_list_state.selected().unwrap_or(0) as isize;
+        self.entry_list_state.select(Some((current + delta).rem_euclid(len) as usize));
+    }
+
+    pub fn edit_selected_entry(&mut self) {
+        let Some(aide_name) = self.selected_item_name() else {
+            return;
+        };
+        if let Some(entry) = self.entry_list_state.selected().and_then(|i| self.aide_entries.get(i)) {
+            self.open_text_editor(
+                format!("Edit Entry: {} #{}", aide_name, self.entry_list_state.selected().unwrap_or(0) + 1),
+                entry.input_text.clone(),
+                EditorCallback::SaveEntry(aide_name, entry.id),
+            );
+        }
+    }
+
+    pub fn toggle_aide_focus(&mut self) {
+        self.aide_focus = !self.aide_focus && self.aide_list_state.selected().is_some();
+        self.aide_focus_scroll = 0;
+    }
+
+    // Scroll the focused aide view, keeping at least the last line on screen
+    pub fn scroll_aide_focus(&mut self, delta: i32) {
+        let line_count = self.selected_item_content().map_or(0, |content| content.lines().count());
+        let max_scroll = line_count.saturating_sub(1).min(u16::MAX as usize) as i32;
+        self.aide_focus_scroll = (self.aide_focus_scroll as i32 + delta).clamp(0, max_scroll) as u16;
+    }
+
+    // Copy the selected item's content to the clipboard, falling back to a temp file
+    // when no clipboard is reachable (e.g. over SSH or in a headless session)
+    pub fn copy_selected_item(&mut self) {
+        let Some(content) = self.selected_item_content() else {
+            self.status_message = Some("Nothing to copy".to_string());
+            return;
+        };
+
+        self.status_message = Some(match crate::clipboard::copy_to_clipboard(&content) {
+            Ok(()) => "Copied to clipboard".to_string(),
+            Err(_) => {
+                let name = self.selected_item_name().unwrap_or_default();
+                let prefix = match crate::paths::file_stem(&name) {
+                    Ok(stem) => format!("aide-copy-{}-", stem),
+                    Err(_) => "aide-copy-".to_string(),
+                };
+                match crate::paths::write_private_temp_file(&prefix, &content) {
+                    Ok(path) => format!("No clipboard available; saved to {}", path.display()),
+                    Err(e) => format!("Copy failed: {}", e),
+                }
+            }
+        });
+    }
+
+    pub fn show_new_item_popup(&mut self) {
+        self.show_new_item_popup = true;
+        self.popup_mode = PopupMode::NewItem;
+        self.input_buffer.clear();
+    }
+
+    // Create a task or aide from the name typed into the popup, without spawning an editor
+    pub fn handle_new_item(&mut self) -> Result<()> {
+        let mut name = self.input_buffer.trim().to_string();
+        let mut keyword_priority = None;
+        // An existing task is selected as typed; only a new one takes priority_keywords
+        if self.current_tab == 0 && self.db.task_log_path(&name)?.is_none() {
+            (name, keyword_priority) = self.db.priority_from_keywords(&name)?;
+        }
+        let valid = if self.current_tab == 0 { validate_name("Task", &name) } else { validate_aide_name(&name) };
+        if let Err(reason) = valid {
+            self.status_message = Some(reason);
+        } else {
+            match self.current_tab {
+                0 => {
+                    self.db.create_task_no_editor(&name, keyword_priority)?;
+                    self.refresh_data()?;
+                    if let Some(i) = self.tasks.iter().position(|t| t.name == name) {
+                        self.task_list_state.select(Some(i));
+                    }
+                }
+                1 => {
+                    self.db.insert_aide(&name)?;
+                    self.refresh_data()?;
+                    if let Some(i) = self.aides.iter().position(|a| a.name == name) {
+                        self.aide_list_state.select(Some(i));
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.close_popup();
+        Ok(())
+    }
+
     pub fn close_popup(&mut self) {
         self.show_priority_popup = false;
         self.show_status_popup = false;
         self.show_aide_popup = false;
         self.show_config_popup = false;
+        self.show_delete_popup = false;
+        self.show_new_item_popup = false;
         self.popup_mode = PopupMode::None;
         self.input_buffer.clear();
         self.config_value_buffer.clear();
@@ -216,48 +966,15 @@ impl App {
 
     pub fn handle_popup_input(&mut self, c: char) -> Result<()> {
         match self.popup_mode {
-            PopupMode::TaskPriority => {
-                if c.is_ascii_digit() && c >= '1' && c <= '5' {
-                    if let Some(i) = self.task_list_state.selected() {
-                        if let Some(task) = self.tasks.get(i) {
-                            let priority = c as u8 - b'0';
-                            self.db.update_task_priority(&task.name, priority)?;
-                            self.refresh_data()?;
-                        }
-                    }
-                    self.close_popup();
-                }
-            }
-            PopupMode::TaskStatus => {
-                match c {
-                    '1' => {
-                        if let Some(i) = self.task_list_state.selected() {
-                            if let Some(task) = self.tasks.get(i) {
-                                self.db.update_task_status(&task.name, "created")?;
-                                self.refresh_data()?;
-                            }
-                        }
-                        self.close_popup();
-                    }
-                    '2' => {
-                        if let Some(i) = self.task_list_state.selected() {
-                            if let Some(task) = self.tasks.get(i) {
-                                self.db.update_task_status(&task.name, "in_progress")?;
-                                self.refresh_data()?;
-                            }
-                        }
-                        self.close_popup();
-                    }
-                    '3' => {
-                        if let Some(i) = self.task_list_state.selected() {
-                            if let Some(task) = self.tasks.get(i) {
-                                self.db.update_task_status(&task.name, "completed")?;
-                                self.refresh_data()?;
-                            }
-                        }
-                        self.close_popup();
-                    }
-                    _ => {}
+            PopupMode::TaskPriority | PopupMode::TaskStatus => {
+                // Enter applies the highlighted row; digits still pick a row directly
+                let index = if c == '\n' || c == '\r' {
+                    self.picker_state.selected()
+                } else {
+                    c.to_digit(10).and_then(|d| (d as usize).checked_sub(1))
+                };
+                if let Some(index) = index.filter(|&i| i < self.picker_len()) {
+                    self.apply_picker(index)?;
                 }
             }
             PopupMode::AideEdit => {
@@ -274,6 +991,29 @@ impl App {
                     self.config_value_buffer.push(c);
                 }
             }
+            PopupMode::NewItem => {
+                if c == '\n' || c == '\r' {
+                    self.handle_new_item()?;
+                } else if !c.is_control() {
+                    self.input_buffer.push(c);
+                }
+            }
+            PopupMode::Filter => {
+                if c == '\n' || c == '\r' {
+                    // Keep the filter and go back to navigating the narrowed list
+                    self.popup_mode = PopupMode::None;
+                } else if !c.is_control() {
+                    self.filter_query.push(c);
+                    self.refresh_data()?;
+                }
+            }
+            PopupMode::ConfirmDelete => {
+                match c {
+                    'y' | 'Y' => self.delete_selected_item()?,
+                    'n' | 'N' => self.close_popup(),
+                    _ => {}
+                }
+            }
             PopupMode::TextEditor => {
                 // Text editor input is handled separately in handle_text_editor_input
             }
@@ -283,38 +1023,45 @@ impl App {
     }
 
     pub fn handle_backspace(&mut self) {
-        if matches!(self.popup_mode, PopupMode::AideEdit) {
+        if matches!(self.popup_mode, PopupMode::AideEdit | PopupMode::NewItem) {
             self.input_buffer.pop();
         } else if matches!(self.popup_mode, PopupMode::ConfigEdit) {
             self.config_value_buffer.pop();
+        } else if matches!(self.popup_mode, PopupMode::Filter) && self.filter_query.pop().is_some() {
+            let _ = self.refresh_data();
         }
     }
 
+    // Surface a handled database failure in the status bar
+    fn report_outcome(&mut self, outcome: Outcome, action: &str) {
+        let reason = match outcome {
+            Outcome::Done => return,
+            Outcome::NotFound => "not found",
+            Outcome::Cancelled => "cancelled",
+            Outcome::Invalid => "invalid input",
+            Outcome::Failed => "failed",
+            Outcome::Blocked => "blocked by unfinished dependencies",
+        };
+        self.status_message = Some(format!("{}: {}", action, reason));
+    }
+
     pub fn open_text_editor(&mut self, title: String, content: String, callback: EditorCallback) {
-        self.text_editor = Some(TextEditor::new(title, content));
+        let mut editor = TextEditor::new(title, content);
+        editor.entry_format = self.db.entry_format().unwrap_or_default();
+        self.text_editor = Some(editor);
         self.editor_save_callback = Some(callback);
         self.popup_mode = PopupMode::TextEditor;
     }
 
     pub fn close_text_editor(&mut self, save: bool) -> Result<()> {
-        if let Some(editor) = &self.text_editor {
-            if save && editor.is_dirty {
-                if let Some(callback) = &self.editor_save_callback {
-                    let content = editor.get_content();
-                    match callback {
-                        EditorCallback::SaveTask(task_name) => {
-                            // Save task log content to file
-                            let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-                            let tasks_dir = PathBuf::from(&home_dir).join(".aide").join("tasks");
-                            let task_file = tasks_dir.join(format!("{}.txt", task_name));
-                            fs::write(&task_file, &content)?;
-                        }
-                        EditorCallback::SaveAide(aide_name) => {
-                            self.db.update_aide_content(aide_name, &content)?;
-                            self.refresh_data()?;
-                        }
-                    }
-                }
+        let pending = match (&self.text_editor, &self.editor_save_callback) {
+            (Some(editor), Some(callback)) if save && editor.is_dirty => Some((callback.clone(), editor.get_content())),
+            _ => None,
+        };
+        // On a failed or rejected save the editor stays open, so the text isn't lost
+        if let Some((callback, content)) = pending {
+            if self.save_editor_content(callback, &content)? != Outcome::Done {
+                return Ok(());
             }
         }
         
@@ -324,6 +1071,45 @@ impl App {
         Ok(())
     }
 
+    // Write edited text back to whatever the editor was opened on. A new kind of editable
+    // item needs only an EditorCallback variant and an arm here.
+    fn save_editor_content(&mut self, callback: EditorCallback, content: &str) -> Result<Outcome> {
+        let outcome = match callback {
+            EditorCallback::SaveTask(task_name) => {
+                // Save task log content to file
+                let task_file = self.db.task_log_path(&task_name)?
+                    .map_or_else(|| paths::task_file(&task_name), Ok)?;
+                fs::write(&task_file, content)?;
+                Outcome::Done
+            }
+            EditorCallback::SaveAide(aide_name) => {
+                // Without .txt files (or for an encrypted aide) the edited text is the
+                // rebuilt file, so apply it as entries
+                if self.db.write_txt_files()? && !self.db.aide_encrypted(&aide_name)? {
+                    self.db.update_aide_content(&aide_name, content)?;
+                } else {
+                    self.db.apply_aide_edit(&aide_name, content)?;
+                }
+                Outcome::Done
+            }
+            EditorCallback::SaveEntry(aide_name, entry_id) => {
+                let outcome = match self.aide_entries.iter().find(|e| e.id == entry_id).cloned() {
+                    Some(entry) => self.db.update_aide_entry(&aide_name, &entry, content.trim_end())?,
+                    None => Outcome::NotFound,
+                };
+                self.report_outcome(outcome, "Entry not saved");
+                self.aide_entries = self.db.get_aide_entries(&aide_name)?;
+                outcome
+            }
+            EditorCallback::SaveConfig(key) => {
+                // The editor ends the text with a newline the value shouldn't keep
+                self.save_config_value(&key, content.trim_end_matches('\n'))?
+            }
+        };
+        self.refresh_data()?;
+        Ok(outcome)
+    }
+
     pub fn handle_text_editor_input(&mut self, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
         if let Some(editor) = &mut self.text_editor {
             match key {
@@ -338,8 +1124,23 @@ impl App {
                                 // Quit without saving
                                 self.close_text_editor(false)?;
                             }
+                            'w' => {
+                                editor.delete_word();
+                            }
+                            'd' => {
+                                editor.duplicate_line();
+                            }
+                            'k' => {
+                                editor.delete_line();
+                            }
                             _ => {}
                         }
+                    } else if modifiers.contains(KeyModifiers::ALT) && matches!(c, 'n' | 'p') {
+                        if c == 'n' {
+                            editor.next_entry();
+                        } else {
+                            editor.previous_entry();
+                        }
                     } else {
                         editor.insert_char(c);
                     }
@@ -347,9 +1148,18 @@ impl App {
                 KeyCode::Enter => {
                     editor.insert_newline();
                 }
+                KeyCode::Backspace if modifiers.contains(KeyModifiers::CONTROL) => {
+                    editor.delete_word();
+                }
                 KeyCode::Backspace => {
                     editor.delete_char();
                 }
+                KeyCode::Left if modifiers.contains(KeyModifiers::CONTROL) => {
+                    editor.move_word_left();
+                }
+                KeyCode::Right if modifiers.contains(KeyModifiers::CONTROL) => {
+                    editor.move_word_right();
+                }
                 KeyCode::Left => {
                     editor.move_cursor_left();
                 }
@@ -387,9 +1197,8 @@ impl App {
         if let Some(i) = self.task_list_state.selected() {
             if let Some(task) = self.tasks.get(i) {
                 // Read existing task log content
-                let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-                let tasks_dir = PathBuf::from(&home_dir).join(".aide").join("tasks");
-                let task_file = tasks_dir.join(format!("{}.txt", task.name));
+                let task_file = self.db.task_log_path(&task.name)?
+                    .map_or_else(|| paths::task_file(&task.name), Ok)?;
                 
                 let content = if task_file.exists() {
                     fs::read_to_string(&task_file).unwrap_or_default()
@@ -411,22 +1220,8 @@ impl App {
     pub fn edit_selected_aide(&mut self) -> Result<()> {
         if let Some(i) = self.aide_list_state.selected() {
             if let Some(aide) = self.aides.get(i) {
-                // All aides are now files, so read the actual file content
-                let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-                let aide_dir = PathBuf::from(&home_dir).join(".aide");
-                let file_path = aide_dir.join(format!("{}.txt", aide.name));
-                
-                let formatted_content = if file_path.exists() {
-                    fs::read_to_string(&file_path).unwrap_or_else(|_| {
-                        format!("# {}\n\nCreated: {}\n\n", 
-                               aide.name, 
-                               chrono::Utc::now().format("%Y-%m-%d %H:%M:%S"))
-                    })
-                } else {
-                    format!("# {}\n\nCreated: {}\n\n", 
-                           aide.name, 
-                           chrono::Utc::now().format("%Y-%m-%d %H:%M:%S"))
-                };
+                // The aide file, or its content rebuilt from the database when there's no file
+                let formatted_content = self.db.aide_text(&aide.name)?;
                 
                 self.open_text_editor(
                     format!("Edit Aide: {}", aide.name),
@@ -449,10 +1244,24 @@ impl App {
         Ok(())
     }
 
+    // Config edits are checked like `aide set` checks them, and a rejected value stays unsaved
+    fn save_config_value(&mut self, key: &str, value: &str) -> Result<Outcome> {
+        if let Err(reason) = validate_config_value(key, value) {
+            self.status_message = Some(format!("Config not saved: {}", reason));
+            return Ok(Outcome::Invalid);
+        }
+        self.db.update_config_value(key, value)?;
+        Ok(Outcome::Done)
+    }
+
     pub fn handle_config_edit(&mut self) -> Result<()> {
         if let Some(i) = self.config_list_state.selected() {
             if let Some(config) = self.configs.get(i) {
-                self.db.update_config_value(&config.key_name, &self.config_value_buffer)?;
+                let (key, value) = (config.key_name.clone(), self.config_value_buffer.clone());
+                // A rejected value stays in the popup to be corrected
+                if self.save_config_value(&key, &value)? != Outcome::Done {
+                    return Ok(());
+                }
                 self.refresh_data()?;
             }
         }
@@ -461,31 +1270,65 @@ impl App {
     }
 }
 
-pub fn run_tui(db: Database) -> Result<()> {
+// With `no_alt_screen` the TUI draws over the normal screen, leaving its final frame in scrollback
+pub fn run_tui(db: Database, no_alt_screen: bool) -> Result<()> {
+    // The passphrase can't be asked for once the TUI owns the terminal
+    db.unlock()?;
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    if !no_alt_screen {
+        execute!(stdout, EnterAlternateScreen)?;
+    }
+    execute!(stdout, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new(db)?;
-    let res = run_app(&mut terminal, &mut app);
+    // A panic skips the cleanup below, so restore the terminal from the panic hook
+    // before the message prints; otherwise the shell is left in raw mode / the alternate screen
+    let previous_hook: Arc<dyn Fn(&panic::PanicHookInfo) + Send + Sync> = Arc::from(panic::take_hook());
+    let chained_hook = Arc::clone(&previous_hook);
+    panic::set_hook(Box::new(move |info| {
+        restore_terminal_after_panic(no_alt_screen);
+        chained_hook(info);
+    }));
+
+    TUI_ACTIVE.store(true, Ordering::SeqCst);
+    let res = App::new(db).and_then(|mut app| Ok(run_app(&mut terminal, &mut app)?));
+    TUI_ACTIVE.store(false, Ordering::SeqCst);
+
+    drop(panic::take_hook());
+    panic::set_hook(Box::new(move |info| previous_hook(info)));
 
     disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
+    if no_alt_screen {
+        // Park the cursor below the last frame so the shell prompt doesn't overwrite it
+        let height = terminal.size()?.height;
+        execute!(terminal.backend_mut(), cursor::MoveTo(0, height.saturating_sub(1)))?;
+        println!();
+    } else {
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    }
+    execute!(terminal.backend_mut(), DisableMouseCapture)?;
     terminal.show_cursor()?;
 
     if let Err(err) = res {
-        println!("{err:?}");
+        eprintln!("{err:?}");
     }
 
     Ok(())
 }
 
+// Best-effort terminal reset for the panic hook; errors are ignored since we're already failing
+fn restore_terminal_after_panic(no_alt_screen: bool) {
+    TUI_ACTIVE.store(false, Ordering::SeqCst);
+    let _ = disable_raw_mode();
+    let mut stdout = io::stdout();
+    if !no_alt_screen {
+        let _ = execute!(stdout, LeaveAlternateScreen);
+    }
+    let _ = execute!(stdout, DisableMouseCapture, cursor::Show);
+}
+
 fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut App,
@@ -493,22 +1336,52 @@ fn run_app<B: ratatui::backend::Backend>(
     loop {
         terminal.draw(|f| ui(f, app))?;
 
+        // With a passphrase set, wake up now and then to lock after the idle timeout
+        if app.lock_hash.is_some() && !event::poll(Duration::from_secs(1))? {
+            app.lock_if_idle();
+            continue;
+        }
+
         if let Event::Key(key) = event::read()? {
             if key.kind == KeyEventKind::Press {
-                // Handle text editor input first
-                if app.popup_mode == PopupMode::TextEditor {
+                app.status_message = None;
+                app.lock_if_idle();
+                app.last_key_press = Instant::now();
+
+                if app.locked {
+                    app.handle_lock_input(key.code, key.modifiers);
+                } else if app.popup_mode == PopupMode::TextEditor {
+                    // Handle text editor input first
                     let _ = app.handle_text_editor_input(key.code, key.modifiers);
                 } else if app.popup_mode != PopupMode::None {
                     match key.code {
                         KeyCode::Esc => {
+                            if app.popup_mode == PopupMode::Filter {
+                                let _ = app.clear_filter();
+                            }
                             app.close_popup();
                         }
+                        KeyCode::Char('e')
+                            if app.popup_mode == PopupMode::ConfigEdit
+                                && key.modifiers.contains(KeyModifiers::CONTROL) =>
+                        {
+                            app.expand_config_popup();
+                        }
                         KeyCode::Char(c) => {
                             let _ = app.handle_popup_input(c);
                         }
+                        KeyCode::Enter => {
+                            let _ = app.handle_popup_input('\n');
+                        }
                         KeyCode::Backspace => {
                             app.handle_backspace();
                         }
+                        KeyCode::Up if app.picker_len() > 0 => {
+                            app.move_picker(-1);
+                        }
+                        KeyCode::Down if app.picker_len() > 0 => {
+                            app.move_picker(1);
+                        }
                         _ => {}
                     }
                 } else {
@@ -517,12 +1390,72 @@ fn run_app<B: ratatui::backend::Backend>(
                         KeyCode::Char('q') => {
                             app.should_quit = true;
                         }
+                        KeyCode::Esc if app.aide_focus => {
+                            app.toggle_aide_focus();
+                        }
+                        KeyCode::Esc | KeyCode::Left if app.entry_mode => {
+                            app.close_entry_list();
+                        }
+                        KeyCode::Down if app.entry_mode => {
+                            app.step_entry(1);
+                        }
+                        KeyCode::Up if app.entry_mode => {
+                            app.step_entry(-1);
+                        }
+                        KeyCode::Enter if app.entry_mode => {
+                            app.edit_selected_entry();
+                        }
+                        KeyCode::Right if app.current_tab == 1 && !app.aide_focus && !app.on_collapsed_folder_row() => {
+                            let _ = app.open_entry_list();
+                        }
+                        KeyCode::Esc => {
+                            let _ = app.clear_filter();
+                        }
+                        KeyCode::Char('z') if app.current_tab == 1 => {
+                            app.toggle_aide_focus();
+                        }
+                        KeyCode::Char(' ') if app.current_tab == 0 => {
+                            let _ = app.cycle_task_status();
+                        }
+                        KeyCode::Char(' ') if app.current_tab == 1 && !app.aide_focus && !app.entry_mode => {
+                            let _ = app.toggle_aide_folder();
+                        }
+                        KeyCode::Char(c @ '1'..='9') if app.current_tab == 1 && !app.aide_focus && !app.entry_mode => {
+                            app.jump_to_favorite(c as usize - '0' as usize);
+                        }
+                        KeyCode::PageDown => {
+                            app.scroll_info_panel(PAGE_SCROLL_LINES);
+                        }
+                        KeyCode::PageUp => {
+                            app.scroll_info_panel(-PAGE_SCROLL_LINES);
+                        }
+                        KeyCode::Down if app.aide_focus => {
+                            app.scroll_aide_focus(1);
+                        }
+                        KeyCode::Up if app.aide_focus => {
+                            app.scroll_aide_focus(-1);
+                        }
+                        KeyCode::Char('f') => {
+                            app.show_filter_popup();
+                        }
+                        KeyCode::Char('t') if app.current_tab != 2 && !app.aide_focus && !app.entry_mode => {
+                            let _ = app.cycle_created_filter();
+                        }
+                        KeyCode::Char('y') if !app.on_collapsed_folder_row() => {
+                            app.copy_selected_item();
+                        }
                         KeyCode::Tab => {
                             app.next_tab();
                         }
                         KeyCode::BackTab => {
                             app.previous_tab();
                         }
+                        KeyCode::Up if app.current_tab == 0 && key.modifiers.contains(KeyModifiers::ALT) => {
+                            let _ = app.move_selected_task(TaskMove::Up);
+                        }
+                        KeyCode::Down if app.current_tab == 0 && key.modifiers.contains(KeyModifiers::ALT) => {
+                            let _ = app.move_selected_task(TaskMove::Down);
+                        }
                         KeyCode::Down => {
                             app.next_item();
                         }
@@ -533,7 +1466,11 @@ fn run_app<B: ratatui::backend::Backend>(
                             if app.current_tab == 0 {
                                 let _ = app.edit_selected_task();
                             } else if app.current_tab == 1 {
-                                let _ = app.edit_selected_aide();
+                                if app.selected_collapsed_folder().is_some() {
+                                    let _ = app.toggle_aide_folder();
+                                } else {
+                                    let _ = app.edit_selected_aide();
+                                }
                             } else if app.current_tab == 2 {
                                 app.show_config_popup();
                             }
@@ -541,25 +1478,23 @@ fn run_app<B: ratatui::backend::Backend>(
                         KeyCode::Char('r') => {
                             let _ = app.refresh_data();
                         }
-                        KeyCode::Char('p') => {
-                            if app.current_tab == 0 {
-                                app.show_priority_popup();
-                            }
+                        KeyCode::Char('p') if app.current_tab == 0 => {
+                            app.show_priority_popup();
                         }
-                        KeyCode::Char('s') => {
-                            if app.current_tab == 0 {
-                                app.show_status_popup();
-                            }
+                        KeyCode::Char('s') if app.current_tab == 0 => {
+                            app.show_status_popup();
                         }
-                        KeyCode::Char('e') => {
-                            if app.current_tab == 1 {
-                                app.show_aide_popup();
-                            }
+                        KeyCode::Char('e') if app.current_tab == 1 && !app.on_collapsed_folder_row() => {
+                            app.show_aide_popup();
                         }
-                        KeyCode::Char('c') => {
-                            if app.current_tab == 2 {
-                                app.show_config_popup();
-                            }
+                        KeyCode::Char('c') if app.current_tab == 2 => {
+                            app.show_config_popup();
+                        }
+                        KeyCode::Char('d') if !app.on_collapsed_folder_row() => {
+                            app.show_delete_popup();
+                        }
+                        KeyCode::Char('n') if app.current_tab == 0 || app.current_tab == 1 => {
+                            app.show_new_item_popup();
                         }
                         _ => {}
                     }
@@ -575,25 +1510,29 @@ fn run_app<B: ratatui::backend::Backend>(
 }
 
 fn ui(f: &mut Frame, app: &mut App) {
+    let theme = app.theme;
+    if app.locked {
+        render_lock_screen(f, app);
+        return;
+    }
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+        .constraints([Constraint::Length(3), Constraint::Min(0), Constraint::Length(1)].as_ref())
         .split(f.area());
 
-    let titles: Vec<Line> = ["Tasks", "Aides", "Configs"]
+    let titles: Vec<Line> = app.visible_tabs
         .iter()
-        .cloned()
-        .map(Line::from)
+        .map(|&tab| Line::from(TAB_NAMES[tab]))
         .collect();
     
     let tabs = Tabs::new(titles)
         .block(Block::default().borders(Borders::ALL).title("Aide TUI"))
-        .select(app.current_tab)
-        .style(Style::default().fg(Color::Cyan))
+        .select(app.visible_tab_position())
+        .style(Style::default().fg(theme.accent))
         .highlight_style(
             Style::default()
                 .add_modifier(Modifier::BOLD)
-                .bg(Color::Black),
+                .bg(theme.background),
         );
     
     f.render_widget(tabs, chunks[0]);
@@ -604,60 +1543,79 @@ fn ui(f: &mut Frame, app: &mut App) {
         2 => render_configs(f, app, chunks[1]),
         _ => {}
     }
+    render_status_bar(f, app, chunks[2]);
 
     // Render popups
     if app.show_priority_popup {
-        let popup_area = centered_rect(50, 20, f.area());
+        let labels: Vec<String> = PRIORITY_NAMES.iter()
+            .enumerate()
+            .map(|(i, name)| format!("{}  {}", i + 1, name))
+            .collect();
+        render_picker(f, app, "Change Task Priority", &labels);
+    }
+
+    if app.show_status_popup {
+        let labels: Vec<String> = TASK_STATUSES.iter()
+            .enumerate()
+            .map(|(i, (_, label))| format!("{}  {}", i + 1, label))
+            .collect();
+        render_picker(f, app, "Change Task Status", &labels);
+    }
+
+    if app.show_aide_popup {
+        let popup_area = centered_rect(60, 25, f.area());
         let block = Block::default()
-            .title("Change Task Priority")
+            .title("Quick Edit Aide")
             .borders(Borders::ALL)
-            .style(Style::default().bg(Color::DarkGray));
-        let content = Paragraph::new("Enter new priority (1-5):\n\n1 = Highest Priority\n2 = High Priority\n3 = Medium Priority\n4 = Low Priority\n5 = Lowest Priority\n\nPress ESC to cancel")
+            .style(Style::default().bg(theme.popup_bg));
+        let content = Paragraph::new(format!("Enter input text for aide:\n\n{}\n\nPress ENTER to save\nPress ESC to cancel", app.input_buffer))
             .block(block)
             .alignment(ratatui::layout::Alignment::Center)
-            .style(Style::default().fg(Color::White));
+            .style(Style::default().fg(theme.text));
         
         f.render_widget(content, popup_area);
     }
 
-    if app.show_status_popup {
+    if app.show_config_popup {
         let popup_area = centered_rect(50, 20, f.area());
         let block = Block::default()
-            .title("Change Task Status")
+            .title("Edit Config Value")
             .borders(Borders::ALL)
-            .style(Style::default().bg(Color::DarkGray));
-        let content = Paragraph::new("Select new status:\n\n1. Created\n2. In Progress\n3. Completed\n\nPress ESC to cancel")
+            .style(Style::default().bg(theme.popup_bg));
+        let content = Paragraph::new(format!("Enter new value for config:\n\n{}\n\nPress ENTER to save\nPress Ctrl+E for the full editor (multi-line values)\nPress ESC to cancel", app.config_value_buffer))
             .block(block)
             .alignment(ratatui::layout::Alignment::Center)
-            .style(Style::default().fg(Color::White));
+            .style(Style::default().fg(theme.text));
         
         f.render_widget(content, popup_area);
     }
 
-    if app.show_aide_popup {
-        let popup_area = centered_rect(60, 25, f.area());
+    if app.show_new_item_popup {
+        let popup_area = centered_rect(50, 20, f.area());
+        let title = if app.current_tab == 0 { "New Task" } else { "New Aide" };
         let block = Block::default()
-            .title("Quick Edit Aide")
+            .title(title)
             .borders(Borders::ALL)
-            .style(Style::default().bg(Color::DarkGray));
-        let content = Paragraph::new(format!("Enter input text for aide:\n\n{}\n\nPress ENTER to save\nPress ESC to cancel", app.input_buffer))
+            .style(Style::default().bg(theme.popup_bg));
+        let content = Paragraph::new(format!("Enter a name:\n\n{}\n\nPress ENTER to create\nPress ESC to cancel", app.input_buffer))
             .block(block)
             .alignment(ratatui::layout::Alignment::Center)
-            .style(Style::default().fg(Color::White));
+            .style(Style::default().fg(theme.text));
         
         f.render_widget(content, popup_area);
     }
 
-    if app.show_config_popup {
+    if app.show_delete_popup {
         let popup_area = centered_rect(50, 20, f.area());
         let block = Block::default()
-            .title("Edit Config Value")
+            .title("Confirm Delete")
             .borders(Borders::ALL)
-            .style(Style::default().bg(Color::DarkGray));
-        let content = Paragraph::new(format!("Enter new value for config:\n\n{}\n\nPress ENTER to save\nPress ESC to cancel", app.config_value_buffer))
+            .style(Style::default().bg(theme.popup_bg));
+        let name = app.selected_item_name().unwrap_or_default();
+        let content = Paragraph::new(format!("Delete '{}'? (y/n)\n\nThis cannot be undone.\n\nPress ESC to cancel", name))
             .block(block)
             .alignment(ratatui::layout::Alignment::Center)
-            .style(Style::default().fg(Color::White));
+            .style(Style::default().fg(theme.text));
         
         f.render_widget(content, popup_area);
     }
@@ -666,17 +1624,17 @@ fn ui(f: &mut Frame, app: &mut App) {
     if let Some(editor) = &mut app.text_editor {
         // Create a completely opaque full-screen background using Clear
         f.render_widget(
-            Block::default().style(Style::default().bg(Color::Black)),
+            Block::default().style(Style::default().bg(theme.background)),
             f.area()
         );
         
         // Fill the entire screen with black background characters
         let full_bg_lines: Vec<Line> = (0..f.area().height)
-            .map(|_| Line::from(Span::styled(" ".repeat(f.area().width as usize), Style::default().bg(Color::Black))))
+            .map(|_| Line::from(Span::styled(" ".repeat(f.area().width as usize), Style::default().bg(theme.background))))
             .collect();
         
         let full_bg = Paragraph::new(full_bg_lines)
-            .style(Style::default().bg(Color::Black));
+            .style(Style::default().bg(theme.background));
         f.render_widget(full_bg, f.area());
         
         let editor_area = centered_rect(90, 80, f.area());
@@ -685,7 +1643,7 @@ fn ui(f: &mut Frame, app: &mut App) {
         let block = Block::default()
             .title(format!("{} - Ctrl+S: Save | Ctrl+Q: Quit | ESC: Cancel | PgUp/PgDn: Scroll | Home/End: Line Nav", &editor.title))
             .borders(Borders::ALL)
-            .style(Style::default().bg(Color::DarkGray).fg(Color::White));
+            .style(Style::default().bg(theme.popup_bg).fg(theme.text));
         
         let inner_area = block.inner(editor_area);
         f.render_widget(block, editor_area);
@@ -713,7 +1671,7 @@ fn ui(f: &mut Frame, app: &mut App) {
                     // Before cursor
                     if editor.cursor_col > 0 && editor.cursor_col <= line_chars.len() {
                         let before_cursor: String = line_chars[..editor.cursor_col].iter().collect();
-                        line_spans.push(Span::styled(before_cursor, Style::default().fg(Color::White).bg(Color::Black)));
+                        line_spans.push(Span::styled(before_cursor, Style::default().fg(theme.text).bg(theme.background)));
                     }
                     
                     // Cursor
@@ -722,13 +1680,13 @@ fn ui(f: &mut Frame, app: &mut App) {
                     } else {
                         " ".to_string()
                     };
-                    line_spans.push(Span::styled(cursor_char, Style::default().bg(Color::Cyan).fg(Color::Black)));
+                    line_spans.push(Span::styled(cursor_char, Style::default().bg(theme.accent).fg(theme.background)));
                     
                     // After cursor
                     if editor.cursor_col < line_chars.len() {
                         let after_cursor: String = line_chars[editor.cursor_col + 1..].iter().collect();
                         if !after_cursor.is_empty() {
-                            line_spans.push(Span::styled(after_cursor, Style::default().fg(Color::White).bg(Color::Black)));
+                            line_spans.push(Span::styled(after_cursor, Style::default().fg(theme.text).bg(theme.background)));
                         }
                     }
                     
@@ -736,7 +1694,7 @@ fn ui(f: &mut Frame, app: &mut App) {
                     let current_width: usize = line_chars.len();
                     if current_width < inner_area.width as usize {
                         let padding = " ".repeat(inner_area.width as usize - current_width);
-                        line_spans.push(Span::styled(padding, Style::default().bg(Color::Black)));
+                        line_spans.push(Span::styled(padding, Style::default().bg(theme.background)));
                     }
                     
                     content_lines.push(Line::from(line_spans));
@@ -747,24 +1705,180 @@ fn ui(f: &mut Frame, app: &mut App) {
                     } else {
                         line.clone()
                     };
-                    content_lines.push(Line::from(Span::styled(padded_line, Style::default().fg(Color::White).bg(Color::Black))));
+                    content_lines.push(Line::from(Span::styled(padded_line, Style::default().fg(theme.text).bg(theme.background))));
                 }
             }
         }
         
         // Fill remaining space with full-width empty lines
         while content_lines.len() < visible_height {
-            content_lines.push(Line::from(Span::styled(" ".repeat(inner_area.width as usize), Style::default().bg(Color::Black))));
+            content_lines.push(Line::from(Span::styled(" ".repeat(inner_area.width as usize), Style::default().bg(theme.background))));
         }
         
         // Render the editor content
         let editor_content = Paragraph::new(content_lines)
-            .style(Style::default().fg(Color::White).bg(Color::Black));
+            .style(Style::default().fg(theme.text).bg(theme.background));
         
         f.render_widget(editor_content, inner_area);
     }
 }
 
+// Keep a list selection on an existing row: the last one if it's past the end, none if the list is empty
+fn clamp_selection(state: &mut ListState, len: usize) {
+    match state.selected() {
+        Some(_) if len == 0 => state.select(None),
+        Some(i) if i >= len => state.select(Some(len - 1)),
+        _ => {}
+    }
+}
+
+// Limit a scroll offset so the last line of wrapped `text` stays at the bottom of the
+// bordered `area` instead of scrolling past the end
+fn clamp_scroll(offset: u16, text: &str, area: Rect) -> u16 {
+    let width = area.width.saturating_sub(2).max(1) as usize;
+    let height = area.height.saturating_sub(2) as usize;
+    let wrapped_lines: usize = text
+        .lines()
+        .map(|line| line.chars().count().div_ceil(width).max(1))
+        .sum();
+    let max_offset = wrapped_lines.saturating_sub(height).min(u16::MAX as usize) as u16;
+    offset.min(max_offset)
+}
+
+// Folder of an aide named like "work/deploy": the part before the first `/`
+fn aide_folder(name: &str) -> Option<&str> {
+    name.split_once('/').map(|(folder, _)| folder)
+}
+
+// Right-hand panel text when a tab's list is empty: why (an active filter, or the created
+// date filter on Tasks and Aides), or else how to create the first item
+fn empty_list_text(app: &App, items: &str, getting_started: &str) -> String {
+    if !app.filter_query.is_empty() {
+        return format!("No {} match the filter '{}'.\n\nPress ESC to clear the filter.", items, app.filter_query);
+    }
+    match app.created_filter {
+        Some(created) if app.current_tab != 2 => format!("No {} created {} (t to change)", items, created.label()),
+        _ => getting_started.to_string(),
+    }
+}
+
+// Truncate to at most `max_chars` characters, adding "..." when cut
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() > max_chars {
+        format!("{}...", text.chars().take(max_chars).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+// Word-wrap `text` to `width` columns after `prefix`, indenting continuation lines to line
+// up under the text rather than the prefix (a hanging indent). Spacing is kept as typed, so
+// indented code or YAML stays readable; only the spaces at a wrap are dropped. Words wider
+// than the remaining space are split. Widths are display columns, so CJK text and emoji fit.
+fn wrap_hanging(prefix: &str, text: &str, width: usize) -> Vec<String> {
+    let indent = " ".repeat(prefix.width());
+    let available = width.saturating_sub(indent.len()).max(1);
+    let mut lines = Vec::new();
+    let mut line = prefix.to_string();
+    let mut line_len = 0;
+    for (i, text_line) in text.lines().enumerate() {
+        if i > 0 {
+            lines.push(std::mem::replace(&mut line, indent.clone()));
+            line_len = 0;
+        }
+        for (gap, word) in spaced_words(text_line) {
+            if line_len > 0 && line_len + gap.width() + word.width() > available {
+                lines.push(std::mem::replace(&mut line, indent.clone()));
+                line_len = 0;
+            } else {
+                line.push_str(gap);
+                line_len += gap.width();
+            }
+            let mut rest = word;
+            while line_len + rest.width() > available {
+                let mut cut = 0;
+                let mut cut_width = 0;
+                for (index, c) in rest.char_indices() {
+                    let char_width = c.width().unwrap_or(0);
+                    if line_len + cut_width + char_width > available {
+                        break;
+                    }
+                    cut = index + c.len_utf8();
+                    cut_width += char_width;
+                }
+                // A character wider than the whole line still goes on one by itself
+                if cut == 0 && line_len == 0 {
+                    cut = rest.chars().next().map_or(rest.len(), char::len_utf8);
+                }
+                line.push_str(&rest[..cut]);
+                lines.push(std::mem::replace(&mut line, indent.clone()));
+                line_len = 0;
+                rest = &rest[cut..];
+            }
+            line.push_str(rest);
+            line_len += rest.width();
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+// A line split into words, each with the whitespace typed in front of it
+fn spaced_words(line: &str) -> Vec<(&str, &str)> {
+    let mut pieces = Vec::new();
+    let mut rest = line;
+    while !rest.is_empty() {
+        let word_start = rest.find(|c: char| !c.is_whitespace()).unwrap_or(rest.len());
+        let word_end = rest[word_start..].find(char::is_whitespace).map_or(rest.len(), |end| word_start + end);
+        pieces.push((&rest[..word_start], &rest[word_start..word_end]));
+        rest = &rest[word_end..];
+    }
+    pieces
+}
+
+// A small list popup for choosing a priority or status: ↑/↓ and Enter, or the row's number
+fn render_picker(f: &mut Frame, app: &mut App, title: &str, labels: &[String]) {
+    let theme = app.theme;
+    let popup_area = centered_rect(40, 30, f.area());
+    // Rows are shorter than the popup, so blank out what's underneath first
+    f.render_widget(Clear, popup_area);
+    
+    let block = Block::default()
+        .title(title)
+        .title_bottom("↑/↓ Enter · Esc cancels")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(theme.popup_bg));
+    let items: Vec<ListItem> = labels.iter()
+        .map(|label| ListItem::new(label.as_str()).style(Style::default().fg(theme.text)))
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(app.highlight_symbol.as_str());
+    
+    f.render_stateful_widget(list, popup_area, &mut app.picker_state);
+}
+
+// Everything else stays hidden until the passphrase is entered
+fn render_lock_screen(f: &mut Frame, app: &App) {
+    let theme = app.theme;
+    let popup_area = centered_rect(50, 25, f.area());
+    let block = Block::default()
+        .title("Aide TUI - Locked")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(theme.popup_bg));
+    let message = app.status_message.as_deref().unwrap_or("Press ENTER to unlock, Ctrl+C to quit");
+    let content = Paragraph::new(format!("Passphrase:\n\n{}\n\n{}", "*".repeat(app.lock_input.chars().count()), message))
+        .block(block)
+        .alignment(ratatui::layout::Alignment::Center)
+        .style(Style::default().fg(theme.text));
+    f.render_widget(content, popup_area);
+}
+
 // Helper function to create centered rectangles for popups
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -786,7 +1900,33 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
+fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
+    let (tab_name, count, selected) = match app.current_tab {
+        0 => ("Tasks", app.tasks.len(), app.task_list_state.selected()),
+        1 => ("Aides", app.aides.len(), app.aide_list_state.selected()),
+        _ => ("Configs", app.configs.len(), app.config_list_state.selected()),
+    };
+
+    let mut status = format!(" {} | ", tab_name);
+    match selected {
+        Some(i) if count > 0 => status.push_str(&format!("{}/{}", i + 1, count)),
+        _ => status.push_str(&format!("{} items", count)),
+    }
+    if !app.filter_query.is_empty() {
+        status.push_str(&format!(" | filter: {}", app.filter_query));
+    }
+    match &app.status_message {
+        Some(message) => status.push_str(&format!(" | {}", message)),
+        None => status.push_str(" | Tab: switch  f: filter  y: copy  q: quit"),
+    }
+
+    let status_bar = Paragraph::new(status)
+        .style(Style::default().fg(app.theme.muted));
+    f.render_widget(status_bar, area);
+}
+
 fn render_tasks(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
@@ -796,21 +1936,20 @@ fn render_tasks(f: &mut Frame, app: &mut App, area: Rect) {
         .tasks
         .iter()
         .map(|task| {
-            let status_color = match task.status.as_str() {
-                "completed" => Color::Green,
-                "in_progress" => Color::Yellow,
-                "created" => Color::Blue,
-                _ => Color::White,
-            };
+            let status_color = theme.status_color(&task.status);
             
+            let pin = if task.pinned { format!("{} ", PIN_MARKER) } else { String::new() };
             ListItem::new(vec![Line::from(vec![
                 Span::styled(
-                    format!("{} ", task.name),
-                    Style::default().fg(Color::White),
+                    format!("{}{} ", pin, task.name),
+                    Style::default().fg(theme.text),
                 ),
                 Span::styled(
-                    format!("[P{}] ", task.priority),
-                    Style::default().fg(Color::Cyan),
+                    match priority_name(task.priority) {
+                        Some(name) => format!("[P{} {}] ", task.priority, name),
+                        None => format!("[P{}] ", task.priority),
+                    },
+                    Style::default().fg(theme.accent),
                 ),
                 Span::styled(
                     format!("[{}]", task.status),
@@ -821,73 +1960,137 @@ fn render_tasks(f: &mut Frame, app: &mut App, area: Rect) {
         .collect();
 
     let tasks_list = List::new(tasks)
-        .block(Block::default().borders(Borders::ALL).title("Tasks"))
+        .block(Block::default().borders(Borders::ALL).title(app.list_title("Tasks", app.tasks.len())))
         .highlight_style(
             Style::default()
-                .bg(Color::LightGreen)
+                .bg(theme.highlight)
                 .add_modifier(Modifier::BOLD),
         )
-        .highlight_symbol(">> ");
+        .highlight_symbol(app.highlight_symbol.as_str());
 
     f.render_stateful_widget(tasks_list, chunks[0], &mut app.task_list_state);
 
     let selected_task = app.task_list_state.selected().and_then(|i| app.tasks.get(i));
     let info_text = if let Some(task) = selected_task {
+        let blockers = if task.blocked_by.is_empty() {
+            String::new()
+        } else {
+            format!("Blocked by: {}\n", task.blocked_by.join(", "))
+        };
+        let effort = match (task.estimate_minutes, task.actual_minutes) {
+            (None, None) => String::new(),
+            (estimate, actual) => format!(
+                "Time: {} spent of {} estimated\n",
+                format_minutes(actual.unwrap_or(0)),
+                estimate.map_or("no".to_string(), format_minutes),
+            ),
+        };
         format!(
-            "Task: {}\nPriority: {}\nStatus: {}\nCreated: {}\n\nControls:\n• Enter: Edit task log\n• p: Change priority\n• s: Change status\n• r: Refresh\n• q: Quit",
-            task.name, task.priority, task.status, task.created_at
+            "Task: {}\nPriority: {}\nStatus: {}\nCreated: {}\n{}{}\nControls:\n• Enter: Edit task log\n• p: Change priority\n• s: Change status\n• Space: Next status\n• n: New task\n• d: Delete\n• f: Filter\n• y: Copy\n• r: Refresh\n• q: Quit",
+            task.name, priority_label(task.priority), task.status, task.created_at, effort, blockers
         )
+    } else if app.tasks.is_empty() {
+        empty_list_text(app, "tasks", TASKS_EMPTY_TEXT)
     } else {
-        "No task selected\n\nControls:\n• ↑/↓: Navigate\n• Enter: Edit task log\n• p: Change priority\n• s: Change status\n• r: Refresh\n• q: Quit".to_string()
+        "No task selected\n\nControls:\n• ↑/↓: Navigate\n• Enter: Edit task log\n• p: Change priority\n• s: Change status\n• Space: Next status\n• n: New task\n• d: Delete\n• f: Filter\n• y: Copy\n• r: Refresh\n• q: Quit".to_string()
     };
 
+    app.task_info_scroll = clamp_scroll(app.task_info_scroll, &info_text, chunks[1]);
     let info_paragraph = Paragraph::new(info_text)
         .block(Block::default().borders(Borders::ALL).title("Task Info"))
-        .style(Style::default().fg(Color::White));
+        .wrap(Wrap { trim: false })
+        .scroll((app.task_info_scroll, 0))
+        .style(Style::default().fg(theme.text));
 
     f.render_widget(info_paragraph, chunks[1]);
 }
 
 fn render_aides(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    if app.aide_focus {
+        render_aide_focus(f, app, area);
+        return;
+    }
+    if app.entry_mode {
+        render_aide_entries(f, app, area);
+        return;
+    }
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
         .split(area);
 
+    // Aides in a folder are listed under a "▾ folder/" heading, by the rest of their name
+    let mut previous_folder = None;
     let aides: Vec<ListItem> = app
         .aides
         .iter()
         .map(|aide| {
-            ListItem::new(vec![Line::from(vec![
+            let folder = aide_folder(&aide.name);
+            let new_folder = folder.is_some() && folder != previous_folder;
+            previous_folder = folder;
+            if let Some(folder) = app.collapsed_folder_of(aide) {
+                let size = app.folder_sizes.get(&folder).copied().unwrap_or(1);
+                return ListItem::new(Line::from(vec![
+                    Span::raw("  "),
+                    Span::styled(format!("▸ {}/ ", folder), Style::default().fg(theme.text)),
+                    Span::styled(format!("({} aide{})", size, if size == 1 { "" } else { "s" }), Style::default().fg(theme.muted)),
+                ]));
+            }
+
+            let pin = if aide.pinned { format!("{} ", PIN_MARKER) } else { String::new() };
+            let key = app.favorite_key(&aide.name).map(|key| format!("{} ", key)).unwrap_or_else(|| "  ".to_string());
+            let name = match aide.name.split_once('/') {
+                Some((_, rest)) => format!("  {}", rest),
+                None => aide.name.clone(),
+            };
+            let mut lines = Vec::new();
+            if let (true, Some(folder)) = (new_folder, folder) {
+                lines.push(Line::from(Span::styled(format!("  ▾ {}/", folder), Style::default().fg(theme.text))));
+            }
+            lines.push(Line::from(vec![
+                Span::styled(key, Style::default().fg(theme.accent)),
                 Span::styled(
-                    format!("{} ", aide.name),
-                    Style::default().fg(Color::White),
+                    format!("{}{} ", pin, name),
+                    Style::default().fg(theme.text),
                 ),
                 Span::styled(
                     "[file]",
-                    Style::default().fg(Color::Green),
+                    Style::default().fg(theme.tag),
                 ),
-            ])])
+            ]));
+            ListItem::new(lines)
         })
         .collect();
 
     let aides_list = List::new(aides)
-        .block(Block::default().borders(Borders::ALL).title("Aides"))
+        .block(Block::default().borders(Borders::ALL).title(app.list_title("Aides", app.aides.len())))
         .highlight_style(
             Style::default()
-                .bg(Color::LightGreen)
+                .bg(theme.highlight)
                 .add_modifier(Modifier::BOLD),
         )
-        .highlight_symbol(">> ");
+        .highlight_symbol(app.highlight_symbol.as_str());
 
     f.render_stateful_widget(aides_list, chunks[0], &mut app.aide_list_state);
 
     let selected_aide = app.aide_list_state.selected().and_then(|i| app.aides.get(i));
     
-    if let Some(aide) = selected_aide {
+    if let Some(folder) = app.selected_collapsed_folder() {
+        let size = app.folder_sizes.get(&folder).copied().unwrap_or(1);
+        let content = format!(
+            "{} aide{} in {}/\n\nControls:\n• Enter/Space: Expand folder\n• n: New aide\n• f: Filter\n• r: Refresh\n• q: Quit",
+            size, if size == 1 { "" } else { "s" }, folder
+        );
+        let paragraph = Paragraph::new(content)
+            .block(Block::default().borders(Borders::ALL).title(format!("Folder - {}/", folder)))
+            .wrap(Wrap { trim: false })
+            .style(Style::default().fg(theme.text));
+        f.render_widget(paragraph, chunks[1]);
+    } else if let Some(aide) = selected_aide {
         let title = format!("File Aide - {}", aide.name);
         let content = if aide.command_output.is_empty() {
-            format!("No entries available\n\nTo add content:\n• aide add {} \"your content\"\n• aide add {} -p /path/to/file\n\nControls:\n• Enter: Edit file\n• e: Quick edit\n• r: Refresh\n• q: Quit", aide.name, aide.name)
+            format!("No entries available\n\nTo add content:\n• aide add {} \"your content\"\n• aide add {} -p /path/to/file\n\nControls:\n• Enter: Edit file\n• e: Quick edit\n• →: Browse entries\n• z: Focus view\n• n: New aide\n• d: Delete\n• f: Filter\n• y: Copy\n• r: Refresh\n• q: Quit", aide.name, aide.name)
         } else {
             // Split concatenated entries and show preview
             let inputs: Vec<&str> = aide.input_text.split("|||").collect();
@@ -897,44 +2100,121 @@ fn render_aides(f: &mut Frame, app: &mut App, area: Rect) {
             content.push_str("File Entries:\n");
             content.push_str("=============\n\n");
             
+            // Wrapped here rather than by the Paragraph so continuation lines get a hanging indent
+            let width = chunks[1].width.saturating_sub(2) as usize;
             for (i, (input, output)) in inputs.iter().zip(outputs.iter()).enumerate() {
                 if !input.is_empty() {
-                    content.push_str(&format!("{}. {}\n", i + 1, input));
+                    for line in wrap_hanging(&format!("{}. ", i + 1), input, width) {
+                        content.push_str(&line);
+                        content.push('\n');
+                    }
                     if !output.is_empty() {
-                        // Show preview of content (first 100 chars)
-                        let preview = if output.len() > 100 {
-                            format!("{}...", &output[..100])
-                        } else {
-                            output.to_string()
-                        };
-                        content.push_str(&format!("   Preview: {}\n", preview));
+                        // Show preview of content, cut on a char boundary
+                        let preview = truncate_chars(output, app.preview_length);
+                        for line in wrap_hanging("   Preview: ", &preview, width) {
+                            content.push_str(&line);
+                            content.push('\n');
+                        }
                     }
-                    content.push_str("\n");
+                    content.push('\n');
                 }
             }
             
-            content.push_str("Controls:\n• Enter: Edit file\n• e: Quick edit\n• r: Refresh\n• q: Quit");
+            content.push_str("Controls:\n• Enter: Edit file\n• e: Quick edit\n• →: Browse entries\n• z: Focus view\n• n: New aide\n• d: Delete\n• f: Filter\n• y: Copy\n• r: Refresh\n• q: Quit");
             content
         };
 
+        app.aide_preview_scroll = clamp_scroll(app.aide_preview_scroll, &content, chunks[1]);
         let content_paragraph = Paragraph::new(content)
             .block(Block::default().borders(Borders::ALL).title(title))
             .wrap(Wrap { trim: false })
-            .style(Style::default().fg(Color::White));
+            .scroll((app.aide_preview_scroll, 0))
+            .style(Style::default().fg(theme.text));
 
         f.render_widget(content_paragraph, chunks[1]);
     } else {
-        let info_text = "No aide selected\n\nControls:\n• ↑/↓: Navigate\n• Enter: Edit aide file\n• e: Quick edit\n• r: Refresh\n• q: Quit";
+        let info_text = if app.aides.is_empty() {
+            empty_list_text(app, "aides", AIDES_EMPTY_TEXT)
+        } else {
+            "No aide selected\n\nControls:\n• ↑/↓: Navigate\n• Enter: Edit aide file\n• e: Quick edit\n• n: New aide\n• d: Delete\n• f: Filter\n• y: Copy\n• r: Refresh\n• q: Quit".to_string()
+        };
         
         let info_paragraph = Paragraph::new(info_text)
             .block(Block::default().borders(Borders::ALL).title("Aide Content"))
-            .style(Style::default().fg(Color::White));
+            .wrap(Wrap { trim: false })
+            .style(Style::default().fg(theme.text));
 
         f.render_widget(info_paragraph, chunks[1]);
     }
 }
 
+// Entry list of the selected aide, with the full text of the selected entry
+fn render_aide_entries(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(area);
+
+    let name = app.selected_item_name().unwrap_or_default();
+    let entries: Vec<ListItem> = app
+        .aide_entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let first_line = entry.input_text.lines().next().unwrap_or_default();
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{}. ", i + 1), Style::default().fg(theme.accent)),
+                Span::styled(truncate_chars(first_line, app.preview_length), Style::default().fg(theme.text)),
+            ]))
+        })
+        .collect();
+
+    let entries_list = List::new(entries)
+        .block(Block::default().borders(Borders::ALL).title(format!("Entries - {}", name)))
+        .highlight_style(
+            Style::default()
+                .bg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(app.highlight_symbol.as_str());
+
+    f.render_stateful_widget(entries_list, chunks[0], &mut app.entry_list_state);
+
+    let selected_entry = app.entry_list_state.selected().and_then(|i| app.aide_entries.get(i));
+    let info_text = match selected_entry {
+        Some(entry) => format!(
+            "Added: {}\n\n{}\n\nControls:\n• Enter: Edit this entry\n• ↑/↓: Navigate entries\n• ←/ESC: Back to aides",
+            entry.created_at.as_deref().unwrap_or("unknown"), entry.file_text()
+        ),
+        None => "This aide has no entries yet.\n\nControls:\n• ←/ESC: Back to aides".to_string(),
+    };
+
+    let info_paragraph = Paragraph::new(info_text)
+        .block(Block::default().borders(Borders::ALL).title("Entry"))
+        .wrap(Wrap { trim: false })
+        .style(Style::default().fg(theme.text));
+
+    f.render_widget(info_paragraph, chunks[1]);
+}
+
+// Full-width, scrollable view of the selected aide's file
+fn render_aide_focus(f: &mut Frame, app: &App, area: Rect) {
+    let theme = app.theme;
+    let name = app.selected_item_name().unwrap_or_default();
+    let content = app.selected_item_content().unwrap_or_else(|| "(no file content)".to_string());
+
+    let paragraph = Paragraph::new(content)
+        .block(Block::default().borders(Borders::ALL).title(format!("{} - ↑/↓: Scroll | z/ESC: Back", name)))
+        .wrap(Wrap { trim: false })
+        .scroll((app.aide_focus_scroll, 0))
+        .style(Style::default().fg(theme.text));
+
+    f.render_widget(paragraph, area);
+}
+
 fn render_configs(f: &mut Frame, app: &mut App, area: Rect) {
+    let theme = app.theme;
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
@@ -944,43 +2224,149 @@ fn render_configs(f: &mut Frame, app: &mut App, area: Rect) {
         .configs
         .iter()
         .map(|config| {
-            ListItem::new(vec![Line::from(vec![
-                Span::styled(
-                    format!("{} ", config.key_name),
-                    Style::default().fg(Color::White),
-                ),
-                Span::styled(
-                    format!("={}", config.value),
-                    Style::default().fg(Color::Cyan),
-                ),
-            ])])
+            let key = Span::styled(format!("{} ", config.key_name), Style::default().fg(theme.text));
+            let value_style = Style::default().fg(theme.accent);
+            if !config.value.contains('\n') {
+                return ListItem::new(Line::from(vec![key, Span::styled(format!("={}", config.value), value_style)]));
+            }
+            // Multi-line values go below the key, indented and cut short so one prompt can't
+            // push the rest of the list off screen
+            let mut lines = vec![Line::from(vec![key, Span::styled("=", value_style)])];
+            let value_lines: Vec<&str> = config.value.lines().collect();
+            for line in value_lines.iter().take(CONFIG_LIST_VALUE_LINES) {
+                lines.push(Line::from(Span::styled(format!("    {}", line), value_style)));
+            }
+            if value_lines.len() > CONFIG_LIST_VALUE_LINES {
+                lines.push(Line::from(Span::styled(
+                    format!("    … {} more lines", value_lines.len() - CONFIG_LIST_VALUE_LINES),
+                    Style::default().fg(theme.muted),
+                )));
+            }
+            ListItem::new(lines)
         })
         .collect();
 
     let configs_list = List::new(configs)
-        .block(Block::default().borders(Borders::ALL).title("Configs"))
+        .block(Block::default().borders(Borders::ALL).title(app.list_title("Configs", app.configs.len())))
         .highlight_style(
             Style::default()
-                .bg(Color::LightGreen)
+                .bg(theme.highlight)
                 .add_modifier(Modifier::BOLD),
         )
-        .highlight_symbol(">> ");
+        .highlight_symbol(app.highlight_symbol.as_str());
 
     f.render_stateful_widget(configs_list, chunks[0], &mut app.config_list_state);
 
     let selected_config = app.config_list_state.selected().and_then(|i| app.configs.get(i));
     let info_text = if let Some(config) = selected_config {
+        let value = if config.value.contains('\n') {
+            config.value.lines().map(|line| format!("\n  {}", line)).collect()
+        } else {
+            format!(" {}", config.value)
+        };
         format!(
-            "Config: {}\nValue: {}\n\nControls:\n• Enter: Edit config value\n• r: Refresh\n• q: Quit",
-            config.key_name, config.value
+            "Config: {}\nValue:{}\n\nControls:\n• Enter: Edit config value\n• d: Delete\n• f: Filter\n• y: Copy\n• r: Refresh\n• q: Quit",
+            config.key_name, value
         )
+    } else if app.configs.is_empty() {
+        empty_list_text(app, "configs", CONFIGS_EMPTY_TEXT)
     } else {
-        "No config selected\n\nControls:\n• ↑/↓: Navigate\n• Enter: Edit config value\n• r: Refresh\n• q: Quit".to_string()
+        "No config selected\n\nControls:\n• ↑/↓: Navigate\n• Enter: Edit config value\n• d: Delete\n• f: Filter\n• y: Copy\n• r: Refresh\n• q: Quit".to_string()
     };
 
     let info_paragraph = Paragraph::new(info_text)
         .block(Block::default().borders(Borders::ALL).title("Config Info"))
-        .style(Style::default().fg(Color::White));
+        .wrap(Wrap { trim: false })
+        .style(Style::default().fg(theme.text));
 
     f.render_widget(info_paragraph, chunks[1]);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_chars_cuts_on_a_char_boundary() {
+        // 'é' is two bytes, spanning bytes 99-100, where a byte slice at 100 would panic
+        let text = format!("{}é and more", "a".repeat(99));
+        let preview = truncate_chars(&text, DEFAULT_PREVIEW_LENGTH);
+        assert_eq!(preview, format!("{}é...", "a".repeat(99)));
+    }
+
+    #[test]
+    fn an_empty_created_filter_isnt_shown_as_a_first_run() {
+        let mut app = test_app();
+        app.created_filter = Some(CreatedWithin::Today);
+        assert_eq!(empty_list_text(&app, "tasks", TASKS_EMPTY_TEXT), "No tasks created today (t to change)");
+        app.created_filter = None;
+        assert_eq!(empty_list_text(&app, "tasks", TASKS_EMPTY_TEXT), TASKS_EMPTY_TEXT);
+    }
+
+    #[test]
+    fn truncate_chars_keeps_short_text() {
+        assert_eq!(truncate_chars("héllo", DEFAULT_PREVIEW_LENGTH), "héllo");
+    }
+
+    #[test]
+    fn a_long_single_line_entry_wraps_under_its_text() {
+        let entry = "deploy the api service to staging first and then promote it to production";
+        let lines = wrap_hanging("1. ", entry, 24);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|line| line.width() <= 24));
+        assert!(lines[1..].iter().all(|line| line.starts_with("   ") && !line[3..].starts_with(' ')));
+        let words: Vec<&str> = lines.iter().flat_map(|line| line.split_whitespace()).collect();
+        assert_eq!(words[1..].join(" "), entry);
+    }
+
+    #[test]
+    fn an_indented_multi_line_entry_keeps_its_spacing() {
+        let lines = wrap_hanging("1. ", "steps:\n  - build\n  - deploy:  now", 40);
+        assert_eq!(lines, ["1. steps:", "     - build", "     - deploy:  now"]);
+    }
+
+    #[test]
+    fn wide_characters_wrap_by_display_width() {
+        let lines = wrap_hanging("", "日本語のテキストを表示する", 10);
+        assert_eq!(lines, ["日本語のテ", "キストを表", "示する"]);
+    }
+
+    fn test_app() -> App {
+        App::new(Database::open(rusqlite::Connection::open_in_memory().unwrap()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn saving_a_config_from_the_editor_drops_the_trailing_newline() {
+        let mut app = test_app();
+        app.db.set_config("preview_length", "100").unwrap();
+        app.save_editor_content(EditorCallback::SaveConfig("preview_length".to_string()), "50\n").unwrap();
+        assert_eq!(app.db.get_config_value("preview_length").unwrap().as_deref(), Some("50"));
+        assert_eq!(app.preview_length, 50);
+    }
+
+    #[test]
+    fn an_invalid_config_from_the_editor_is_not_saved() {
+        let mut app = test_app();
+        app.db.set_config("llm_base_url", "http://localhost:11434").unwrap();
+        app.open_text_editor("llm_base_url".to_string(), "localhost\n".to_string(), EditorCallback::SaveConfig("llm_base_url".to_string()));
+        app.text_editor.as_mut().unwrap().is_dirty = true;
+        app.close_text_editor(true).unwrap();
+        assert!(app.text_editor.is_some());
+        assert_eq!(app.db.get_config_value("llm_base_url").unwrap().as_deref(), Some("http://localhost:11434"));
+        assert!(app.status_message.as_deref().is_some_and(|message| message.starts_with("Config not saved")));
+    }
+
+    #[test]
+    fn aide_keys_skip_a_collapsed_folder_row() {
+        let mut app = test_app();
+        app.db.insert_aide("grp/first").unwrap();
+        app.db.insert_aide("grp/second").unwrap();
+        app.current_tab = 1;
+        app.refresh_data().unwrap();
+        app.aide_list_state.select(app.aides.iter().position(|aide| aide.name == "grp/first"));
+        app.toggle_aide_folder().unwrap();
+        assert!(app.on_collapsed_folder_row());
+        app.toggle_aide_folder().unwrap();
+        assert!(!app.on_collapsed_folder_row());
+    }
+}
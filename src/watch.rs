@@ -0,0 +1,69 @@
+use anyhow::{bail, Result};
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{self, ClearType},
+};
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+// Clear the screen and run `render` every `interval` until q, Esc or Ctrl+C is pressed.
+// The cursor is hidden meanwhile and shown again on the way out.
+pub fn watch(title: &str, interval: Duration, mut render: impl FnMut() -> Result<()>) -> Result<()> {
+    if !io::stdout().is_terminal() || !io::stdin().is_terminal() {
+        bail!("--watch needs a terminal");
+    }
+
+    let mut out = io::stdout();
+    execute!(out, cursor::Hide)?;
+    let result = run(&mut out, title, interval, &mut render);
+    let _ = terminal::disable_raw_mode();
+    execute!(out, cursor::Show)?;
+    result
+}
+
+fn run(out: &mut io::Stdout, title: &str, interval: Duration, render: &mut impl FnMut() -> Result<()>) -> Result<()> {
+    loop {
+        execute!(out, terminal::Clear(ClearType::All), terminal::Clear(ClearType::Purge), cursor::MoveTo(0, 0))?;
+        println!(
+            "Every {}s: {}  ({})  q or Ctrl+C to stop\n",
+            interval.as_secs(),
+            title,
+            chrono::Utc::now().format("%Y-%m-%d %H:%M:%S")
+        );
+        render()?;
+        out.flush()?;
+
+        // Raw mode only while waiting, so Ctrl+C arrives as a key rather than killing
+        // the process with the cursor still hidden
+        terminal::enable_raw_mode()?;
+        let stop = wait_for_stop(interval);
+        terminal::disable_raw_mode()?;
+        if stop? {
+            return Ok(());
+        }
+    }
+}
+
+// Whether a stop key was pressed before `interval` ran out
+fn wait_for_stop(interval: Duration) -> Result<bool> {
+    let deadline = Instant::now() + interval;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() || !event::poll(remaining)? {
+            return Ok(false);
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(true),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(true),
+            _ => {}
+        }
+    }
+}